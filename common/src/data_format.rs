@@ -50,9 +50,28 @@ pub enum DataFormat {
     PackedStruct32,
     PackedStruct64,
     PackedStruct128,
-    // Rezerved
-    // Path,
-    // DateTime -> maybe from chronno
+    Char,
+    Duration,
+    // date & time (chrono / time crates)
+    UtcDateTime,
+    NaiveDateTime,
+    OffsetDateTime,
+    Date,
+    Uuid,
+    Path,
+    // networks (ipnet crate)
+    Ipv4Net,
+    Ipv6Net,
+    IpNet,
+    // bit-packed booleans
+    Bits,
+    // variable length integers (LEB128, zigzag for signed values)
+    VarUInt,
+    VarInt,
+    // delta encoded integers (zigzag + LEB128)
+    Delta,
+    // index (LEB128) in the string table of a message
+    StringRef,
 }
 impl DataFormat {
     pub const fn is_object_container(&self) -> bool {
@@ -123,6 +142,23 @@ impl DataFormat {
                 | DataFormat::Flags128
         )
     }
+    fn from_non_zero(value: &str) -> Option<DataFormat> {
+        let name = value
+            .strip_prefix("std :: num :: ")
+            .or_else(|| value.strip_prefix("num :: "))
+            .unwrap_or(value);
+        let inner = if let Some(inner) = name.strip_prefix("NonZero<") {
+            inner.strip_suffix(">")?.to_string()
+        } else {
+            name.strip_prefix("NonZero")?.to_lowercase()
+        };
+        match inner.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "u128" | "i8" | "i16" | "i32" | "i64" | "i128" => {
+                Some(DataFormat::from(inner.as_str()))
+            }
+            _ => None,
+        }
+    }
     pub const fn requires_padding(&self) -> bool {
         (self.alignament() > 1) && (self.is_object_container())
     }
@@ -172,6 +208,22 @@ impl DataFormat {
             DataFormat::Variant32 => 4,
             DataFormat::Variant64 => 8,
             DataFormat::Variant128 => 16,
+            DataFormat::Char => 4,
+            DataFormat::Duration => 4,
+            DataFormat::UtcDateTime => 4,
+            DataFormat::NaiveDateTime => 4,
+            DataFormat::OffsetDateTime => 4,
+            DataFormat::Date => 4,
+            DataFormat::Uuid => 1,
+            DataFormat::Path => 1,
+            DataFormat::Ipv4Net => 1,
+            DataFormat::Ipv6Net => 1,
+            DataFormat::IpNet => 1,
+            DataFormat::Bits => 1,
+            DataFormat::VarUInt => 1,
+            DataFormat::VarInt => 1,
+            DataFormat::Delta => 1,
+            DataFormat::StringRef => 1,
             DataFormat::Unknwon => 1,
         }
     }
@@ -223,22 +275,116 @@ impl Display for DataFormat {
             DataFormat::Variant32 => write!(f, "Variant32"),
             DataFormat::Variant64 => write!(f, "Variant64"),
             DataFormat::Variant128 => write!(f, "Variant128"),
+            DataFormat::Char => write!(f, "Char"),
+            DataFormat::Duration => write!(f, "Duration"),
+            DataFormat::UtcDateTime => write!(f, "UtcDateTime"),
+            DataFormat::NaiveDateTime => write!(f, "NaiveDateTime"),
+            DataFormat::OffsetDateTime => write!(f, "OffsetDateTime"),
+            DataFormat::Date => write!(f, "Date"),
+            DataFormat::Uuid => write!(f, "Uuid"),
+            DataFormat::Path => write!(f, "Path"),
+            DataFormat::Ipv4Net => write!(f, "Ipv4Net"),
+            DataFormat::Ipv6Net => write!(f, "Ipv6Net"),
+            DataFormat::IpNet => write!(f, "IpNet"),
+            DataFormat::Bits => write!(f, "Bits"),
+            DataFormat::VarUInt => write!(f, "VarUInt"),
+            DataFormat::VarInt => write!(f, "VarInt"),
+            DataFormat::Delta => write!(f, "Delta"),
+            DataFormat::StringRef => write!(f, "StringRef"),
             DataFormat::Unknwon => write!(f, "Unknwon"),
         }
     }
 }
 
+impl TryFrom<u8> for DataFormat {
+    type Error = u8;
+
+    /// Converts the raw value stored in the low byte of a field hash back into a `DataFormat`.
+    /// The slice/vector bit (`0x80`) must be removed before calling this method.
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            x if x == DataFormat::U8 as u8 => Ok(DataFormat::U8),
+            x if x == DataFormat::U16 as u8 => Ok(DataFormat::U16),
+            x if x == DataFormat::U32 as u8 => Ok(DataFormat::U32),
+            x if x == DataFormat::U64 as u8 => Ok(DataFormat::U64),
+            x if x == DataFormat::U128 as u8 => Ok(DataFormat::U128),
+            x if x == DataFormat::I8 as u8 => Ok(DataFormat::I8),
+            x if x == DataFormat::I16 as u8 => Ok(DataFormat::I16),
+            x if x == DataFormat::I32 as u8 => Ok(DataFormat::I32),
+            x if x == DataFormat::I64 as u8 => Ok(DataFormat::I64),
+            x if x == DataFormat::I128 as u8 => Ok(DataFormat::I128),
+            x if x == DataFormat::F32 as u8 => Ok(DataFormat::F32),
+            x if x == DataFormat::F64 as u8 => Ok(DataFormat::F64),
+            x if x == DataFormat::Bool as u8 => Ok(DataFormat::Bool),
+            x if x == DataFormat::String as u8 => Ok(DataFormat::String),
+            x if x == DataFormat::EnumI8 as u8 => Ok(DataFormat::EnumI8),
+            x if x == DataFormat::EnumI16 as u8 => Ok(DataFormat::EnumI16),
+            x if x == DataFormat::EnumI32 as u8 => Ok(DataFormat::EnumI32),
+            x if x == DataFormat::EnumI64 as u8 => Ok(DataFormat::EnumI64),
+            x if x == DataFormat::EnumU8 as u8 => Ok(DataFormat::EnumU8),
+            x if x == DataFormat::EnumU16 as u8 => Ok(DataFormat::EnumU16),
+            x if x == DataFormat::EnumU32 as u8 => Ok(DataFormat::EnumU32),
+            x if x == DataFormat::EnumU64 as u8 => Ok(DataFormat::EnumU64),
+            x if x == DataFormat::IPv4 as u8 => Ok(DataFormat::IPv4),
+            x if x == DataFormat::IPv6 as u8 => Ok(DataFormat::IPv6),
+            x if x == DataFormat::IP as u8 => Ok(DataFormat::IP),
+            x if x == DataFormat::FixArray as u8 => Ok(DataFormat::FixArray),
+            x if x == DataFormat::Flags8 as u8 => Ok(DataFormat::Flags8),
+            x if x == DataFormat::Flags16 as u8 => Ok(DataFormat::Flags16),
+            x if x == DataFormat::Flags32 as u8 => Ok(DataFormat::Flags32),
+            x if x == DataFormat::Flags64 as u8 => Ok(DataFormat::Flags64),
+            x if x == DataFormat::Flags128 as u8 => Ok(DataFormat::Flags128),
+            x if x == DataFormat::Struct4 as u8 => Ok(DataFormat::Struct4),
+            x if x == DataFormat::Struct8 as u8 => Ok(DataFormat::Struct8),
+            x if x == DataFormat::Struct16 as u8 => Ok(DataFormat::Struct16),
+            x if x == DataFormat::Variant8 as u8 => Ok(DataFormat::Variant8),
+            x if x == DataFormat::Variant16 as u8 => Ok(DataFormat::Variant16),
+            x if x == DataFormat::Variant32 as u8 => Ok(DataFormat::Variant32),
+            x if x == DataFormat::Variant64 as u8 => Ok(DataFormat::Variant64),
+            x if x == DataFormat::Variant128 as u8 => Ok(DataFormat::Variant128),
+            x if x == DataFormat::PackedStruct8 as u8 => Ok(DataFormat::PackedStruct8),
+            x if x == DataFormat::PackedStruct16 as u8 => Ok(DataFormat::PackedStruct16),
+            x if x == DataFormat::PackedStruct32 as u8 => Ok(DataFormat::PackedStruct32),
+            x if x == DataFormat::PackedStruct64 as u8 => Ok(DataFormat::PackedStruct64),
+            x if x == DataFormat::PackedStruct128 as u8 => Ok(DataFormat::PackedStruct128),
+            x if x == DataFormat::Char as u8 => Ok(DataFormat::Char),
+            x if x == DataFormat::Duration as u8 => Ok(DataFormat::Duration),
+            x if x == DataFormat::UtcDateTime as u8 => Ok(DataFormat::UtcDateTime),
+            x if x == DataFormat::NaiveDateTime as u8 => Ok(DataFormat::NaiveDateTime),
+            x if x == DataFormat::OffsetDateTime as u8 => Ok(DataFormat::OffsetDateTime),
+            x if x == DataFormat::Date as u8 => Ok(DataFormat::Date),
+            x if x == DataFormat::Uuid as u8 => Ok(DataFormat::Uuid),
+            x if x == DataFormat::Path as u8 => Ok(DataFormat::Path),
+            x if x == DataFormat::Ipv4Net as u8 => Ok(DataFormat::Ipv4Net),
+            x if x == DataFormat::Ipv6Net as u8 => Ok(DataFormat::Ipv6Net),
+            x if x == DataFormat::IpNet as u8 => Ok(DataFormat::IpNet),
+            x if x == DataFormat::Bits as u8 => Ok(DataFormat::Bits),
+            x if x == DataFormat::VarUInt as u8 => Ok(DataFormat::VarUInt),
+            x if x == DataFormat::VarInt as u8 => Ok(DataFormat::VarInt),
+            x if x == DataFormat::Delta as u8 => Ok(DataFormat::Delta),
+            x if x == DataFormat::StringRef as u8 => Ok(DataFormat::StringRef),
+            _ => Err(value),
+        }
+    }
+}
+
 impl From<&str> for DataFormat {
     fn from(value: &str) -> Self {
         //println!("Value: {}", value);
-        // check to see if value is in th form of [u8;<number>]
-        if let Some(buf) = value.strip_prefix("[u8;") {
-            if let Some(value) = buf.strip_suffix("]") {
-                if value.trim().parse::<usize>().is_ok() {
-                    return DataFormat::FixArray;
-                }
+        // check to see if value is in th form of [u8;<number>] (or an array of another byte type such as NonZeroU8)
+        if let Some((element, count)) = value
+            .strip_prefix('[')
+            .and_then(|v| v.strip_suffix(']'))
+            .and_then(|v| v.rsplit_once(';'))
+        {
+            if !count.trim().is_empty() && !count.contains(']') && DataFormat::from(element.trim()) == DataFormat::U8 {
+                return DataFormat::FixArray;
             }
         };
+        // non-zero integers (NonZeroU32, NonZero<u32>, ...) use the same format as the integer they wrap
+        if let Some(data_format) = DataFormat::from_non_zero(value) {
+            return data_format;
+        }
         match value {
             "u8" => DataFormat::U8,
             "u16" => DataFormat::U16,
@@ -253,7 +399,39 @@ impl From<&str> for DataFormat {
             "f32" => DataFormat::F32,
             "f64" => DataFormat::F64,
             "bool" => DataFormat::Bool,
+            "char" => DataFormat::Char,
+            // usize / isize are always stored on 64 bits
+            "usize" => DataFormat::U64,
+            "isize" => DataFormat::I64,
+            // duration
+            "Duration" | "std :: time :: Duration" => DataFormat::Duration,
+            // date & time (chrono)
+            #[cfg(feature = "chrono")]
+            "DateTime<Utc>" | "chrono :: DateTime<Utc>" | "DateTime<chrono :: Utc>" | "chrono :: DateTime<chrono :: Utc>" => DataFormat::UtcDateTime,
+            #[cfg(feature = "chrono")]
+            "DateTime<FixedOffset>" | "chrono :: DateTime<FixedOffset>" | "DateTime<chrono :: FixedOffset>" | "chrono :: DateTime<chrono :: FixedOffset>" => DataFormat::OffsetDateTime,
+            #[cfg(feature = "chrono")]
+            "NaiveDateTime" | "chrono :: NaiveDateTime" => DataFormat::NaiveDateTime,
+            #[cfg(feature = "chrono")]
+            "NaiveDate" | "chrono :: NaiveDate" => DataFormat::Date,
+            // date & time (time)
+            #[cfg(feature = "time")]
+            "OffsetDateTime" | "time :: OffsetDateTime" => DataFormat::OffsetDateTime,
+            #[cfg(feature = "time")]
+            "PrimitiveDateTime" | "time :: PrimitiveDateTime" => DataFormat::NaiveDateTime,
+            #[cfg(feature = "time")]
+            "Date" | "time :: Date" => DataFormat::Date,
+            // paths
+            "&Path" | "&path :: Path" | "&std :: path :: Path" => DataFormat::Path,
+            "PathBuf" | "path :: PathBuf" | "std :: path :: PathBuf" => DataFormat::Path,
+            "&OsStr" | "&ffi :: OsStr" | "&std :: ffi :: OsStr" => DataFormat::Path,
+            "OsString" | "ffi :: OsString" | "std :: ffi :: OsString" => DataFormat::Path,
+            // uuid
+            #[cfg(feature = "uuid")]
+            "Uuid" | "uuid :: Uuid" => DataFormat::Uuid,
             "&str" => DataFormat::String,
+            #[cfg(feature = "bytes")]
+            "SharedStr" | "flat_message :: SharedStr" => DataFormat::String,
             "String" => DataFormat::String,
             "enum_i8" => DataFormat::EnumI8,
             "enum_i16" => DataFormat::EnumI16,
@@ -267,6 +445,17 @@ impl From<&str> for DataFormat {
             "std :: net :: Ipv4Addr" | "net :: Ipv4Addr" | "Ipv4Addr" => DataFormat::IPv4,
             "std :: net :: Ipv6Addr" | "net :: Ipv6Addr" | "Ipv6Addr" => DataFormat::IPv6,
             "std :: net :: IpAddr" | "net :: IpAddr" | "IpAddr" => DataFormat::IP,
+            // networks (ipnet)
+            #[cfg(feature = "ipnet")]
+            "Ipv4Net" | "ipnet :: Ipv4Net" => DataFormat::Ipv4Net,
+            #[cfg(feature = "ipnet")]
+            "Ipv6Net" | "ipnet :: Ipv6Net" => DataFormat::Ipv6Net,
+            #[cfg(feature = "ipnet")]
+            "IpNet" | "ipnet :: IpNet" => DataFormat::IpNet,
+            // bit-packed booleans
+            // (only the full path is recognized, since other crates use the same names - e.g. `bitvec`)
+            "flat_message :: BitVec" | ":: flat_message :: BitVec" => DataFormat::Bits,
+            "flat_message :: BitSlice" | "flat_message :: BitSlice<>" | ":: flat_message :: BitSlice" | ":: flat_message :: BitSlice<>" => DataFormat::Bits,
             // flags
             "flags_u8" => DataFormat::Flags8,
             "flags_u16" => DataFormat::Flags16,
@@ -33,7 +33,7 @@ const CRC32_TABLE: [u32; 256] = [
     0xb3667a2e, 0xc4614ab8, 0x5d681b02, 0x2a6f2b94, 0xb40bbe37, 0xc30c8ea1, 0x5a05df1b, 0x2d02ef8d,
 ];
 
-static LOWER_CASE_TABLE: [u8; 256] = [
+const LOWER_CASE_TABLE: [u8; 256] = [
     0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
     26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49,
     50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64, 97, 98, 99, 100, 101, 102, 103,
@@ -65,7 +65,7 @@ pub fn crc32(buffer: &[u8]) -> u32 {
     crc32fast::hash(buffer)
 }
 
-pub fn fnv_32(text: &str) -> u32 {
+pub const fn fnv_32(text: &str) -> u32 {
     let buf = text.as_bytes();
     // use FNV algorithm ==> https://en.wikipedia.org/wiki/Fowler%E2%80%93Noll%E2%80%93Vo_hash_function
     if buf.is_empty() {
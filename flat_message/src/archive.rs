@@ -0,0 +1,477 @@
+use crate::{crc32, Name, StructureInformation, Timestamp, UniqueID};
+use memmap2::Mmap;
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Read, Seek, SeekFrom, Write};
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+
+// Archive layout (all values are little endian):
+//
+// +--------+----------+-----+----------+-------+--------+
+// | Header | Record 1 | ... | Record N | Index | Footer |
+// +--------+----------+-----+----------+-------+--------+
+//
+// Header (16 bytes) : magic ("FLMA"), version (1 byte), 11 reserved bytes
+// Record            : message size (u64), CRC32 of the message (u32), record marker ("FLMR"),
+//                     the message, padding up to a multiple of 16 bytes
+// Index             : one 40 bytes entry per record (message offset, message size, timestamp,
+//                     unique id, name hash, reserved)
+// Footer (24 bytes) : index offset (u64), number of entries (u64), CRC32 of the index (u32), magic ("FLMI")
+//
+// Every message starts at an offset that is a multiple of 16 (the same alignment as a `Storage`).
+// Records are written before the index, and the index is only written when the writer is finished.
+// If the footer is missing or invalid (e.g. the writer was not finished), the index is rebuilt by
+// scanning the records (the scan stops at the first incomplete or corrupted record).
+
+const ARCHIVE_MAGIC: [u8; 4] = *b"FLMA";
+const RECORD_MAGIC: [u8; 4] = *b"FLMR";
+const INDEX_MAGIC: [u8; 4] = *b"FLMI";
+const ARCHIVE_VERSION: u8 = 1;
+const HEADER_SIZE: u64 = 16;
+const RECORD_HEADER_SIZE: u64 = 16;
+const ENTRY_SIZE: u64 = 40;
+const FOOTER_SIZE: u64 = 24;
+const ALIGNMENT: u64 = 16;
+
+#[inline(always)]
+fn align(value: u64) -> u64 {
+    (value + ALIGNMENT - 1) & !(ALIGNMENT - 1)
+}
+
+#[inline(always)]
+fn read_u32(buf: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(buf[pos..pos + 4].try_into().unwrap())
+}
+
+#[inline(always)]
+fn read_u64(buf: &[u8], pos: usize) -> u64 {
+    u64::from_le_bytes(buf[pos..pos + 8].try_into().unwrap())
+}
+
+fn invalid_data(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn header() -> [u8; HEADER_SIZE as usize] {
+    let mut header = [0u8; HEADER_SIZE as usize];
+    header[..4].copy_from_slice(&ARCHIVE_MAGIC);
+    header[4] = ARCHIVE_VERSION;
+    header
+}
+
+fn check_header(buf: &[u8]) -> io::Result<()> {
+    if buf.len() < HEADER_SIZE as usize || buf[..4] != ARCHIVE_MAGIC {
+        return Err(invalid_data("not a FlatMessage archive (invalid magic)"));
+    }
+    if buf[4] != ARCHIVE_VERSION {
+        return Err(invalid_data("unsupported FlatMessage archive version"));
+    }
+    Ok(())
+}
+
+/// The index entry of a message stored in an archive.
+///
+/// The metadata (name hash, timestamp and unique id) is the one found in the trailer of the message
+/// (it is available only if the message was serialized with the corresponding options).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    offset: u64,
+    size: u64,
+    timestamp: u64,
+    unique_id: u64,
+    name: u32,
+}
+
+impl ArchiveEntry {
+    fn from_message(offset: u64, message: &[u8]) -> Result<Self, crate::Error> {
+        let info = StructureInformation::try_from(message)?;
+        Ok(Self {
+            offset,
+            size: message.len() as u64,
+            timestamp: info.timestamp().unwrap_or(0),
+            unique_id: info.unique_id().unwrap_or(0),
+            name: info.name().map(|n| n.value).unwrap_or(0),
+        })
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.offset.to_le_bytes());
+        buf.extend_from_slice(&self.size.to_le_bytes());
+        buf.extend_from_slice(&self.timestamp.to_le_bytes());
+        buf.extend_from_slice(&self.unique_id.to_le_bytes());
+        buf.extend_from_slice(&self.name.to_le_bytes());
+        buf.extend_from_slice(&[0u8; 4]);
+    }
+
+    fn read_from(buf: &[u8]) -> Self {
+        Self {
+            offset: read_u64(buf, 0),
+            size: read_u64(buf, 8),
+            timestamp: read_u64(buf, 16),
+            unique_id: read_u64(buf, 24),
+            name: read_u32(buf, 32),
+        }
+    }
+
+    /// The offset of the message in the archive file.
+    #[inline(always)]
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+    /// The size of the message (in bytes).
+    #[inline(always)]
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+    /// The hash of the name of the message (if it was stored in the message).
+    #[inline(always)]
+    pub fn name(&self) -> Option<Name> {
+        (self.name != 0).then(|| Name::new(self.name))
+    }
+    /// The timestamp of the message (if it was stored in the message).
+    #[inline(always)]
+    pub fn timestamp(&self) -> Option<Timestamp> {
+        (self.timestamp != 0).then(|| Timestamp::with_value(self.timestamp))
+    }
+    /// The unique id of the message (if it was stored in the message).
+    #[inline(always)]
+    pub fn unique_id(&self) -> Option<UniqueID> {
+        (self.unique_id != 0).then(|| UniqueID::with_value(self.unique_id))
+    }
+}
+
+// Reads the footer of an archive of `file_len` bytes.
+// Returns the offset of the index, the number of entries and the CRC32 of the index.
+fn parse_footer(footer: &[u8], file_len: u64) -> Option<(u64, u64, u32)> {
+    if footer[20..24] != INDEX_MAGIC {
+        return None;
+    }
+    let index_offset = read_u64(footer, 0);
+    let count = read_u64(footer, 8);
+    let index_size = count.checked_mul(ENTRY_SIZE)?;
+    if index_offset < HEADER_SIZE
+        || !index_offset.is_multiple_of(ALIGNMENT)
+        || index_offset.checked_add(index_size)?.checked_add(FOOTER_SIZE)? != file_len
+    {
+        return None;
+    }
+    Some((index_offset, count, read_u32(footer, 16)))
+}
+
+fn parse_index(index: &[u8], index_offset: u64, crc: u32) -> Option<Vec<ArchiveEntry>> {
+    if crc32(index) != crc {
+        return None;
+    }
+    let entries: Vec<ArchiveEntry> = index.chunks_exact(ENTRY_SIZE as usize).map(ArchiveEntry::read_from).collect();
+    let valid = entries.iter().all(|e| {
+        e.offset >= HEADER_SIZE + RECORD_HEADER_SIZE
+            && e.offset.is_multiple_of(ALIGNMENT)
+            && e.offset.checked_add(e.size).is_some_and(|end| end <= index_offset)
+    });
+    valid.then_some(entries)
+}
+
+// Rebuilds the index by scanning the records of an archive (`buf` is the whole file).
+// Returns the entries and the offset where the valid records end (the padding of the last record
+// is part of the record, even if it was not completely written).
+fn scan(buf: &[u8]) -> (Vec<ArchiveEntry>, u64) {
+    let mut entries = Vec::new();
+    let len = buf.len() as u64;
+    let mut pos = HEADER_SIZE;
+    while pos + RECORD_HEADER_SIZE <= len {
+        let p = pos as usize;
+        if buf[p + 12..p + 16] != RECORD_MAGIC {
+            break;
+        }
+        let size = read_u64(buf, p);
+        let start = pos + RECORD_HEADER_SIZE;
+        let Some(end) = start.checked_add(size).filter(|end| *end <= len) else {
+            break;
+        };
+        let message = &buf[start as usize..end as usize];
+        if crc32(message) != read_u32(buf, p + 8) {
+            break;
+        }
+        let Ok(entry) = ArchiveEntry::from_message(start, message) else {
+            break;
+        };
+        entries.push(entry);
+        pos = align(end);
+    }
+    (entries, pos)
+}
+
+// Loads the index of an archive from its bytes (from the footer if valid, or by scanning the records).
+fn load(buf: &[u8]) -> io::Result<(Vec<ArchiveEntry>, u64)> {
+    check_header(buf)?;
+    let len = buf.len() as u64;
+    if len >= HEADER_SIZE + FOOTER_SIZE {
+        let footer = &buf[(len - FOOTER_SIZE) as usize..];
+        if let Some((index_offset, _, crc)) = parse_footer(footer, len) {
+            let index = &buf[index_offset as usize..(len - FOOTER_SIZE) as usize];
+            if let Some(entries) = parse_index(index, index_offset, crc) {
+                return Ok((entries, index_offset));
+            }
+        }
+    }
+    Ok(scan(buf))
+}
+
+/// Writes FlatMessage buffers one after another in an archive file.
+///
+/// The index of the archive is written by [`ArchiveWriter::finish`]. If a writer is not finished
+/// (for example, if the process crashes), the records that were completely written are kept and
+/// the index is rebuilt the next time the archive is opened (by [`ArchiveWriter::open`] or [`ArchiveReader::open`]).
+///
+/// ```rust,no_run
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Event {
+///     code: u32,
+///     id: UniqueID,
+///     timestamp: Timestamp,
+/// }
+///
+/// let mut writer = ArchiveWriter::create("events.flma").unwrap();
+/// let mut storage = Storage::default();
+/// for code in 0..10 {
+///     let event = Event { code, id: UniqueID::new(), timestamp: Timestamp::now() };
+///     event.serialize_to(&mut storage, Config::default()).unwrap();
+///     writer.append(storage.as_slice()).unwrap();
+/// }
+/// writer.finish().unwrap();
+/// ```
+pub struct ArchiveWriter {
+    file: BufWriter<File>,
+    entries: Vec<ArchiveEntry>,
+    end: u64,
+}
+
+impl ArchiveWriter {
+    /// Creates a new (empty) archive. If the file already exists, it is truncated.
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path)?;
+        let mut file = BufWriter::new(file);
+        file.write_all(&header())?;
+        Ok(Self {
+            file,
+            entries: Vec::new(),
+            end: HEADER_SIZE,
+        })
+    }
+
+    /// Opens an archive to append messages to it (the archive is created if it does not exist).
+    ///
+    /// The index of the archive is removed from the file until the writer is finished. If the archive
+    /// has no valid index, the index is rebuilt from the records and any incomplete record at the end
+    /// of the file is discarded.
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let len = file.metadata()?.len();
+        if len == 0 {
+            let mut file = BufWriter::new(file);
+            file.write_all(&header())?;
+            return Ok(Self {
+                file,
+                entries: Vec::new(),
+                end: HEADER_SIZE,
+            });
+        }
+        let (entries, end) = match Self::read_index(&mut file, len)? {
+            Some(index) => index,
+            None => {
+                let mut buf = Vec::with_capacity(len as usize);
+                file.seek(SeekFrom::Start(0))?;
+                file.read_to_end(&mut buf)?;
+                load(&buf)?
+            }
+        };
+        file.set_len(end)?;
+        file.seek(SeekFrom::Start(end))?;
+        Ok(Self {
+            file: BufWriter::new(file),
+            entries,
+            end,
+        })
+    }
+
+    // Reads the index from the end of the file (without reading the records).
+    fn read_index(file: &mut File, len: u64) -> io::Result<Option<(Vec<ArchiveEntry>, u64)>> {
+        let mut header = [0u8; HEADER_SIZE as usize];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut header)?;
+        check_header(&header)?;
+        if len < HEADER_SIZE + FOOTER_SIZE {
+            return Ok(None);
+        }
+        let mut footer = [0u8; FOOTER_SIZE as usize];
+        file.seek(SeekFrom::Start(len - FOOTER_SIZE))?;
+        file.read_exact(&mut footer)?;
+        let Some((index_offset, count, crc)) = parse_footer(&footer, len) else {
+            return Ok(None);
+        };
+        let mut index = vec![0u8; (count * ENTRY_SIZE) as usize];
+        file.seek(SeekFrom::Start(index_offset))?;
+        file.read_exact(&mut index)?;
+        Ok(parse_index(&index, index_offset, crc).map(|entries| (entries, index_offset)))
+    }
+
+    /// Appends a serialized message (e.g. `storage.as_slice()`) to the archive and returns its position.
+    ///
+    /// The message must be a valid FlatMessage buffer (its metadata is read from its header and trailer).
+    pub fn append(&mut self, message: &[u8]) -> io::Result<usize> {
+        let offset = self.end + RECORD_HEADER_SIZE;
+        let entry = ArchiveEntry::from_message(offset, message).map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        let mut record_header = [0u8; RECORD_HEADER_SIZE as usize];
+        record_header[..8].copy_from_slice(&entry.size.to_le_bytes());
+        record_header[8..12].copy_from_slice(&crc32(message).to_le_bytes());
+        record_header[12..].copy_from_slice(&RECORD_MAGIC);
+        let end = align(offset + entry.size);
+        self.file.write_all(&record_header)?;
+        self.file.write_all(message)?;
+        self.file.write_all(&[0u8; ALIGNMENT as usize][..(end - offset - entry.size) as usize])?;
+        self.end = end;
+        self.entries.push(entry);
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Number of messages in the archive.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the archive has no messages.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Writes the messages appended so far to the disk (the index is not written).
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        self.file.get_ref().sync_data()
+    }
+
+    /// Writes the index of the archive and closes the file.
+    pub fn finish(mut self) -> io::Result<()> {
+        let mut index = Vec::with_capacity(self.entries.len() * ENTRY_SIZE as usize);
+        for entry in &self.entries {
+            entry.write_to(&mut index);
+        }
+        let mut footer = [0u8; FOOTER_SIZE as usize];
+        footer[..8].copy_from_slice(&self.end.to_le_bytes());
+        footer[8..16].copy_from_slice(&(self.entries.len() as u64).to_le_bytes());
+        footer[16..20].copy_from_slice(&crc32(&index).to_le_bytes());
+        footer[20..].copy_from_slice(&INDEX_MAGIC);
+        self.file.write_all(&index)?;
+        self.file.write_all(&footer)?;
+        self.file.flush()?;
+        self.file.get_ref().sync_all()
+    }
+}
+
+/// Reads the messages of an archive created by an [`ArchiveWriter`] from a memory mapped file.
+///
+/// Messages are returned as byte slices from the mapped file (every message starts at a 16 bytes aligned
+/// address). To deserialize a message, copy it into a `Storage` (`Storage::from_buffer`).
+///
+/// ```rust,no_run
+/// use flat_message::*;
+///
+/// let archive = unsafe { ArchiveReader::open("events.flma").unwrap() };
+/// for message in archive.iter() {
+///     let info = StructureInformation::try_from(message).unwrap();
+///     println!("{:?}", info.timestamp());
+/// }
+/// let last_hour = archive.range_by_timestamp(Timestamp::with_value(1_700_000_000_000)..);
+/// println!("{} messages", last_hour.count());
+/// ```
+pub struct ArchiveReader {
+    map: Mmap,
+    entries: Vec<ArchiveEntry>,
+    by_unique_id: Vec<usize>,
+    by_timestamp: Vec<usize>,
+}
+
+impl ArchiveReader {
+    /// Opens an archive (if the archive has no valid index, the index is rebuilt from the records).
+    ///
+    /// # Safety
+    ///
+    /// The file is memory mapped: it must not be modified (e.g. by an `ArchiveWriter`) or truncated
+    /// while the reader exists.
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let file = File::open(path)?;
+        let map = unsafe { Mmap::map(&file)? };
+        let (entries, _) = load(&map)?;
+        let mut by_unique_id: Vec<usize> = (0..entries.len()).filter(|i| entries[*i].unique_id != 0).collect();
+        by_unique_id.sort_by_key(|i| entries[*i].unique_id);
+        let mut by_timestamp: Vec<usize> = (0..entries.len()).filter(|i| entries[*i].timestamp != 0).collect();
+        by_timestamp.sort_by_key(|i| entries[*i].timestamp);
+        Ok(Self {
+            map,
+            entries,
+            by_unique_id,
+            by_timestamp,
+        })
+    }
+
+    /// Number of messages in the archive.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if the archive has no messages.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The index entries of the archive (in the order the messages were appended).
+    #[inline(always)]
+    pub fn entries(&self) -> &[ArchiveEntry] {
+        &self.entries
+    }
+
+    #[inline(always)]
+    fn message(&self, entry: &ArchiveEntry) -> &[u8] {
+        &self.map[entry.offset as usize..(entry.offset + entry.size) as usize]
+    }
+
+    /// Returns the message from a position (or `None` if the position is out of bounds).
+    pub fn get(&self, index: usize) -> Option<&[u8]> {
+        self.entries.get(index).map(|e| self.message(e))
+    }
+
+    /// Iterates over all messages (in the order they were appended).
+    pub fn iter(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.entries.iter().map(|e| self.message(e))
+    }
+
+    /// Returns the (first) message with a specific unique id.
+    pub fn find_by_unique_id(&self, id: UniqueID) -> Option<&[u8]> {
+        let pos = self.by_unique_id.partition_point(|i| self.entries[*i].unique_id < id.value());
+        let entry = &self.entries[*self.by_unique_id.get(pos)?];
+        (entry.unique_id == id.value()).then(|| self.message(entry))
+    }
+
+    /// Iterates (in timestamp order) over the messages with a timestamp within `range`.
+    /// Messages without a timestamp are not included.
+    pub fn range_by_timestamp<R: RangeBounds<Timestamp>>(&self, range: R) -> impl Iterator<Item = &[u8]> + '_ {
+        let timestamp = |i: &usize| self.entries[*i].timestamp;
+        let start = match range.start_bound() {
+            Bound::Included(t) => self.by_timestamp.partition_point(|i| timestamp(i) < t.value()),
+            Bound::Excluded(t) => self.by_timestamp.partition_point(|i| timestamp(i) <= t.value()),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(t) => self.by_timestamp.partition_point(|i| timestamp(i) <= t.value()),
+            Bound::Excluded(t) => self.by_timestamp.partition_point(|i| timestamp(i) < t.value()),
+            Bound::Unbounded => self.by_timestamp.len(),
+        };
+        self.by_timestamp[start..end.max(start)].iter().map(|i| self.message(&self.entries[*i]))
+    }
+}
@@ -0,0 +1,112 @@
+use crate::{Config, Error, FlatMessage, Storage};
+
+/// A structure that can be stored in a columnar [`Batch`] (implemented by the derive for
+/// structures with `#[flat_message_options(batch: true)]`).
+///
+/// The derive also creates a `<Name>Columns<'a>` structure with one public field (a column) for
+/// every field of the structure. The methods of this trait are used by [`Batch`].
+pub trait BatchRow<'a>: Sized {
+    /// The columns of the batch (`<Name>Columns<'a>`).
+    type Columns: FlatMessage<'a>;
+    /// Serializes `rows` as a batch (one column for every field).
+    fn serialize_batch(rows: &[Self], output: &mut Storage, config: Config) -> Result<(), Error>;
+    /// Number of rows in the batch.
+    fn len(columns: &Self::Columns) -> usize;
+    /// Checks that every column has the same number of values as the number of rows.
+    fn check(columns: &Self::Columns) -> Result<(), Error>;
+    /// Rebuilds the row at `index` (or returns `None` if `index` is out of bounds).
+    fn row(columns: &Self::Columns, index: usize) -> Option<Self>;
+    /// Iterates over the rows of the batch.
+    fn rows(columns: &Self::Columns) -> impl Iterator<Item = Self> + '_;
+}
+
+/// Many values of the same structure stored as columns (struct-of-arrays).
+///
+/// A batch is a regular FlatMessage buffer (with one header, one hash table and one name for all
+/// the rows) where every field of the structure is stored as a list. Numeric columns can be read
+/// directly from the buffer (e.g. `&[u64]` for a `u64` field) and `Option` fields use a validity
+/// bitmap (`NullableSlice`).
+///
+/// ```rust
+/// use flat_message::*;
+///
+/// #[derive(Debug, PartialEq, FlatMessage)]
+/// #[flat_message_options(batch: true)]
+/// struct Trade {
+///     price: f64,
+///     quantity: u32,
+///     symbol: String,
+/// }
+///
+/// let trades: Vec<Trade> = (0..100)
+///     .map(|i| Trade { price: i as f64 * 1.5, quantity: i, symbol: format!("S{}", i % 4) })
+///     .collect();
+/// let mut storage = Storage::default();
+/// Batch::serialize_to(&trades, &mut storage, Config::default()).unwrap();
+///
+/// let batch = Batch::<Trade>::deserialize_from(&storage).unwrap();
+/// assert_eq!(batch.len(), 100);
+/// let quantity: &[u32] = batch.columns().quantity;
+/// assert_eq!(quantity.iter().sum::<u32>(), 4950);
+/// assert_eq!(batch.get(3), Some(Trade { price: 4.5, quantity: 3, symbol: "S3".to_string() }));
+/// assert!(batch.iter().eq(trades.into_iter()));
+/// ```
+pub struct Batch<'a, T: BatchRow<'a>> {
+    columns: T::Columns,
+}
+
+impl<'a, T: BatchRow<'a>> Batch<'a, T> {
+    /// Serializes `rows` as a batch.
+    #[inline(always)]
+    pub fn serialize_to(rows: &[T], output: &mut Storage, config: Config) -> Result<(), Error> {
+        T::serialize_batch(rows, output, config)
+    }
+
+    /// Deserializes a batch (the columns are checked to have the same number of values).
+    pub fn deserialize_from(input: &'a Storage) -> Result<Self, Error> {
+        Self::from_columns(T::Columns::deserialize_from(input)?)
+    }
+
+    /// Deserializes a batch using the limits from `config` (see `FlatMessage::deserialize_from_with_config`).
+    pub fn deserialize_from_with_config(input: &'a Storage, config: Config) -> Result<Self, Error> {
+        Self::from_columns(T::Columns::deserialize_from_with_config(input, config)?)
+    }
+
+    fn from_columns(columns: T::Columns) -> Result<Self, Error> {
+        T::check(&columns)?;
+        Ok(Self { columns })
+    }
+
+    /// The columns of the batch (one public field for every field of `T`).
+    #[inline(always)]
+    pub fn columns(&self) -> &T::Columns {
+        &self.columns
+    }
+
+    /// Number of rows.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        T::len(&self.columns)
+    }
+
+    /// Returns `true` if the batch has no rows.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Rebuilds the row at `index` (or returns `None` if `index` is out of bounds).
+    ///
+    /// The value of an `Option` column is found by counting the values before `index` - use
+    /// `iter()` to go through all the rows.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<T> {
+        T::row(&self.columns, index)
+    }
+
+    /// Iterates over the rows (every row is rebuilt from the columns).
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = T> + use<'_, 'a, T> {
+        T::rows(&self.columns)
+    }
+}
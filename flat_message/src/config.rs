@@ -1,20 +1,65 @@
 #[derive(Copy, Clone, Eq, PartialEq)]
 pub struct Config {
-    max_size: u32,
+    max_size: u64,
+    max_depth: u32,
+    max_list_len: u64,
+    max_string_len: u64,
+    max_allocation: u64,
 }
 impl Config {
+    /// Default value for [`Config::max_depth`].
+    pub const DEFAULT_MAX_DEPTH: u32 = 64;
+
     /// Returns the maximum serialized size allowed (in bytes).
     ///
     /// If the serialized size exceeds this limit, an error is returned.
     #[inline(always)]
-    pub fn max_size(&self) -> u32 {
+    pub fn max_size(&self) -> u64 {
         self.max_size
     }
+
+    /// Returns the maximum number of nested `Box`, `Rc` or `Arc` values, structures and variants
+    /// (e.g. the depth of a recursive tree) that can be deserialized.
+    ///
+    /// If the limit is exceeded, `FlatMessage::deserialize_from_with_config` returns `Error::MaxDepthExceeded`.
+    #[inline(always)]
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+
+    /// Returns the maximum number of elements of a list (vector or slice) that can be deserialized.
+    ///
+    /// If the limit is exceeded, `FlatMessage::deserialize_from_with_config` returns `Error::MaxListLengthExceeded`.
+    #[inline(always)]
+    pub fn max_list_len(&self) -> u64 {
+        self.max_list_len
+    }
+
+    /// Returns the maximum length (in bytes) of a string that can be deserialized.
+    ///
+    /// If the limit is exceeded, `FlatMessage::deserialize_from_with_config` returns `Error::MaxStringLengthExceeded`.
+    #[inline(always)]
+    pub fn max_string_len(&self) -> u64 {
+        self.max_string_len
+    }
+
+    /// Returns the maximum number of bytes that can be allocated (for vectors, strings and other
+    /// owned values) while deserializing a message. Zero-copy values (slices, `&str`) are not counted.
+    ///
+    /// If the limit is exceeded, `FlatMessage::deserialize_from_with_config` returns `Error::MaxAllocationExceeded`.
+    #[inline(always)]
+    pub fn max_allocation(&self) -> u64 {
+        self.max_allocation
+    }
 }
 impl Default for Config {
     fn default() -> Self {
         Self {
             max_size: 16 * 1024 * 1024,
+            max_depth: Self::DEFAULT_MAX_DEPTH,
+            max_list_len: u64::MAX,
+            max_string_len: u64::MAX,
+            max_allocation: u64::MAX,
         }
     }
 }
@@ -44,11 +89,43 @@ impl ConfigBuilder {
     ///
     /// If the serialized size exceeds this limit, an error is returned.
     #[inline(always)]
-    pub fn max_size(mut self, max_size: u32) -> Self {
+    pub fn max_size(mut self, max_size: u64) -> Self {
         self.config.max_size = max_size;
         self
     }
 
+    /// Sets the maximum number of nested `Box`, `Rc` or `Arc` values, structures and variants that can be deserialized.
+    ///
+    /// This protects against stack exhaustion when deserializing recursive structures from untrusted input.
+    #[inline(always)]
+    pub fn max_depth(mut self, max_depth: u32) -> Self {
+        self.config.max_depth = max_depth;
+        self
+    }
+
+    /// Sets the maximum number of elements of a list that can be deserialized (no limit by default).
+    #[inline(always)]
+    pub fn max_list_len(mut self, max_list_len: u64) -> Self {
+        self.config.max_list_len = max_list_len;
+        self
+    }
+
+    /// Sets the maximum length (in bytes) of a string that can be deserialized (no limit by default).
+    #[inline(always)]
+    pub fn max_string_len(mut self, max_string_len: u64) -> Self {
+        self.config.max_string_len = max_string_len;
+        self
+    }
+
+    /// Sets the maximum number of bytes that can be allocated while deserializing a message (no limit by default).
+    ///
+    /// Together with `max_list_len` and `max_string_len`, this bounds the memory used when deserializing untrusted input.
+    #[inline(always)]
+    pub fn max_allocation(mut self, max_allocation: u64) -> Self {
+        self.config.max_allocation = max_allocation;
+        self
+    }
+
     /// Builds the `Config` instance with the configured options.
     ///
     /// This method returns the `Config` instance with the specified options.
@@ -4,20 +4,33 @@ pub use std::fmt;
 pub enum Error {
     InvalidHeaderLength(usize),
     InvalidMagic,
-    InvalidSize((u32, u32)),
+    InvalidSize((u64, u64)),
     InvalidOffsetSize,
-    InvalidSizeToStoreMetaData((u32, u32)),
+    InvalidSizeToStoreMetaData((u64, u64)),
     InvalidHash((u32, u32)),
-    InvalidSizeToStoreFieldsTable((u32, u32)),
+    InvalidSizeToStoreFieldsTable((u64, u64)),
     IncompatibleVersion(u8),
     FieldIsMissing(u32),
-    InvalidFieldOffset((u32, u32)),
+    InvalidFieldOffset((u64, u64)),
     FailToDeserialize(u32),
     NameNotStored,
     UnmatchedName,
     ChecksumNotStored,
     InvalidChecksum((u32, u32)),
-    ExceedMaxSize((u32, u32)),
+    ExceedMaxSize((u64, u64)),
+    InvalidFlags(u8),
+    UnsortedHashTable(u32),
+    DuplicateFieldHash(u32),
+    UnknownDataFormat(u32),
+    InvalidFieldSize(u32),
+    UnalignedField(u32),
+    UnalignedHashTable(u64),
+    MaxDepthExceeded(u32),
+    MaxListLengthExceeded(u64),
+    MaxStringLengthExceeded(u64),
+    MaxAllocationExceeded(u64),
+    InvalidColumnLength((u64, u64)),
+    InvalidFieldValue,
 }
 
 impl fmt::Display for Error {
@@ -34,7 +47,7 @@ impl fmt::Display for Error {
                 "Invalid buffer size (expected {} bytes - but found: {})",
                 expected, actual
             ),
-            Error::InvalidOffsetSize => write!(f, "Invalid offset size (only 0, 1, 2 representing U8, U16 and U32 are allowed)"),
+            Error::InvalidOffsetSize => write!(f, "Invalid offset size (only 0, 1, 2 and 3 representing U8, U16, U32 and U64 are allowed)"),
             Error::InvalidSizeToStoreMetaData((actual, expected)) => write!(
                 f,
                 "Invalid buffer size to store meta data (expected at least {} bytes - but found: {})",
@@ -71,6 +84,25 @@ impl fmt::Display for Error {
                 "Exceed maximum size (maximum size allowed: {} bytes - but found: {})",
                 max_size, actual
             ),
+            Error::InvalidFlags(flags) => write!(f, "Invalid serialization flags: 0b{:08b} (bits 6 and 7 are reserved)", flags),
+            Error::UnsortedHashTable(hash) => write!(f, "The hash table is not sorted (hash 0x{:08X} is out of order)", hash),
+            Error::DuplicateFieldHash(hash) => write!(f, "Duplicate field hash in the hash table: 0x{:08X}", hash),
+            Error::UnknownDataFormat(hash) => write!(f, "Unknown data format for field with hash: 0x{:08X}", hash),
+            Error::InvalidFieldSize(hash) => write!(f, "The size of the field with hash 0x{:08X} exceeds the data region", hash),
+            Error::UnalignedField(hash) => write!(f, "The field with hash 0x{:08X} is not properly aligned", hash),
+            Error::UnalignedHashTable(offset) => write!(f, "The hash table (at offset {}) is not aligned to 4 bytes", offset),
+            Error::MaxDepthExceeded(depth) => write!(f, "Maximum nesting depth exceeded (maximum depth allowed: {})", depth),
+            Error::MaxListLengthExceeded(len) => write!(f, "Maximum number of elements in a list exceeded (maximum allowed: {})", len),
+            Error::MaxStringLengthExceeded(len) => write!(f, "Maximum string length exceeded (maximum allowed: {} bytes)", len),
+            Error::MaxAllocationExceeded(size) => write!(f, "Maximum memory allocated while deserializing exceeded (maximum allowed: {} bytes)", size),
+            Error::InvalidColumnLength((actual, expected)) => write!(
+                f,
+                "Invalid number of values in a batch column (expected {} values - but found: {})",
+                expected, actual
+            ),
+            Error::InvalidFieldValue => write!(f, "The value of a field could not be read (by a FlatField or a field adapter)"),
         }
     }
 }
+
+impl std::error::Error for Error {}
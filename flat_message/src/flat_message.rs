@@ -6,6 +6,23 @@ pub trait FlatMessage<'a> {
     fn deserialize_from(input: &'a Storage) -> Result<Self, Error>
     where
         Self: Sized;
+    /// Deserializes an object using the deserialization limits from `config` (see [`Config::max_depth`],
+    /// [`Config::max_list_len`], [`Config::max_string_len`] and [`Config::max_allocation`]).
+    fn deserialize_from_with_config(input: &'a Storage, config: Config) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        crate::limits::with_limits(Some(config), || Self::deserialize_from(input))
+    }
+    /// Deserializes an object from a shared buffer. `bytes::Bytes` and [`SharedStr`](crate::SharedStr) fields
+    /// are created as views into `input` (reference counted, without copying their content).
+    #[cfg(feature = "bytes")]
+    fn deserialize_from_shared(input: &'a crate::SharedStorage) -> Result<Self, Error>
+    where
+        Self: Sized,
+    {
+        crate::shared::with_source(input.to_bytes(), || Self::deserialize_from(input))
+    }
     unsafe fn deserialize_from_unchecked(input: &'a Storage) -> Result<Self, Error>
     where
         Self: Sized;
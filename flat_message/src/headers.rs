@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 #[repr(C, packed)]
 #[derive(Debug, Clone, Copy)]
 pub struct HeaderV1 {
@@ -6,3 +8,49 @@ pub struct HeaderV1 {
     pub version: u8,
     pub flags: u8,
 }
+
+/// The largest size of a nested structure (its size is stored on 24 bits, next to its flags).
+pub const MAX_NESTED_STRUCT_SIZE: usize = 0xFF_FFFF;
+
+// `SerDe::size` can not return an error, so a nested structure that is too large is recorded in a
+// thread-local state while the size of a message is computed, and `serialize_to` returns
+// `Error::ExceedMaxSize` before anything is written.
+
+thread_local! {
+    static NESTED_STRUCT_TOO_LARGE: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Records `size` if it is larger than [`MAX_NESTED_STRUCT_SIZE`] and returns it.
+#[doc(hidden)]
+#[inline(always)]
+pub fn check_nested_struct_size(size: usize) -> usize {
+    if size > MAX_NESTED_STRUCT_SIZE {
+        NESTED_STRUCT_TOO_LARGE.with(|s| s.set(Some(s.get().map_or(size, |v| v.max(size)))));
+    }
+    size
+}
+
+/// Collects the nested structures that are too large while `serialize_to` computes the size of a
+/// message. The previous state is restored when the guard is dropped.
+#[doc(hidden)]
+pub struct NestedStructSizes(Option<usize>);
+
+impl NestedStructSizes {
+    #[inline(always)]
+    pub fn enter() -> NestedStructSizes {
+        NestedStructSizes(NESTED_STRUCT_TOO_LARGE.with(|s| s.take()))
+    }
+    /// Returns the size of the largest nested structure that can not be stored (if any).
+    #[inline(always)]
+    pub fn too_large(&self) -> Option<usize> {
+        NESTED_STRUCT_TOO_LARGE.with(|s| s.take())
+    }
+}
+
+impl Drop for NestedStructSizes {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let previous = self.0;
+        NESTED_STRUCT_TOO_LARGE.with(|s| s.set(previous));
+    }
+}
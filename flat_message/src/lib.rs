@@ -1,30 +1,82 @@
+#[cfg(feature = "archive")]
+mod archive;
+mod batch;
 mod buffer;
 mod config;
+#[doc(hidden)]
+pub mod limits;
 mod error;
 mod flat_message;
 //mod flat_message_buffer;
 pub mod headers;
 mod name;
+mod owned_message;
 mod serde;
+#[cfg(feature = "bytes")]
+mod shared;
 mod unique_id;
 mod timestamp;
 pub mod size;
 mod storage;
+mod storage_pool;
 mod structure_information;
 mod flags_support;
+mod validate;
 //mod builder;
 
+#[cfg(feature = "archive")]
+pub use self::archive::ArchiveEntry;
+#[cfg(feature = "archive")]
+pub use self::archive::ArchiveReader;
+#[cfg(feature = "archive")]
+pub use self::archive::ArchiveWriter;
+pub use self::batch::Batch;
+pub use self::batch::BatchRow;
 pub use self::config::Config;
 pub use self::config::ConfigBuilder;
 pub use self::error::Error;
 pub use self::flat_message::FlatMessage;
 //pub use self::flat_message_buffer::FlatMessageBuffer;
 pub use self::name::Name;
+pub use self::owned_message::MessageView;
+pub use self::owned_message::OwnedMessage;
+pub use self::serde::BitSlice;
+pub use self::serde::BitVec;
+pub use self::serde::DeltaIter;
+pub use self::serde::DeltaSlice;
+#[doc(hidden)]
+pub use self::serde::AdapterSizes;
+#[doc(hidden)]
+pub use self::serde::FieldAdapter;
+pub use self::serde::FieldFormat;
+#[doc(hidden)]
+pub use self::serde::FieldListAdapter;
+pub use self::serde::FieldReader;
+pub use self::serde::FieldWriter;
+pub use self::serde::FlatField;
+pub use self::serde::IterField;
+pub use self::serde::NullableSlice;
 pub use self::serde::SerDe;
+pub use self::serde::SerDeDelta;
+pub use self::serde::SerDeIter;
+pub use self::serde::SerDeNullable;
 pub use self::serde::SerDeSlice;
+pub use self::serde::SerDeStringRef;
+pub use self::serde::SerDeVarint;
 pub use self::serde::SerDeVec;
+pub use self::serde::SerDeVecPointer;
+pub use self::serde::SerDeWith;
+#[doc(hidden)]
+pub use self::serde::StringTable;
+#[cfg(feature = "bytes")]
+pub use self::shared::SharedStorage;
+#[cfg(feature = "bytes")]
+pub use self::shared::SharedStr;
 pub use self::storage::Storage;
+pub use self::storage_pool::PooledStorage;
+pub use self::storage_pool::StoragePool;
 pub use self::structure_information::StructureInformation;
+pub use self::validate::validate;
 
 pub use flat_message_proc_macro::*;
 
@@ -0,0 +1,176 @@
+use crate::{Config, Error};
+use std::cell::Cell;
+
+// The limits from `Config` are stored in a thread-local state while a message is deserialized
+// (`SerDe::from_buffer` does not receive a `Config`). When a limit is reached, the value that
+// is being deserialized returns `None` and the limit is recorded, so that `with_limits` can
+// return the specific error instead of `Error::FailToDeserialize`.
+
+#[derive(Copy, Clone)]
+enum Exceeded {
+    Depth,
+    ListLength,
+    StringLength,
+    Allocation,
+}
+
+#[derive(Copy, Clone)]
+struct LimitsState {
+    depth: u32,
+    max_depth: u32,
+    max_list_len: u64,
+    max_string_len: u64,
+    max_allocation: u64,
+    allocated: u64,
+    exceeded: Option<Exceeded>,
+    active: bool,
+}
+
+impl LimitsState {
+    fn new(config: &Config, active: bool) -> Self {
+        Self {
+            depth: 0,
+            max_depth: config.max_depth(),
+            max_list_len: config.max_list_len(),
+            max_string_len: config.max_string_len(),
+            max_allocation: config.max_allocation(),
+            allocated: 0,
+            exceeded: None,
+            active,
+        }
+    }
+}
+
+thread_local! {
+    static STATE: Cell<LimitsState> = const {
+        Cell::new(LimitsState {
+            depth: 0,
+            max_depth: Config::DEFAULT_MAX_DEPTH,
+            max_list_len: u64::MAX,
+            max_string_len: u64::MAX,
+            max_allocation: u64::MAX,
+            allocated: 0,
+            exceeded: None,
+            active: false,
+        })
+    };
+}
+
+#[inline(always)]
+fn fail(kind: Exceeded) -> Option<()> {
+    STATE.with(|s| {
+        let mut state = s.get();
+        state.exceeded = Some(kind);
+        s.set(state);
+    });
+    None
+}
+
+/// Returns `None` (and marks the current deserialization as failed) if a list has more elements than allowed.
+#[inline(always)]
+pub(crate) fn check_list_len(count: usize) -> Option<()> {
+    let state = STATE.with(|s| s.get());
+    if !state.active || count as u64 <= state.max_list_len {
+        Some(())
+    } else {
+        fail(Exceeded::ListLength)
+    }
+}
+
+/// Returns `None` (and marks the current deserialization as failed) if a string is longer than allowed.
+#[inline(always)]
+pub(crate) fn check_string_len(len: usize) -> Option<()> {
+    let state = STATE.with(|s| s.get());
+    if !state.active || len as u64 <= state.max_string_len {
+        Some(())
+    } else {
+        fail(Exceeded::StringLength)
+    }
+}
+
+/// Accounts for the memory needed to store `count` values of type `T` and returns `None`
+/// (marking the current deserialization as failed) if the allocation limit is reached.
+#[inline(always)]
+pub(crate) fn allocate<T>(count: usize) -> Option<()> {
+    STATE.with(|s| {
+        let mut state = s.get();
+        if !state.active {
+            return Some(());
+        }
+        let size = (count as u64).saturating_mul(std::mem::size_of::<T>() as u64);
+        state.allocated = state.allocated.saturating_add(size);
+        if state.allocated > state.max_allocation {
+            state.exceeded = Some(Exceeded::Allocation);
+            s.set(state);
+            None
+        } else {
+            s.set(state);
+            Some(())
+        }
+    })
+}
+
+/// Tracks one nesting level while deserializing a `Box`, `Rc` or `Arc` value, a structure or a variant.
+/// The level is released when the guard is dropped.
+#[doc(hidden)]
+pub struct DepthGuard;
+
+impl DepthGuard {
+    /// Returns `None` (and marks the current deserialization as failed) if the maximum depth was reached.
+    #[inline(always)]
+    pub fn enter() -> Option<DepthGuard> {
+        STATE.with(|s| {
+            let mut state = s.get();
+            if state.active && state.depth >= state.max_depth {
+                state.exceeded = Some(Exceeded::Depth);
+                s.set(state);
+                None
+            } else {
+                state.depth += 1;
+                s.set(state);
+                Some(DepthGuard)
+            }
+        })
+    }
+}
+
+impl Drop for DepthGuard {
+    #[inline(always)]
+    fn drop(&mut self) {
+        STATE.with(|s| {
+            let mut state = s.get();
+            state.depth -= 1;
+            s.set(state);
+        });
+    }
+}
+
+/// Runs `f` (a deserialization) with the limits from `config` and returns the error that
+/// corresponds to the limit reached by `f` (e.g. `Error::MaxDepthExceeded`). If `config` is `None`,
+/// the limits of an already running deserialization are kept (or the default ones are used).
+pub fn with_limits<R>(config: Option<Config>, f: impl FnOnce() -> Result<R, Error>) -> Result<R, Error> {
+    let current = STATE.with(|s| s.get());
+    if config.is_none() && current.active {
+        return f();
+    }
+    // the previous state is restored even if `f` panics
+    struct Restore(LimitsState);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0;
+            STATE.with(|s| s.set(previous));
+        }
+    }
+    STATE.with(|s| s.set(LimitsState::new(&config.unwrap_or_default(), true)));
+    let restore = Restore(current);
+    let result = f();
+    let state = STATE.with(|s| s.get());
+    drop(restore);
+    match state.exceeded {
+        None => result,
+        Some(Exceeded::Depth) => Err(Error::MaxDepthExceeded(state.max_depth)),
+        Some(Exceeded::ListLength) => Err(Error::MaxListLengthExceeded(state.max_list_len)),
+        Some(Exceeded::StringLength) => Err(Error::MaxStringLengthExceeded(state.max_string_len)),
+        Some(Exceeded::Allocation) => Err(Error::MaxAllocationExceeded(state.max_allocation)),
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{Config, Error, FlatMessage, Storage};
+use std::fmt;
+use std::io::{self, Read};
+use std::mem::ManuallyDrop;
+use std::ptr::NonNull;
+
+/// Structures that can be stored in an [`OwnedMessage`] (implemented by the derive for structures
+/// without type parameters and with at most one lifetime).
+///
+/// `Self` is the structure with a `'static` lifetime (e.g. `Msg<'static>` for `struct Msg<'a>`) and
+/// `View<'a>` is the same structure with any lifetime.
+///
+/// # Safety
+/// `View<'a>` must be covariant in `'a`, and a value deserialized from a `&'a Storage` must only
+/// keep references to that storage in the returned value.
+pub unsafe trait MessageView: 'static {
+    /// The structure with the lifetime `'a`.
+    type View<'a>: FlatMessage<'a>;
+    /// Shortens the lifetime of a view (only possible if the structure is covariant).
+    fn shorten<'short, 'long: 'short>(view: &'short Self::View<'long>) -> &'short Self::View<'short>;
+}
+
+/// A deserialized message together with the [`Storage`] it borrows from.
+///
+/// A structure with borrowed fields (such as `struct Msg<'a> { name: &'a str }`) can not be returned
+/// from a function together with the buffer it was deserialized from. An `OwnedMessage` owns both
+/// (the buffer is allocated on the heap and never moves), so it can be returned, stored in queues or
+/// caches and sent to other threads. The message is accessed with [`OwnedMessage::get`].
+///
+/// ```rust
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Msg<'a> {
+///     name: &'a str,
+///     values: &'a [u32],
+/// }
+///
+/// fn load() -> OwnedMessage<Msg<'static>> {
+///     let mut storage = Storage::default();
+///     let msg = Msg { name: "sensor", values: &[1, 2, 3] };
+///     msg.serialize_to(&mut storage, Config::default()).unwrap();
+///     OwnedMessage::new(storage).unwrap()
+/// }
+///
+/// let messages = vec![load(), load()];
+/// for message in messages.iter() {
+///     let msg: &Msg<'_> = message.get();
+///     assert_eq!(msg.name, "sensor");
+///     assert_eq!(msg.values, &[1, 2, 3]);
+/// }
+/// ```
+pub struct OwnedMessage<T: MessageView> {
+    // borrows from `storage` (the `'static` lifetime is never exposed - see `get`)
+    message: ManuallyDrop<T::View<'static>>,
+    // allocated with `Box` (released in `drop`, after the message)
+    storage: NonNull<Storage>,
+}
+
+impl<T: MessageView> OwnedMessage<T> {
+    /// Deserializes a message and keeps it together with its buffer.
+    pub fn new(storage: Storage) -> Result<Self, Error> {
+        Self::deserialize(storage, T::View::<'static>::deserialize_from)
+    }
+
+    /// Deserializes a message using the limits from `config` (see `FlatMessage::deserialize_from_with_config`).
+    pub fn with_config(storage: Storage, config: Config) -> Result<Self, Error> {
+        Self::deserialize(storage, |input| {
+            T::View::<'static>::deserialize_from_with_config(input, config)
+        })
+    }
+
+    /// Deserializes a message from a vector of bytes (the bytes are copied into an aligned `Storage`).
+    pub fn from_vec(buffer: Vec<u8>) -> Result<Self, Error> {
+        Self::new(Storage::from_buffer(&buffer))
+    }
+
+    /// Reads a frame (see [`Storage::write_frame`]) and deserializes it. Returns `Ok(None)` if the reader
+    /// has no more data. Frames larger than `config.max_size()` and messages that can not be deserialized
+    /// return an `InvalidData` error.
+    pub fn read_frame<R: Read>(reader: &mut R, config: Config) -> io::Result<Option<Self>> {
+        let mut storage = Storage::default();
+        if !storage.read_frame(reader, config.max_size())? {
+            return Ok(None);
+        }
+        Self::with_config(storage, config)
+            .map(Some)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn deserialize(
+        storage: Storage,
+        deserialize: impl FnOnce(&'static Storage) -> Result<T::View<'static>, Error>,
+    ) -> Result<Self, Error> {
+        let storage = NonNull::from(Box::leak(Box::new(storage)));
+        // the storage lives (and does not move) until the message is dropped
+        match deserialize(unsafe { storage.as_ref() }) {
+            Ok(message) => Ok(Self {
+                message: ManuallyDrop::new(message),
+                storage,
+            }),
+            Err(e) => {
+                drop(unsafe { Box::from_raw(storage.as_ptr()) });
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the message (borrowed from the buffer).
+    #[inline(always)]
+    pub fn get(&self) -> &T::View<'_> {
+        T::shorten(&self.message)
+    }
+
+    /// Returns the buffer the message was deserialized from.
+    #[inline(always)]
+    pub fn storage(&self) -> &Storage {
+        unsafe { self.storage.as_ref() }
+    }
+
+    /// Drops the message and returns its buffer.
+    pub fn into_storage(self) -> Storage {
+        let mut this = ManuallyDrop::new(self);
+        unsafe {
+            ManuallyDrop::drop(&mut this.message);
+            *Box::from_raw(this.storage.as_ptr())
+        }
+    }
+}
+
+impl<T: MessageView> Drop for OwnedMessage<T> {
+    fn drop(&mut self) {
+        unsafe {
+            ManuallyDrop::drop(&mut self.message);
+            drop(Box::from_raw(self.storage.as_ptr()));
+        }
+    }
+}
+
+// the storage is owned by the message (it is only shared with the borrowed view)
+unsafe impl<T: MessageView> Send for OwnedMessage<T> where T::View<'static>: Send {}
+unsafe impl<T: MessageView> Sync for OwnedMessage<T> where T::View<'static>: Sync {}
+
+impl<T: MessageView> fmt::Debug for OwnedMessage<T>
+where
+    for<'a> T::View<'a>: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.get(), f)
+    }
+}
@@ -1,13 +1,44 @@
 use common::data_format::DataFormat;
 
+mod adapter;
 mod basic_types;
+mod bits;
 mod bool;
 mod buffers;
 mod buffers_8bits;
+#[cfg(feature = "bytes")]
+mod bytes_support;
+mod char;
+mod cow;
+#[cfg(any(feature = "chrono", feature = "time"))]
+mod date_time;
+mod delta;
+mod duration;
 mod fix_array;
 mod ip;
+mod iter_field;
+#[cfg(feature = "ipnet")]
+mod ip_net;
+mod nested_lists;
+mod nonzero;
+mod nullable;
+mod path;
+mod pointer_sized;
+mod smart_pointers;
 mod string;
 mod string_lists;
+mod string_table;
+mod tuple;
+mod varint;
+#[cfg(feature = "uuid")]
+mod uuid_support;
+
+pub use adapter::{AdapterSizes, FieldAdapter, FieldFormat, FieldListAdapter, FieldReader, FieldWriter};
+pub use bits::{BitSlice, BitVec};
+pub use delta::{DeltaIter, DeltaSlice};
+pub use iter_field::IterField;
+pub use nullable::NullableSlice;
+pub use string_table::StringTable;
 
 pub unsafe trait SerDe<'a> {
     const DATA_FORMAT: DataFormat;
@@ -17,6 +48,10 @@ pub unsafe trait SerDe<'a> {
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self>
     where
         Self: Sized;
+    /// Writes a value and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj)` bytes available starting from `pos`.
     unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize;
     fn size(obj: &Self) -> usize;
 }
@@ -52,3 +87,244 @@ pub unsafe trait SerDeVec<'a> {
     where
         Self: Sized;
 }
+
+/// Vectors behind a `Box`, `Rc` or `Arc` (`Box<Vec<T>>`, ...). They are stored exactly like a `Vec<T>`.
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+pub unsafe trait SerDeVecPointer<'a> {
+    const DATA_FORMAT: DataFormat;
+    /// Reads a vector.
+    ///
+    /// # Safety
+    /// The buffer must contain a valid vector at `pos`.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self
+    where
+        Self: Sized;
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self>
+    where
+        Self: Sized;
+    /// Writes a vector and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj)` bytes available starting from `pos`.
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize;
+    fn size(obj: &Self) -> usize;
+}
+
+/// Element types that can be stored in a list with missing values (`Vec<Option<T>>` or [`NullableSlice`]).
+///
+/// The values that are present are stored one after another (after a validity bitmap), so each method
+/// reads or writes a single value.
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+/// If `ZERO_COPY` is `true`, a value must be stored as `size_of::<Self>()` bytes with the memory layout of `Self`.
+pub unsafe trait SerDeNullable<'a> {
+    const DATA_FORMAT: DataFormat;
+    /// Hash stored in the header of the list (used to validate the type of the elements, e.g. the name of an enum).
+    const TYPE_HASH: u32 = 0;
+    /// `true` if a value is stored with the same memory layout as `Self` (this allows zero-copy
+    /// deserialization via [`NullableSlice`]).
+    const ZERO_COPY: bool = false;
+    /// Reads a value and returns it together with the position right after it.
+    ///
+    /// # Safety
+    /// The buffer must contain a valid value at `pos`.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> (Self, usize)
+    where
+        Self: Sized;
+    /// Reads and validates a value and returns it together with the position right after it.
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<(Self, usize)>
+    where
+        Self: Sized;
+    /// Writes a value and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj)` bytes available starting from `pos`.
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize;
+    fn size(obj: &Self) -> usize;
+}
+
+/// Integer types (and vectors of integers) that can be stored as variable length integers
+/// (`#[flat_message_item(encoding = "varint")]`).
+///
+/// Values are stored in LEB128 format (7 bits per byte), signed values are zigzag encoded first
+/// (small negative values use a small number of bytes as well).
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+pub unsafe trait SerDeVarint<'a> {
+    const DATA_FORMAT: DataFormat;
+    /// Reads a value.
+    ///
+    /// # Safety
+    /// The buffer must contain a valid value at `pos`.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self
+    where
+        Self: Sized;
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self>
+    where
+        Self: Sized;
+    /// Writes a value and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj)` bytes available starting from `pos`.
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize;
+    fn size(obj: &Self) -> usize;
+}
+
+/// Vectors of integers that can be stored as a list of differences between consecutive values
+/// (`#[flat_message_item(encoding = "delta")]` or a [`DeltaSlice`] field).
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+pub unsafe trait SerDeDelta<'a> {
+    const DATA_FORMAT: DataFormat;
+    /// Reads a list.
+    ///
+    /// # Safety
+    /// The buffer must contain a valid list at `pos`.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self
+    where
+        Self: Sized;
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self>
+    where
+        Self: Sized;
+    /// Writes a list and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj)` bytes available starting from `pos`.
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize;
+    fn size(obj: &Self) -> usize;
+}
+
+/// Element types of an [`IterField`] (a list that is written directly from an iterator, using
+/// the same format as a `Vec<T>`).
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+pub unsafe trait SerDeIter<'a>: Sized + 'a {
+    const DATA_FORMAT: DataFormat;
+    /// Reads a list.
+    ///
+    /// # Safety
+    /// The buffer must contain a valid list at `pos`.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> IterField<'a, Self>;
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<IterField<'a, Self>>;
+    /// Writes the elements of a list and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj)` bytes available starting from `pos`.
+    unsafe fn write(obj: &IterField<'a, Self>, p: *mut u8, pos: usize) -> usize;
+    fn size(obj: &IterField<'a, Self>) -> usize;
+}
+
+/// Strings and vectors of strings that can be stored as indexes in the string table of a message
+/// (`#[flat_message_options(string_table = true)]`).
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+pub unsafe trait SerDeStringRef<'a> {
+    const DATA_FORMAT: DataFormat;
+    /// Adds the strings of `obj` to the string table.
+    fn collect<'t>(obj: &'t Self, table: &mut StringTable<'t>);
+    /// Reads a value (`strings` are the strings from the string table).
+    ///
+    /// # Safety
+    /// The buffer must contain a valid value at `pos` and every index must be valid.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize, strings: &[&'a str]) -> Self
+    where
+        Self: Sized;
+    fn from_buffer(buf: &'a [u8], pos: usize, strings: &[&'a str]) -> Option<Self>
+    where
+        Self: Sized;
+    /// Writes a value and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj, table)` bytes available starting from `pos` and the
+    /// strings of `obj` must have been added to `table`.
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize, table: &StringTable) -> usize;
+    fn size(obj: &Self, table: &StringTable) -> usize;
+}
+
+/// Values that are serialized by a field adapter (`#[flat_message_item(with = "path", format = "...")]`).
+/// `A` holds the functions of the adapter (see [`FieldAdapter`]).
+///
+/// # Safety
+/// `write` must write exactly `size` bytes and `from_buffer` must only return values that were validated.
+pub unsafe trait SerDeWith<'a, A>: Sized {
+    /// Reads a value.
+    ///
+    /// # Safety
+    /// The buffer must contain a valid value at `pos`.
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize, adapter: A) -> Self;
+    fn from_buffer(buf: &'a [u8], pos: usize, adapter: A) -> Option<Self>;
+    /// Writes a value and returns the position right after it.
+    ///
+    /// # Safety
+    /// `p` must have at least `size(obj, adapter)` bytes available starting from `pos`.
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize, adapter: A) -> usize;
+    fn size(obj: &Self, adapter: A) -> usize;
+}
+
+/// A type that can be used as a field of a structure without implementing the (unsafe) serialization
+/// traits. The value is written and read through cursors that check every access, and it is stored
+/// in the same way as a buffer (`&[u8]`) or as a string (`&str`), as described by `FORMAT`.
+///
+/// A field of this type (or an `Option<T>` / `Vec<T>` of it) does not need any attribute - a field
+/// whose type is not known by the derive is stored with the methods of this trait (and in the format
+/// given by `FORMAT`).
+///
+/// ```rust
+/// use flat_message::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Money {
+///     cents: i64,
+///     currency: [u8; 3],
+/// }
+///
+/// impl FlatField<'_> for Money {
+///     fn size(&self) -> usize {
+///         11
+///     }
+///     fn encode(&self, writer: &mut FieldWriter) {
+///         writer.write_i64(self.cents);
+///         writer.write_bytes(&self.currency);
+///     }
+///     fn decode(reader: &mut FieldReader) -> Result<Self, Error> {
+///         Ok(Money { cents: reader.read_i64()?, currency: reader.read_array()? })
+///     }
+/// }
+///
+/// #[derive(Debug, PartialEq, FlatMessage)]
+/// struct Invoice {
+///     total: Money,
+///     items: Vec<Money>,
+///     discount: Option<Money>,
+/// }
+///
+/// let invoice = Invoice {
+///     total: Money { cents: 1500, currency: *b"EUR" },
+///     items: vec![Money { cents: 1000, currency: *b"EUR" }, Money { cents: 500, currency: *b"EUR" }],
+///     discount: None,
+/// };
+/// let mut storage = Storage::default();
+/// invoice.serialize_to(&mut storage, Config::default()).unwrap();
+/// assert_eq!(Invoice::deserialize_from(&storage).unwrap(), invoice);
+/// ```
+#[diagnostic::on_unimplemented(
+    message = "the type `{Self}` can not be used as a field",
+    note = "implement `FlatField` for `{Self}`, or describe the field with #[flat_message_item(...)] (for example `kind = struct`)"
+)]
+pub trait FlatField<'a>: Sized {
+    /// How the value is stored.
+    const FORMAT: FieldFormat = FieldFormat::Bytes;
+    /// Returns the number of bytes written by `encode`.
+    fn size(&self) -> usize;
+    /// Writes the value (exactly `size()` bytes).
+    fn encode(&self, writer: &mut FieldWriter);
+    /// Reads a value (returns an error, usually `Error::InvalidFieldValue`, if the data is not valid).
+    fn decode(reader: &mut FieldReader<'a>) -> Result<Self, crate::Error>;
+}
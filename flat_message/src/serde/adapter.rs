@@ -0,0 +1,413 @@
+use super::{SerDe, SerDeSlice, SerDeWith};
+use crate::size;
+use crate::Error;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ptr;
+
+// The `size` function of an adapter is called more than once for the same value (when the size of
+// the message is computed and again when the value is written, or when a nested structure computes
+// its own size). While a message is serialized, the first result for every value is recorded and
+// reused, so that a `size` function that does not always return the same value can not change the
+// layout of the message after the buffer was allocated.
+
+thread_local! {
+    static SIZES: RefCell<Option<HashMap<(usize, usize), usize>>> = const { RefCell::new(None) };
+}
+
+/// Records the sizes returned by field adapters while a message is serialized (see `serialize_to`).
+/// The previous state is restored when the guard is dropped.
+#[doc(hidden)]
+pub struct AdapterSizes(Option<HashMap<(usize, usize), usize>>);
+
+impl AdapterSizes {
+    #[inline(always)]
+    pub fn enter() -> AdapterSizes {
+        AdapterSizes(SIZES.with(|s| s.replace(Some(HashMap::new()))))
+    }
+}
+
+impl Drop for AdapterSizes {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let previous = self.0.take();
+        SIZES.with(|s| *s.borrow_mut() = previous);
+    }
+}
+
+/// How the value of a [`FlatField`](crate::FlatField) or of a field adapter is stored
+/// (`#[flat_message_item(format = "...")]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldFormat {
+    /// the same format as a `&[u8]` / `Vec<u8>` (a list of values is stored as a `Vec<&[u8]>`)
+    Bytes,
+    /// the same format as a `&str` / `String` (a list of values is stored as a `Vec<&str>`). The
+    /// written bytes must be a valid UTF-8 string.
+    String,
+}
+
+/// The functions of a field adapter (`#[flat_message_item(with = "path")]`) or of a [`FlatField`](crate::FlatField).
+/// It is created by the derive from the `path::size`, `path::write` and `path::read` functions.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct FieldAdapter<S, W, R> {
+    pub format: FieldFormat,
+    pub size: S,
+    pub write: W,
+    pub read: R,
+}
+
+/// The functions of a field adapter used for every element of a `Vec<T>` field.
+#[doc(hidden)]
+#[derive(Clone, Copy)]
+pub struct FieldListAdapter<S, W, R>(pub FieldAdapter<S, W, R>);
+
+/// A cursor used by a [`FlatField`](crate::FlatField) or a field adapter (`#[flat_message_item(with = "...")]`) to write a value.
+///
+/// The adapter must write exactly the number of bytes returned by its `size` function. Writing more
+/// bytes panics (the writer never writes outside of the space reserved for the value).
+pub struct FieldWriter<'b> {
+    buf: &'b mut [u8],
+    pos: usize,
+}
+
+macro_rules! WRITE_NUMBER {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Writes a `", stringify!($t), "` value (little endian).")]
+        #[inline(always)]
+        pub fn $name(&mut self, value: $t) {
+            self.write_bytes(&value.to_le_bytes());
+        }
+    };
+}
+
+impl FieldWriter<'_> {
+    /// Writes a buffer.
+    #[inline(always)]
+    pub fn write_bytes(&mut self, data: &[u8]) {
+        let end = self.pos + data.len();
+        let Some(dest) = self.buf.get_mut(self.pos..end) else {
+            panic!(
+                "A field adapter wrote more than the {} bytes returned by its `size` function !",
+                self.buf.len()
+            );
+        };
+        dest.copy_from_slice(data);
+        self.pos = end;
+    }
+    /// Writes a string (without its length).
+    #[inline(always)]
+    pub fn write_str(&mut self, s: &str) {
+        self.write_bytes(s.as_bytes());
+    }
+    WRITE_NUMBER!(write_u8, u8);
+    WRITE_NUMBER!(write_u16, u16);
+    WRITE_NUMBER!(write_u32, u32);
+    WRITE_NUMBER!(write_u64, u64);
+    WRITE_NUMBER!(write_u128, u128);
+    WRITE_NUMBER!(write_i8, i8);
+    WRITE_NUMBER!(write_i16, i16);
+    WRITE_NUMBER!(write_i32, i32);
+    WRITE_NUMBER!(write_i64, i64);
+    WRITE_NUMBER!(write_i128, i128);
+    WRITE_NUMBER!(write_f32, f32);
+    WRITE_NUMBER!(write_f64, f64);
+    /// Returns the number of bytes written so far.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+    /// Returns the number of bytes that still have to be written.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+}
+
+/// A cursor used by a [`FlatField`](crate::FlatField) or a field adapter (`#[flat_message_item(with = "...")]`) to read a value.
+///
+/// The reader only contains the bytes of the value. Every method returns `Err(Error::InvalidFieldValue)`
+/// if there are not enough bytes left, so an adapter can use the `?` operator.
+pub struct FieldReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+macro_rules! READ_NUMBER {
+    ($name:ident, $t:ty) => {
+        #[doc = concat!("Reads a `", stringify!($t), "` value (little endian).")]
+        #[inline(always)]
+        pub fn $name(&mut self) -> Result<$t, Error> {
+            Ok(<$t>::from_le_bytes(self.read_array()?))
+        }
+    };
+}
+
+impl<'a> FieldReader<'a> {
+    /// Reads `len` bytes.
+    #[inline(always)]
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let end = self.pos.checked_add(len).ok_or(Error::InvalidFieldValue)?;
+        let data = self.buf.get(self.pos..end).ok_or(Error::InvalidFieldValue)?;
+        self.pos = end;
+        Ok(data)
+    }
+    /// Reads an array of `N` bytes.
+    #[inline(always)]
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        self.read_bytes(N)?.try_into().map_err(|_| Error::InvalidFieldValue)
+    }
+    /// Reads a string of `len` bytes (returns an error if it is not a valid UTF-8 string).
+    #[inline(always)]
+    pub fn read_str(&mut self, len: usize) -> Result<&'a str, Error> {
+        let pos = self.pos;
+        let s = std::str::from_utf8(self.read_bytes(len)?).map_err(|_| Error::InvalidFieldValue);
+        if s.is_err() {
+            self.pos = pos;
+        }
+        s
+    }
+    /// Reads all the bytes that are left.
+    #[inline(always)]
+    pub fn read_rest(&mut self) -> &'a [u8] {
+        let data = &self.buf[self.pos..];
+        self.pos = self.buf.len();
+        data
+    }
+    /// Reads all the bytes that are left as a string (returns an error if it is not a valid UTF-8 string).
+    #[inline(always)]
+    pub fn read_rest_str(&mut self) -> Result<&'a str, Error> {
+        self.read_str(self.remaining())
+    }
+    READ_NUMBER!(read_u8, u8);
+    READ_NUMBER!(read_u16, u16);
+    READ_NUMBER!(read_u32, u32);
+    READ_NUMBER!(read_u64, u64);
+    READ_NUMBER!(read_u128, u128);
+    READ_NUMBER!(read_i8, i8);
+    READ_NUMBER!(read_i16, i16);
+    READ_NUMBER!(read_i32, i32);
+    READ_NUMBER!(read_i64, i64);
+    READ_NUMBER!(read_i128, i128);
+    READ_NUMBER!(read_f32, f32);
+    READ_NUMBER!(read_f64, f64);
+    /// Returns the number of bytes read so far.
+    #[inline(always)]
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+    /// Returns the number of bytes that are left.
+    #[inline(always)]
+    pub fn remaining(&self) -> usize {
+        self.buf.len() - self.pos
+    }
+    /// Returns `true` if all the bytes were read.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.pos == self.buf.len()
+    }
+}
+
+impl<S, W, R> FieldAdapter<S, W, R> {
+    /// Returns the content of a value (without its size prefix).
+    #[inline(always)]
+    fn data<'a>(&self, buf: &'a [u8], pos: usize) -> Option<&'a [u8]> {
+        match self.format {
+            FieldFormat::Bytes => SerDeSlice::from_buffer(buf, pos),
+            FieldFormat::String => Some(<&str as SerDe>::from_buffer(buf, pos)?.as_bytes()),
+        }
+    }
+    #[inline(always)]
+    fn read_value<'a, T>(&self, data: &'a [u8]) -> Option<T>
+    where
+        R: Fn(&mut FieldReader<'a>) -> Result<T, Error>,
+    {
+        (self.read)(&mut FieldReader { buf: data, pos: 0 }).ok()
+    }
+    #[inline(always)]
+    fn read_value_unchecked<'a, T>(&self, data: &'a [u8]) -> T
+    where
+        R: Fn(&mut FieldReader<'a>) -> Result<T, Error>,
+    {
+        match self.read_value(data) {
+            Some(value) => value,
+            None => panic!("A field adapter could not read its value (use `deserialize_from` to validate the message) !"),
+        }
+    }
+    /// Returns the size of the content of a value (the first result of the adapter's `size` function
+    /// for this value, if a message is being serialized).
+    #[inline(always)]
+    fn content_len<T>(&self, obj: &T) -> usize
+    where
+        S: Fn(&T) -> usize,
+    {
+        let key = (obj as *const T as usize, std::mem::size_of::<T>());
+        if let Some(len) = SIZES.with(|s| s.borrow().as_ref().and_then(|sizes| sizes.get(&key).copied())) {
+            return len;
+        }
+        // the adapter is called without borrowing the state (it can serialize another message)
+        let len = (self.size)(obj);
+        SIZES.with(|s| {
+            if let Some(sizes) = s.borrow_mut().as_mut() {
+                sizes.insert(key, len);
+            }
+        });
+        len
+    }
+    #[inline(always)]
+    fn value_len<T>(&self, obj: &T) -> usize
+    where
+        S: Fn(&T) -> usize,
+    {
+        let len = self.content_len(obj);
+        size::len(len, size::Format::U8withExtension) + len
+    }
+    /// Writes a value (its size followed by the content written by the adapter).
+    ///
+    /// # Safety
+    /// `p` must have at least `self.value_len(obj)` bytes available starting from `pos`.
+    #[inline(always)]
+    unsafe fn write_value<T>(&self, obj: &T, p: *mut u8, pos: usize) -> usize
+    where
+        S: Fn(&T) -> usize,
+        W: Fn(&T, &mut FieldWriter),
+    {
+        let len = self.content_len(obj);
+        unsafe {
+            let pos = pos + size::write(p, pos, len, size::Format::U8withExtension);
+            let mut writer = FieldWriter {
+                buf: std::slice::from_raw_parts_mut(p.add(pos), len),
+                pos: 0,
+            };
+            (self.write)(obj, &mut writer);
+            assert!(
+                writer.pos == len,
+                "A field adapter wrote {} bytes instead of the {} bytes returned by its `size` function !",
+                writer.pos,
+                len
+            );
+            assert!(
+                self.format == FieldFormat::Bytes || std::str::from_utf8(writer.buf).is_ok(),
+                "A field adapter with the 'string' format wrote an invalid UTF-8 string !"
+            );
+            pos + len
+        }
+    }
+}
+
+/// Returns the position right after `data` (a part of `buf`).
+#[inline(always)]
+fn end_of(buf: &[u8], data: &[u8]) -> usize {
+    data.as_ptr() as usize - buf.as_ptr() as usize + data.len()
+}
+
+/// Offset of the first value of a list stored as a `Vec<&[u8]>` (a u32 count - see `nested_lists.rs`).
+const BYTES_LIST_OFFSET: usize = 4;
+
+// A value is stored as a buffer (`&[u8]`) or as a string (`&str`) - the adapter only writes and reads
+// the content, through a cursor that is limited to the space of the value.
+unsafe impl<'a, T, S, W, R> SerDeWith<'a, FieldAdapter<S, W, R>> for T
+where
+    S: Fn(&T) -> usize,
+    W: Fn(&T, &mut FieldWriter),
+    R: Fn(&mut FieldReader<'a>) -> Result<T, Error>,
+{
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize, adapter: FieldAdapter<S, W, R>) -> Self {
+        adapter.read_value_unchecked(SerDeSlice::from_buffer_unchecked(buf, pos))
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize, adapter: FieldAdapter<S, W, R>) -> Option<Self> {
+        adapter.read_value(adapter.data(buf, pos)?)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize, adapter: FieldAdapter<S, W, R>) -> usize {
+        adapter.write_value(obj, p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Self, adapter: FieldAdapter<S, W, R>) -> usize {
+        adapter.value_len(obj)
+    }
+}
+
+// A list of values is stored as a `Vec<&[u8]>` or as a `Vec<&str>` (the values are stored in the same
+// way as above, after the number of values).
+unsafe impl<'a, T, S, W, R> SerDeWith<'a, FieldListAdapter<S, W, R>> for Vec<T>
+where
+    S: Fn(&T) -> usize,
+    W: Fn(&T, &mut FieldWriter),
+    R: Fn(&mut FieldReader<'a>) -> Result<T, Error>,
+{
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize, adapter: FieldListAdapter<S, W, R>) -> Self {
+        let adapter = adapter.0;
+        unsafe {
+            let (count, mut pos) = match adapter.format {
+                FieldFormat::Bytes => (
+                    ptr::read_unaligned(buf.as_ptr().add(pos) as *const u32) as usize,
+                    pos + BYTES_LIST_OFFSET,
+                ),
+                FieldFormat::String => {
+                    let (count, slen) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+                    (count, pos + slen)
+                }
+            };
+            let mut result = Vec::with_capacity(count);
+            for _ in 0..count {
+                let data: &[u8] = SerDeSlice::from_buffer_unchecked(buf, pos);
+                pos = end_of(buf, data);
+                result.push(adapter.read_value_unchecked(data));
+            }
+            result
+        }
+    }
+    fn from_buffer(buf: &'a [u8], pos: usize, adapter: FieldListAdapter<S, W, R>) -> Option<Self> {
+        let adapter = adapter.0;
+        let (count, mut pos) = match adapter.format {
+            FieldFormat::Bytes => {
+                let count = buf.get(pos..pos.checked_add(4)?)?;
+                (u32::from_le_bytes(count.try_into().ok()?) as usize, pos + BYTES_LIST_OFFSET)
+            }
+            FieldFormat::String => {
+                let (count, slen) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+                (count, pos + slen)
+            }
+        };
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<T>(count)?;
+        // each value needs at least one byte (the size prefix)
+        if count.checked_add(pos)? > buf.len() {
+            return None;
+        }
+        let mut result = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let data = adapter.data(buf, pos)?;
+            pos = end_of(buf, data);
+            result.push(adapter.read_value(data)?);
+        }
+        Some(result)
+    }
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize, adapter: FieldListAdapter<S, W, R>) -> usize {
+        let adapter = adapter.0;
+        unsafe {
+            let mut pos = match adapter.format {
+                FieldFormat::Bytes => {
+                    ptr::write_unaligned(p.add(pos) as *mut u32, obj.len() as u32);
+                    pos + BYTES_LIST_OFFSET
+                }
+                FieldFormat::String => pos + size::write(p, pos, obj.len(), size::Format::U8withExtension),
+            };
+            for value in obj.iter() {
+                pos = adapter.write_value(value, p, pos);
+            }
+            pos
+        }
+    }
+    fn size(obj: &Self, adapter: FieldListAdapter<S, W, R>) -> usize {
+        let adapter = adapter.0;
+        let count_len = match adapter.format {
+            FieldFormat::Bytes => BYTES_LIST_OFFSET,
+            FieldFormat::String => size::len(obj.len(), size::Format::U8withExtension),
+        };
+        count_len + obj.iter().map(|value| adapter.value_len(value)).sum::<usize>()
+    }
+}
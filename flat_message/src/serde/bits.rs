@@ -0,0 +1,320 @@
+use super::SerDe;
+use crate::size;
+use common::data_format::DataFormat;
+use std::ptr;
+
+// Layout of a bit-packed list of booleans:
+// - number of bits (size::Format::U8withExtension)
+// - (count + 7) / 8 bytes (bit `i` is stored in byte `i / 8`, starting with the least significant bit)
+// All the bits after the last element must be 0.
+
+#[inline(always)]
+fn bytes_len(count: usize) -> usize {
+    count.div_ceil(8)
+}
+
+#[inline(always)]
+fn is_set(bytes: &[u8], index: usize) -> bool {
+    bytes[index >> 3] & (1 << (index & 7)) != 0
+}
+
+#[inline(always)]
+fn count_set(bytes: &[u8]) -> usize {
+    bytes.iter().map(|b| b.count_ones() as usize).sum()
+}
+
+/// The bytes are valid if there are exactly `count` bits (rounded up to a byte) and all
+/// the bits after the last element are 0.
+#[inline(always)]
+fn is_valid(bytes: &[u8], count: usize) -> bool {
+    if bytes.len() != bytes_len(count) {
+        return false;
+    }
+    let extra_bits = count & 7;
+    extra_bits == 0 || (bytes[bytes.len() - 1] >> extra_bits) == 0
+}
+
+/// A zero-copy view over a list of booleans stored as bits (8 booleans per byte).
+///
+/// A `BitSlice` uses the same binary format as a [`BitVec`] (the two types are interchangeable).
+/// Both have their own data format, so a bit-packed list can not be read as a `Vec<bool>` / `&[bool]`
+/// (and vice versa).
+///
+/// # Example
+/// ```
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Features {
+///     enabled: flat_message::BitVec,
+/// }
+/// #[derive(FlatMessage)]
+/// struct FeaturesView<'a> {
+///     enabled: flat_message::BitSlice<'a>,
+/// }
+///
+/// let mut storage = Storage::default();
+/// let enabled = BitVec::from(&[true, false, true, true][..]);
+/// Features { enabled }.serialize_to(&mut storage, Config::default()).unwrap();
+/// let f = FeaturesView::deserialize_from(&storage).unwrap();
+/// assert_eq!(f.enabled.len(), 4);
+/// assert_eq!(f.enabled.get(1), Some(false));
+/// assert_eq!(f.enabled.count_ones(), 3);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BitSlice<'a> {
+    len: usize,
+    bytes: &'a [u8],
+}
+
+impl<'a> BitSlice<'a> {
+    /// Creates a new view from the number of bits and the bytes that store them.
+    /// Returns `None` if `bytes` does not have exactly `(len + 7) / 8` bytes or if it has bits set
+    /// after the last element.
+    pub fn new(len: usize, bytes: &'a [u8]) -> Option<Self> {
+        if !is_valid(bytes, len) {
+            return None;
+        }
+        Some(Self { len, bytes })
+    }
+    /// Number of bits.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// The bytes that store the bits (bit `i` is stored in byte `i / 8`, starting with the least significant bit).
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+    /// Returns the bit at `index` or `None` if `index` is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        if index < self.len {
+            Some(is_set(self.bytes, index))
+        } else {
+            None
+        }
+    }
+    /// Number of bits that are set.
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        count_set(self.bytes)
+    }
+    /// Number of bits that are not set.
+    #[inline(always)]
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+    /// Iterates through all the bits.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + 'a {
+        let bytes = self.bytes;
+        (0..self.len).map(move |index| is_set(bytes, index))
+    }
+    /// Converts the view into a vector of booleans.
+    pub fn to_vec(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+    /// Creates an owned copy of the bits.
+    pub fn to_bit_vec(&self) -> BitVec {
+        BitVec {
+            len: self.len,
+            bytes: self.bytes.to_vec(),
+        }
+    }
+}
+
+/// A list of booleans stored as bits (8 booleans per byte). See [`BitSlice`] for the zero-copy counterpart.
+#[derive(Debug, Clone, PartialEq, Eq, Default, Hash)]
+pub struct BitVec {
+    len: usize,
+    bytes: Vec<u8>,
+}
+
+impl BitVec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Creates an empty list that can store `bits` booleans without reallocating.
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            len: 0,
+            bytes: Vec::with_capacity(bytes_len(bits)),
+        }
+    }
+    /// Creates a list with `len` bits that are all set to `value`.
+    pub fn repeat(value: bool, len: usize) -> Self {
+        let mut bytes = vec![if value { 0xFF } else { 0 }; bytes_len(len)];
+        if value && len & 7 != 0 {
+            bytes[len >> 3] = (1 << (len & 7)) - 1;
+        }
+        Self { len, bytes }
+    }
+    /// Number of bits.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Adds a bit at the end of the list.
+    pub fn push(&mut self, value: bool) {
+        if self.len & 7 == 0 {
+            self.bytes.push(0);
+        }
+        if value {
+            self.bytes[self.len >> 3] |= 1 << (self.len & 7);
+        }
+        self.len += 1;
+    }
+    /// Returns the bit at `index` or `None` if `index` is out of bounds.
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        self.as_bit_slice().get(index)
+    }
+    /// Sets the bit at `index`.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn set(&mut self, index: usize, value: bool) {
+        assert!(index < self.len, "index {index} is out of bounds (len is {})", self.len);
+        if value {
+            self.bytes[index >> 3] |= 1 << (index & 7);
+        } else {
+            self.bytes[index >> 3] &= !(1 << (index & 7));
+        }
+    }
+    /// Number of bits that are set.
+    #[inline(always)]
+    pub fn count_ones(&self) -> usize {
+        count_set(&self.bytes)
+    }
+    /// Number of bits that are not set.
+    #[inline(always)]
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+    /// Iterates through all the bits.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        self.as_bit_slice().iter()
+    }
+    /// The bytes that store the bits (bit `i` is stored in byte `i / 8`, starting with the least significant bit).
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+    #[inline(always)]
+    pub fn as_bit_slice(&self) -> BitSlice<'_> {
+        BitSlice {
+            len: self.len,
+            bytes: &self.bytes,
+        }
+    }
+    /// Converts the list into a vector of booleans.
+    pub fn to_vec(&self) -> Vec<bool> {
+        self.iter().collect()
+    }
+}
+
+impl From<&[bool]> for BitVec {
+    fn from(values: &[bool]) -> Self {
+        values.iter().copied().collect()
+    }
+}
+
+impl From<Vec<bool>> for BitVec {
+    fn from(values: Vec<bool>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl From<BitSlice<'_>> for BitVec {
+    fn from(value: BitSlice<'_>) -> Self {
+        value.to_bit_vec()
+    }
+}
+
+impl FromIterator<bool> for BitVec {
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let iter = iter.into_iter();
+        let mut result = BitVec::with_capacity(iter.size_hint().0);
+        for value in iter {
+            result.push(value);
+        }
+        result
+    }
+}
+
+impl Extend<bool> for BitVec {
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for value in iter {
+            self.push(value);
+        }
+    }
+}
+
+unsafe impl<'a> SerDe<'a> for BitSlice<'a> {
+    const DATA_FORMAT: DataFormat = DataFormat::Bits;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        unsafe {
+            let (len, buf_len) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+            let start = pos + buf_len;
+            BitSlice {
+                len,
+                bytes: buf.get_unchecked(start..start + bytes_len(len)),
+            }
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let (len, buf_len) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+        crate::limits::check_list_len(len)?;
+        let start = pos + buf_len;
+        let end = start.checked_add(bytes_len(len))?;
+        if end > buf.len() {
+            return None;
+        }
+        BitSlice::new(len, &buf[start..end])
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            let buf_len = size::write(p, pos, obj.len, size::Format::U8withExtension);
+            ptr::copy_nonoverlapping(obj.bytes.as_ptr(), p.add(pos + buf_len), obj.bytes.len());
+            pos + buf_len + obj.bytes.len()
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        size::len(obj.len, size::Format::U8withExtension) + obj.bytes.len()
+    }
+}
+
+unsafe impl<'a> SerDe<'a> for BitVec {
+    const DATA_FORMAT: DataFormat = DataFormat::Bits;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        unsafe { BitSlice::from_buffer_unchecked(buf, pos).to_bit_vec() }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let slice = BitSlice::from_buffer(buf, pos)?;
+        crate::limits::allocate::<u8>(slice.bytes.len())?;
+        Some(slice.to_bit_vec())
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe { BitSlice::write(&obj.as_bit_slice(), p, pos) }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        BitSlice::size(&obj.as_bit_slice())
+    }
+}
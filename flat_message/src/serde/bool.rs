@@ -15,6 +15,9 @@ unsafe impl SerDe<'_> for bool {
         }
     }
     fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        if pos >= buf.len() {
+            return None;
+        }
         unsafe {
             let ptr = buf.as_ptr().add(pos);
             match *ptr {
@@ -50,7 +53,8 @@ unsafe impl<'a> SerDeSlice<'a> for bool {
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
         let (len, buf_len) =
             size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
-        let end = pos + buf_len + len;
+        crate::limits::check_list_len(len)?;
+        let end = (pos + buf_len).checked_add(len)?;
         if end > buf.len() {
             None
         } else {
@@ -67,16 +71,16 @@ unsafe impl<'a> SerDeSlice<'a> for bool {
     }
     #[inline(always)]
     unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
-        let len = obj.len() as u32;
+        let len = obj.len();
         unsafe {
             let buf_len = size::write(p, pos, len, size::Format::U8withExtension);
             std::ptr::copy_nonoverlapping(obj.as_ptr() as *mut u8, p.add(pos + buf_len), obj.len());
-            pos + buf_len + len as usize
+            pos + buf_len + len
         }
     }
     #[inline(always)]
     fn size(obj: &[Self]) -> usize {
-        size::len(obj.len() as u32, size::Format::U8withExtension) + obj.len()
+        size::len(obj.len(), size::Format::U8withExtension) + obj.len()
     }
 }
 
@@ -90,6 +94,7 @@ unsafe impl SerDeVec<'_> for bool {
     #[inline(always)]
     fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
         let res: &[bool] = SerDeSlice::from_buffer(buf, pos)?;
+        crate::limits::allocate::<Self>(res.len())?;
         Some(res.to_vec())
     }
     #[inline(always)]
@@ -98,6 +103,6 @@ unsafe impl SerDeVec<'_> for bool {
     }
     #[inline(always)]
     fn size(obj: &Vec<Self>) -> usize {
-        size::len(obj.len() as u32, size::Format::U8withExtension) + obj.len()
+        size::len(obj.len(), size::Format::U8withExtension) + obj.len()
     }
 }
@@ -17,9 +17,17 @@ macro_rules! IMPLEMENT_SERDE_FOR_SLICE {
             fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
                 let (count, size_len) =
                     size::read(buf.as_ptr(), pos, buf.len(), size::Format::$align_method)?;
-                let end = pos + size_len + count * std::mem::size_of::<$t>();
+                crate::limits::check_list_len(count)?;
+                let end = count
+                    .checked_mul(std::mem::size_of::<$t>())
+                    .and_then(|sz| sz.checked_add(pos + size_len))?;
                 if end > buf.len() {
                     None
+                } else if !(buf.as_ptr() as usize + pos + size_len)
+                    .is_multiple_of(std::mem::align_of::<$t>())
+                {
+                    // the values are referenced directly from the buffer
+                    None
                 } else {
                     Some(unsafe {
                         std::slice::from_raw_parts(
@@ -31,7 +39,7 @@ macro_rules! IMPLEMENT_SERDE_FOR_SLICE {
             }
             #[inline(always)]
             unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
-                let len = obj.len() as u32;
+                let len = obj.len();
                 unsafe {
                     let size_len = size::write(p, pos, len, size::Format::$align_method);
                     std::ptr::copy_nonoverlapping(
@@ -39,12 +47,12 @@ macro_rules! IMPLEMENT_SERDE_FOR_SLICE {
                         p.add(pos + size_len),
                         obj.len() * std::mem::size_of::<$t>(),
                     );
-                    pos + size_len + (len as usize) * std::mem::size_of::<$t>()
+                    pos + size_len + len * std::mem::size_of::<$t>()
                 }
             }
             #[inline(always)]
             fn size(obj: &[Self]) -> usize {
-                size::len(obj.len() as u32, size::Format::$align_method)
+                size::len(obj.len(), size::Format::$align_method)
                     + obj.len() * std::mem::size_of::<$t>()
             }
         }
@@ -63,6 +71,7 @@ macro_rules! IMPLEMENT_SERDE_FOR_VECTOR {
             #[inline(always)]
             fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
                 let res: &[$t] = SerDeSlice::from_buffer(buf, pos)?;
+                crate::limits::allocate::<Self>(res.len())?;
                 Some(res.to_vec())
             }
             #[inline(always)]
@@ -71,7 +80,7 @@ macro_rules! IMPLEMENT_SERDE_FOR_VECTOR {
             }
             #[inline(always)]
             fn size(obj: &Vec<Self>) -> usize {
-                size::len(obj.len() as u32, size::Format::$align_method)
+                size::len(obj.len(), size::Format::$align_method)
                     + obj.len() * std::mem::size_of::<$t>()
             }
         }
@@ -17,7 +17,8 @@ macro_rules! IMPLEMENT_SERDE_FOR_SLICE {
             fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
                 let (len, buf_len) =
                     size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
-                let end = pos + buf_len + len;
+                crate::limits::check_list_len(len)?;
+                let end = (pos + buf_len).checked_add(len)?;
                 if end > buf.len() {
                     None
                 } else {
@@ -31,7 +32,7 @@ macro_rules! IMPLEMENT_SERDE_FOR_SLICE {
             }
             #[inline(always)]
             unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
-                let len = obj.len() as u32;
+                let len = obj.len();
                 unsafe {
                     let buf_len = size::write(p, pos, len, size::Format::U8withExtension);
                     std::ptr::copy_nonoverlapping(
@@ -39,12 +40,12 @@ macro_rules! IMPLEMENT_SERDE_FOR_SLICE {
                         p.add(pos + buf_len),
                         obj.len(),
                     );
-                    pos + buf_len + len as usize
+                    pos + buf_len + len
                 }
             }
             #[inline(always)]
             fn size(obj: &[Self]) -> usize {
-                size::len(obj.len() as u32, size::Format::U8withExtension) + obj.len()
+                size::len(obj.len(), size::Format::U8withExtension) + obj.len()
             }
         }
     };
@@ -62,6 +63,7 @@ macro_rules! IMPLEMENT_SERDE_FOR_VECTOR {
             #[inline(always)]
             fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
                 let res: &[$t] = SerDeSlice::from_buffer(buf, pos)?;
+                crate::limits::allocate::<Self>(res.len())?;
                 Some(res.to_vec())
             }
             #[inline(always)]
@@ -70,7 +72,7 @@ macro_rules! IMPLEMENT_SERDE_FOR_VECTOR {
             }
             #[inline(always)]
             fn size(obj: &Vec<Self>) -> usize {
-                size::len(obj.len() as u32, size::Format::U8withExtension) + obj.len()
+                size::len(obj.len(), size::Format::U8withExtension) + obj.len()
             }
         }
     };
@@ -0,0 +1,83 @@
+use super::{SerDe, SerDeSlice, SerDeVec};
+use crate::shared::{share, share_str, SharedStr};
+use crate::size;
+use bytes::Bytes;
+use common::data_format::DataFormat;
+
+// `Bytes` is serialized like a `&[u8]` and `SharedStr` like a `&str` (the types are interchangeable).
+// The values are views into the buffer of the message if it is deserialized from a `SharedStorage`
+// (otherwise the content is copied).
+
+unsafe impl<'a> SerDe<'a> for Bytes {
+    const DATA_FORMAT: DataFormat = DataFormat::U8;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        let data: &[u8] = SerDeSlice::from_buffer_unchecked(buf, pos);
+        share(data).unwrap_or_default()
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let data: &[u8] = SerDeSlice::from_buffer(buf, pos)?;
+        share(data)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        <u8 as SerDeSlice>::write(obj, p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        <u8 as SerDeSlice>::size(obj)
+    }
+}
+
+unsafe impl<'a> SerDe<'a> for SharedStr {
+    const DATA_FORMAT: DataFormat = DataFormat::String;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        let s: &str = SerDe::from_buffer_unchecked(buf, pos);
+        share_str(s).unwrap_or_default()
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let s: &str = SerDe::from_buffer(buf, pos)?;
+        share_str(s)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        SerDe::write(&obj.as_str(), p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        <&str as SerDe>::size(&obj.as_str())
+    }
+}
+
+unsafe impl<'a> SerDeVec<'a> for SharedStr {
+    const DATA_FORMAT: DataFormat = DataFormat::String;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Vec<Self> {
+        let list: Vec<&str> = SerDeVec::from_buffer_unchecked(buf, pos);
+        list.into_iter().map(|s| share_str(s).unwrap_or_default()).collect()
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Vec<Self>> {
+        let list: Vec<&str> = SerDeVec::from_buffer(buf, pos)?;
+        list.into_iter().map(share_str).collect()
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+        // same format as a Vec<&str>
+        unsafe {
+            let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+            for s in obj.iter() {
+                pos = SerDe::write(&s.as_str(), p, pos);
+            }
+            pos
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Vec<Self>) -> usize {
+        size::len(obj.len(), size::Format::U8withExtension)
+            + obj.iter().map(|s| <&str as SerDe>::size(&s.as_str())).sum::<usize>()
+    }
+}
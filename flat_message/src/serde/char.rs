@@ -0,0 +1,84 @@
+use super::SerDe;
+use super::SerDeSlice;
+use super::SerDeVec;
+use crate::size;
+use common::data_format::DataFormat;
+use std::ptr;
+
+unsafe impl SerDe<'_> for char {
+    const DATA_FORMAT: DataFormat = DataFormat::Char;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+        unsafe {
+            let ptr = buf.as_ptr().add(pos) as *const u32;
+            char::from_u32_unchecked(ptr::read_unaligned(ptr))
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        let value: u32 = SerDe::from_buffer(buf, pos)?;
+        char::from_u32(value)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            ptr::write_unaligned(p.add(pos) as *mut u32, *obj as u32);
+            pos + 4
+        }
+    }
+    #[inline(always)]
+    fn size(_: &Self) -> usize {
+        4
+    }
+}
+
+// a char has the same size and alignment as an u32, so a slice of chars
+// uses the same layout as a slice of u32 values
+unsafe impl<'a> SerDeSlice<'a> for char {
+    const DATA_FORMAT: DataFormat = DataFormat::Char;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> &'a [Self] {
+        let res: &[u32] = SerDeSlice::from_buffer_unchecked(buf, pos);
+        std::slice::from_raw_parts(res.as_ptr() as *const char, res.len())
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
+        let res: &[u32] = SerDeSlice::from_buffer(buf, pos)?;
+        if res.iter().any(|&value| char::from_u32(value).is_none()) {
+            return None;
+        }
+        Some(unsafe { std::slice::from_raw_parts(res.as_ptr() as *const char, res.len()) })
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
+        let values = std::slice::from_raw_parts(obj.as_ptr() as *const u32, obj.len());
+        SerDeSlice::write(values, p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &[Self]) -> usize {
+        size::len(obj.len(), size::Format::U32) + obj.len() * 4
+    }
+}
+
+unsafe impl SerDeVec<'_> for char {
+    const DATA_FORMAT: DataFormat = DataFormat::Char;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+        let res: &[char] = SerDeSlice::from_buffer_unchecked(buf, pos);
+        res.to_vec()
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+        let res: &[char] = SerDeSlice::from_buffer(buf, pos)?;
+        crate::limits::allocate::<Self>(res.len())?;
+        Some(res.to_vec())
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+        SerDeSlice::write(obj.as_slice(), p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Vec<Self>) -> usize {
+        size::len(obj.len(), size::Format::U32) + obj.len() * 4
+    }
+}
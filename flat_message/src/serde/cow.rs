@@ -0,0 +1,65 @@
+use super::{SerDe, SerDeSlice, SerDeVec};
+use crate::Storage;
+use common::data_format::DataFormat;
+use std::borrow::Cow;
+
+/// Implementation for Cow<str> (same format as &str / String)
+unsafe impl<'a> SerDe<'a> for Cow<'a, str> {
+    const DATA_FORMAT: DataFormat = DataFormat::String;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        Cow::Borrowed(SerDe::from_buffer_unchecked(buf, pos))
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        Some(Cow::Borrowed(SerDe::from_buffer(buf, pos)?))
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        SerDe::write(&obj.as_ref(), p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        <&str as SerDe>::size(&obj.as_ref())
+    }
+}
+
+/// Implementation for Cow<[T]> (same format as &[T] / Vec<T>)
+///
+/// The slice is borrowed from the buffer whenever its elements are properly aligned in memory.
+/// Otherwise (e.g. a buffer that does not start on an aligned address) the elements are
+/// copied into an owned vector.
+unsafe impl<'a, T> SerDe<'a> for Cow<'a, [T]>
+where
+    T: SerDeSlice<'a> + for<'b> SerDeVec<'b> + Clone,
+{
+    const DATA_FORMAT: DataFormat = <T as SerDeSlice<'a>>::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        Cow::Borrowed(SerDeSlice::from_buffer_unchecked(buf, pos))
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        if pos > buf.len() {
+            return None;
+        }
+        // the size prefix keeps the elements aligned, so it is enough to check the start of the field
+        if (buf.as_ptr() as usize + pos).is_multiple_of(std::mem::align_of::<T>()) {
+            Some(Cow::Borrowed(SerDeSlice::from_buffer(buf, pos)?))
+        } else {
+            // only the bytes of this field are copied (into an aligned buffer)
+            let end = crate::validate::validate_slice(buf, pos, buf.len(), Self::DATA_FORMAT, 0).ok()?;
+            crate::limits::allocate::<u8>(end - pos)?;
+            let storage = Storage::from_buffer(&buf[pos..end]);
+            Some(Cow::Owned(SerDeVec::from_buffer(storage.as_slice(), 0)?))
+        }
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        <T as SerDeSlice>::write(obj.as_ref(), p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        <T as SerDeSlice>::size(obj.as_ref())
+    }
+}
@@ -0,0 +1,133 @@
+// Date & time types from the `chrono` and `time` crates.
+// Equivalent types from the two crates share the same data format (for example `chrono::NaiveDate`
+// and `time::Date`), so services that use different crates can exchange values. The exact layout
+// of each data format is described in the "Date and Time" chapter of the book.
+
+#[cfg(feature = "chrono")]
+mod chrono_support;
+#[cfg(feature = "time")]
+mod time_support;
+
+use std::ptr;
+
+pub(super) const DATE_TIME_SIZE: usize = 12;
+pub(super) const OFFSET_DATE_TIME_SIZE: usize = 16;
+pub(super) const DATE_SIZE: usize = 4;
+
+#[inline(always)]
+pub(super) unsafe fn read_date_time(p: *const u8, pos: usize) -> (i64, u32) {
+    unsafe {
+        let secs = ptr::read_unaligned(p.add(pos) as *const i64);
+        let nanos = ptr::read_unaligned(p.add(pos + 8) as *const u32);
+        (secs, nanos)
+    }
+}
+
+#[inline(always)]
+pub(super) unsafe fn write_date_time(p: *mut u8, pos: usize, secs: i64, nanos: u32) {
+    unsafe {
+        ptr::write_unaligned(p.add(pos) as *mut i64, secs);
+        ptr::write_unaligned(p.add(pos + 8) as *mut u32, nanos);
+    }
+}
+
+#[inline(always)]
+pub(super) unsafe fn read_offset_date_time(p: *const u8, pos: usize) -> (i64, u32, i32) {
+    unsafe {
+        let (secs, nanos) = read_date_time(p, pos);
+        let offset = ptr::read_unaligned(p.add(pos + 12) as *const i32);
+        (secs, nanos, offset)
+    }
+}
+
+#[inline(always)]
+pub(super) unsafe fn write_offset_date_time(p: *mut u8, pos: usize, secs: i64, nanos: u32, offset: i32) {
+    unsafe {
+        write_date_time(p, pos, secs, nanos);
+        ptr::write_unaligned(p.add(pos + 12) as *mut i32, offset);
+    }
+}
+
+#[inline(always)]
+pub(super) unsafe fn read_date(p: *const u8, pos: usize) -> i32 {
+    unsafe { ptr::read_unaligned(p.add(pos) as *const i32) }
+}
+
+#[inline(always)]
+pub(super) unsafe fn write_date(p: *mut u8, pos: usize, days: i32) {
+    unsafe { ptr::write_unaligned(p.add(pos) as *mut i32, days) }
+}
+
+// Implements `SerDe` and `SerDeVec` for a date / time type with a fixed size representation.
+// `$read` is an `unsafe fn(*const u8, usize) -> Option<$t>` that validates the value, and
+// `$write` is an `unsafe fn(&$t, *mut u8, usize)`.
+// These types do not have a stable memory layout, so zero-copy slices are not supported.
+macro_rules! IMPLEMENT_SERDE_FOR_DATE_TIME {
+    ($t:ty, $data_format:ident, $size:expr, $read:ident, $write:ident) => {
+        unsafe impl SerDe<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+                $read(buf.as_ptr(), pos).unwrap_unchecked()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+                if pos + $size > buf.len() {
+                    return None;
+                }
+                unsafe { $read(buf.as_ptr(), pos) }
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                $write(obj, p, pos);
+                pos + $size
+            }
+            #[inline(always)]
+            fn size(_: &Self) -> usize {
+                $size
+            }
+        }
+        unsafe impl SerDeVec<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+                let p = buf.as_ptr();
+                let (count, size_len) = size::read_unchecked(p, pos, size::Format::U32);
+                let start = pos + size_len;
+                (0..count)
+                    .map(|index| $read(p, start + index * $size).unwrap_unchecked())
+                    .collect()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+                let p = buf.as_ptr();
+                let (count, size_len) = size::read(p, pos, buf.len(), size::Format::U32)?;
+                crate::limits::check_list_len(count)?;
+                crate::limits::allocate::<Self>(count)?;
+                let start = pos + size_len;
+                let end = count.checked_mul($size).and_then(|sz| sz.checked_add(start))?;
+                if end > buf.len() {
+                    return None;
+                }
+                (0..count)
+                    .map(|index| unsafe { $read(p, start + index * $size) })
+                    .collect()
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+                let size_len = size::write(p, pos, obj.len(), size::Format::U32);
+                let mut pos = pos + size_len;
+                for value in obj {
+                    $write(value, p, pos);
+                    pos += $size;
+                }
+                pos
+            }
+            #[inline(always)]
+            fn size(obj: &Vec<Self>) -> usize {
+                size::len(obj.len(), size::Format::U32) + obj.len() * $size
+            }
+        }
+    };
+}
+pub(super) use IMPLEMENT_SERDE_FOR_DATE_TIME;
@@ -0,0 +1,70 @@
+use super::*;
+use crate::serde::{SerDe, SerDeVec};
+use crate::size;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use common::data_format::DataFormat;
+
+// chrono represents leap seconds with a nanoseconds value between 1_000_000_000 and 1_999_999_999.
+// These values are stored as they are and validated by chrono when deserializing.
+
+#[inline(always)]
+unsafe fn read_utc(p: *const u8, pos: usize) -> Option<DateTime<Utc>> {
+    let (secs, nanos) = read_date_time(p, pos);
+    DateTime::from_timestamp(secs, nanos)
+}
+#[inline(always)]
+unsafe fn write_utc(obj: &DateTime<Utc>, p: *mut u8, pos: usize) {
+    write_date_time(p, pos, obj.timestamp(), obj.timestamp_subsec_nanos());
+}
+
+#[inline(always)]
+unsafe fn read_naive(p: *const u8, pos: usize) -> Option<NaiveDateTime> {
+    let (secs, nanos) = read_date_time(p, pos);
+    Some(DateTime::from_timestamp(secs, nanos)?.naive_utc())
+}
+#[inline(always)]
+unsafe fn write_naive(obj: &NaiveDateTime, p: *mut u8, pos: usize) {
+    let utc = obj.and_utc();
+    write_date_time(p, pos, utc.timestamp(), utc.timestamp_subsec_nanos());
+}
+
+#[inline(always)]
+unsafe fn read_fixed_offset(p: *const u8, pos: usize) -> Option<DateTime<FixedOffset>> {
+    let (secs, nanos, offset) = read_offset_date_time(p, pos);
+    let offset = FixedOffset::east_opt(offset)?;
+    Some(DateTime::from_timestamp(secs, nanos)?.with_timezone(&offset))
+}
+#[inline(always)]
+unsafe fn write_fixed_offset(obj: &DateTime<FixedOffset>, p: *mut u8, pos: usize) {
+    write_offset_date_time(
+        p,
+        pos,
+        obj.timestamp(),
+        obj.timestamp_subsec_nanos(),
+        obj.offset().local_minus_utc(),
+    );
+}
+
+// number of days from 0001-01-01 (CE) to 1970-01-01
+const UNIX_EPOCH_DAYS_FROM_CE: i32 = 719_163;
+
+#[inline(always)]
+unsafe fn read_naive_date(p: *const u8, pos: usize) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(read_date(p, pos).checked_add(UNIX_EPOCH_DAYS_FROM_CE)?)
+}
+#[inline(always)]
+unsafe fn write_naive_date(obj: &NaiveDate, p: *mut u8, pos: usize) {
+    use chrono::Datelike;
+    write_date(p, pos, obj.num_days_from_ce() - UNIX_EPOCH_DAYS_FROM_CE);
+}
+
+IMPLEMENT_SERDE_FOR_DATE_TIME!(DateTime<Utc>, UtcDateTime, DATE_TIME_SIZE, read_utc, write_utc);
+IMPLEMENT_SERDE_FOR_DATE_TIME!(NaiveDateTime, NaiveDateTime, DATE_TIME_SIZE, read_naive, write_naive);
+IMPLEMENT_SERDE_FOR_DATE_TIME!(
+    DateTime<FixedOffset>,
+    OffsetDateTime,
+    OFFSET_DATE_TIME_SIZE,
+    read_fixed_offset,
+    write_fixed_offset
+);
+IMPLEMENT_SERDE_FOR_DATE_TIME!(NaiveDate, Date, DATE_SIZE, read_naive_date, write_naive_date);
@@ -0,0 +1,70 @@
+use super::*;
+use crate::serde::{SerDe, SerDeVec};
+use crate::size;
+use common::data_format::DataFormat;
+use time::{Date, OffsetDateTime, PrimitiveDateTime, UtcOffset};
+
+#[inline(always)]
+fn from_unix_timestamp(secs: i64, nanos: u32) -> Option<OffsetDateTime> {
+    OffsetDateTime::from_unix_timestamp(secs)
+        .ok()?
+        .replace_nanosecond(nanos)
+        .ok()
+}
+
+#[inline(always)]
+unsafe fn read_offset(p: *const u8, pos: usize) -> Option<OffsetDateTime> {
+    let (secs, nanos, offset) = read_offset_date_time(p, pos);
+    let offset = UtcOffset::from_whole_seconds(offset).ok()?;
+    from_unix_timestamp(secs, nanos)?.checked_to_offset(offset)
+}
+#[inline(always)]
+unsafe fn write_offset(obj: &OffsetDateTime, p: *mut u8, pos: usize) {
+    write_offset_date_time(
+        p,
+        pos,
+        obj.unix_timestamp(),
+        obj.nanosecond(),
+        obj.offset().whole_seconds(),
+    );
+}
+
+#[inline(always)]
+unsafe fn read_primitive(p: *const u8, pos: usize) -> Option<PrimitiveDateTime> {
+    let (secs, nanos) = read_date_time(p, pos);
+    let value = from_unix_timestamp(secs, nanos)?;
+    Some(PrimitiveDateTime::new(value.date(), value.time()))
+}
+#[inline(always)]
+unsafe fn write_primitive(obj: &PrimitiveDateTime, p: *mut u8, pos: usize) {
+    let utc = obj.assume_utc();
+    write_date_time(p, pos, utc.unix_timestamp(), utc.nanosecond());
+}
+
+// julian day number of 1970-01-01
+const UNIX_EPOCH_JULIAN_DAY: i32 = 2_440_588;
+
+#[inline(always)]
+unsafe fn read_time_date(p: *const u8, pos: usize) -> Option<Date> {
+    Date::from_julian_day(read_date(p, pos).checked_add(UNIX_EPOCH_JULIAN_DAY)?).ok()
+}
+#[inline(always)]
+unsafe fn write_time_date(obj: &Date, p: *mut u8, pos: usize) {
+    write_date(p, pos, obj.to_julian_day() - UNIX_EPOCH_JULIAN_DAY);
+}
+
+IMPLEMENT_SERDE_FOR_DATE_TIME!(
+    OffsetDateTime,
+    OffsetDateTime,
+    OFFSET_DATE_TIME_SIZE,
+    read_offset,
+    write_offset
+);
+IMPLEMENT_SERDE_FOR_DATE_TIME!(
+    PrimitiveDateTime,
+    NaiveDateTime,
+    DATE_TIME_SIZE,
+    read_primitive,
+    write_primitive
+);
+IMPLEMENT_SERDE_FOR_DATE_TIME!(Date, Date, DATE_SIZE, read_time_date, write_time_date);
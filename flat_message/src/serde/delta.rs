@@ -0,0 +1,265 @@
+use super::varint::{len_u128, read_u128, unzigzag_i128, write_u128, zigzag_i128};
+use super::SerDeDelta;
+use crate::size;
+use common::data_format::DataFormat;
+use std::marker::PhantomData;
+
+// Layout of a delta encoded list:
+// - number of elements (size::Format::U8withExtension)
+// - the difference between each value and the previous one (the first value is stored as the
+//   difference from 0), zigzag encoded and stored as a variable length integer (see varint.rs)
+//
+// The differences are computed exactly (not modulo the size of the type), so the values do not
+// have to be sorted and the format does not depend on the type of the elements: a list written
+// from a Vec<u64> can be read into a Vec<u32> or a Vec<i64> as long as every value fits.
+
+/// Reads and validates `count` differences starting from `pos` and calls `f` for every value.
+/// Returns the position right after the last difference.
+#[inline(always)]
+fn read_values<T: TryFrom<i128>>(buf: &[u8], mut pos: usize, count: usize, mut f: impl FnMut(T)) -> Option<usize> {
+    let mut value: i128 = 0;
+    for _ in 0..count {
+        let (delta, next) = read_u128(buf, pos)?;
+        value = value.checked_add(unzigzag_i128(delta))?;
+        f(T::try_from(value).ok()?);
+        pos = next;
+    }
+    Some(pos)
+}
+
+/// Reads the number of elements and checks that the buffer has at least one byte for each of them.
+/// Returns the number of elements and the position of the first difference.
+#[inline(always)]
+fn read_count(buf: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let (count, buf_len) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+    crate::limits::check_list_len(count)?;
+    let pos = pos + buf_len;
+    if count > buf.len() - pos {
+        None
+    } else {
+        Some((count, pos))
+    }
+}
+
+/// A zero-copy view over a delta encoded list of integers (`#[flat_message_item(encoding = "delta")]`).
+///
+/// The values are decoded while iterating (`iter()` does not allocate). A `DeltaSlice<T>` uses the
+/// same binary format as a delta encoded `Vec<T>` (the two types are interchangeable).
+///
+/// # Example
+/// ```
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Series {
+///     #[flat_message_item(encoding = "delta")]
+///     timestamps: Vec<u64>,
+/// }
+/// #[derive(FlatMessage)]
+/// struct SeriesView<'a> {
+///     timestamps: DeltaSlice<'a, u64>,
+/// }
+///
+/// let mut storage = Storage::default();
+/// let timestamps = vec![1_700_000_000_000, 1_700_000_000_250, 1_700_000_000_500];
+/// Series { timestamps }.serialize_to(&mut storage, Config::default()).unwrap();
+/// let s = SeriesView::deserialize_from(&storage).unwrap();
+/// assert_eq!(s.timestamps.len(), 3);
+/// assert_eq!(s.timestamps.iter().last(), Some(1_700_000_000_500));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct DeltaSlice<'a, T> {
+    len: usize,
+    data: &'a [u8],
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T> DeltaSlice<'a, T> {
+    /// Number of values.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Iterates through the values (each value is decoded when it is needed).
+    #[inline(always)]
+    pub fn iter(&self) -> DeltaIter<'a, T> {
+        DeltaIter {
+            data: self.data,
+            pos: 0,
+            remaining: self.len,
+            value: 0,
+            _marker: PhantomData,
+        }
+    }
+    /// Decodes all the values into a vector.
+    pub fn to_vec(&self) -> Vec<T>
+    where
+        DeltaIter<'a, T>: Iterator<Item = T>,
+    {
+        self.iter().collect()
+    }
+}
+
+impl<T> Clone for DeltaSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for DeltaSlice<'_, T> {}
+
+impl<T> Default for DeltaSlice<'_, T> {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            data: &[],
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> IntoIterator for DeltaSlice<'a, T>
+where
+    DeltaIter<'a, T>: Iterator<Item = T>,
+{
+    type Item = T;
+    type IntoIter = DeltaIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Iterator over the values of a [`DeltaSlice`].
+#[derive(Debug, Clone)]
+pub struct DeltaIter<'a, T> {
+    data: &'a [u8],
+    pos: usize,
+    remaining: usize,
+    value: i128,
+    _marker: PhantomData<T>,
+}
+
+macro_rules! IMPLEMENT_SERDE_DELTA_FOR {
+    ($t:ty) => {
+        impl Iterator for DeltaIter<'_, $t> {
+            type Item = $t;
+            #[inline(always)]
+            fn next(&mut self) -> Option<$t> {
+                if self.remaining == 0 {
+                    return None;
+                }
+                let (delta, pos) = read_u128(self.data, self.pos)?;
+                self.value = self.value.wrapping_add(unzigzag_i128(delta));
+                self.pos = pos;
+                self.remaining -= 1;
+                // the values were validated when the DeltaSlice was deserialized
+                Some(self.value as $t)
+            }
+            #[inline(always)]
+            fn size_hint(&self) -> (usize, Option<usize>) {
+                (self.remaining, Some(self.remaining))
+            }
+        }
+        impl ExactSizeIterator for DeltaIter<'_, $t> {}
+
+        unsafe impl<'a> SerDeDelta<'a> for Vec<$t> {
+            const DATA_FORMAT: DataFormat = DataFormat::Delta;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                unsafe { DeltaSlice::<$t>::from_buffer_unchecked(buf, pos).to_vec() }
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                let (count, pos) = read_count(buf, pos)?;
+                crate::limits::allocate::<$t>(count)?;
+                let mut result = Vec::with_capacity(count.min(1024));
+                read_values(buf, pos, count, |v: $t| result.push(v))?;
+                Some(result)
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                unsafe {
+                    let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+                    let mut previous: i128 = 0;
+                    for value in obj.iter() {
+                        let value = *value as i128;
+                        pos = write_u128(zigzag_i128(value - previous), p, pos);
+                        previous = value;
+                    }
+                    pos
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                let mut previous: i128 = 0;
+                let mut size = size::len(obj.len(), size::Format::U8withExtension);
+                for value in obj.iter() {
+                    let value = *value as i128;
+                    size += len_u128(zigzag_i128(value - previous));
+                    previous = value;
+                }
+                size
+            }
+        }
+
+        unsafe impl<'a> SerDeDelta<'a> for DeltaSlice<'a, $t> {
+            const DATA_FORMAT: DataFormat = DataFormat::Delta;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                unsafe {
+                    let (len, buf_len) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+                    let start = pos + buf_len;
+                    // the last byte of each difference has the most significant bit cleared
+                    let mut end = start;
+                    for _ in 0..len {
+                        while *buf.get_unchecked(end) & 0x80 != 0 {
+                            end += 1;
+                        }
+                        end += 1;
+                    }
+                    DeltaSlice {
+                        len,
+                        data: buf.get_unchecked(start..end),
+                        _marker: PhantomData,
+                    }
+                }
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                let (len, start) = read_count(buf, pos)?;
+                let end = read_values(buf, start, len, |_: $t| {})?;
+                Some(DeltaSlice {
+                    len,
+                    data: &buf[start..end],
+                    _marker: PhantomData,
+                })
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                unsafe {
+                    let pos = pos + size::write(p, pos, obj.len, size::Format::U8withExtension);
+                    std::ptr::copy_nonoverlapping(obj.data.as_ptr(), p.add(pos), obj.data.len());
+                    pos + obj.data.len()
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                size::len(obj.len, size::Format::U8withExtension) + obj.data.len()
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_DELTA_FOR!(u8);
+IMPLEMENT_SERDE_DELTA_FOR!(u16);
+IMPLEMENT_SERDE_DELTA_FOR!(u32);
+IMPLEMENT_SERDE_DELTA_FOR!(u64);
+IMPLEMENT_SERDE_DELTA_FOR!(usize);
+IMPLEMENT_SERDE_DELTA_FOR!(i8);
+IMPLEMENT_SERDE_DELTA_FOR!(i16);
+IMPLEMENT_SERDE_DELTA_FOR!(i32);
+IMPLEMENT_SERDE_DELTA_FOR!(i64);
+IMPLEMENT_SERDE_DELTA_FOR!(isize);
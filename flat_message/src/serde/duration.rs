@@ -0,0 +1,112 @@
+use super::SerDe;
+use super::SerDeVec;
+use crate::size;
+use common::data_format::DataFormat;
+use std::ptr;
+use std::time::Duration;
+
+// A duration is stored on 12 bytes: the number of seconds (u64) followed by the
+// sub-second part in nanoseconds (u32). The nanoseconds value must be lower than 1_000_000_000.
+const DURATION_SIZE: usize = 12;
+const NANOS_PER_SEC: u32 = 1_000_000_000;
+
+#[inline(always)]
+unsafe fn read_duration(p: *const u8, pos: usize) -> (u64, u32) {
+    unsafe {
+        let secs = ptr::read_unaligned(p.add(pos) as *const u64);
+        let nanos = ptr::read_unaligned(p.add(pos + 8) as *const u32);
+        (secs, nanos)
+    }
+}
+
+#[inline(always)]
+unsafe fn write_duration(obj: &Duration, p: *mut u8, pos: usize) {
+    unsafe {
+        ptr::write_unaligned(p.add(pos) as *mut u64, obj.as_secs());
+        ptr::write_unaligned(p.add(pos + 8) as *mut u32, obj.subsec_nanos());
+    }
+}
+
+unsafe impl SerDe<'_> for Duration {
+    const DATA_FORMAT: DataFormat = DataFormat::Duration;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+        let (secs, nanos) = read_duration(buf.as_ptr(), pos);
+        Duration::new(secs, nanos)
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        if pos + DURATION_SIZE > buf.len() {
+            return None;
+        }
+        let (secs, nanos) = unsafe { read_duration(buf.as_ptr(), pos) };
+        if nanos >= NANOS_PER_SEC {
+            return None;
+        }
+        Some(Duration::new(secs, nanos))
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        write_duration(obj, p, pos);
+        pos + DURATION_SIZE
+    }
+    #[inline(always)]
+    fn size(_: &Self) -> usize {
+        DURATION_SIZE
+    }
+}
+
+// `Duration` does not have a stable memory layout, so only vectors are supported (no zero-copy slices).
+unsafe impl SerDeVec<'_> for Duration {
+    const DATA_FORMAT: DataFormat = DataFormat::Duration;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+        let p = buf.as_ptr();
+        let (count, size_len) = size::read_unchecked(p, pos, size::Format::U32);
+        let start = pos + size_len;
+        (0..count)
+            .map(|index| {
+                let (secs, nanos) = read_duration(p, start + index * DURATION_SIZE);
+                Duration::new(secs, nanos)
+            })
+            .collect()
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+        let p = buf.as_ptr();
+        let (count, size_len) = size::read(p, pos, buf.len(), size::Format::U32)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<Self>(count)?;
+        let start = pos + size_len;
+        let end = count
+            .checked_mul(DURATION_SIZE)
+            .and_then(|sz| sz.checked_add(start))?;
+        if end > buf.len() {
+            return None;
+        }
+        (0..count)
+            .map(|index| {
+                let (secs, nanos) = unsafe { read_duration(p, start + index * DURATION_SIZE) };
+                if nanos >= NANOS_PER_SEC {
+                    None
+                } else {
+                    Some(Duration::new(secs, nanos))
+                }
+            })
+            .collect()
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+        let size_len = size::write(p, pos, obj.len(), size::Format::U32);
+        let mut pos = pos + size_len;
+        for value in obj {
+            write_duration(value, p, pos);
+            pos += DURATION_SIZE;
+        }
+        pos
+    }
+    #[inline(always)]
+    fn size(obj: &Vec<Self>) -> usize {
+        size::len(obj.len(), size::Format::U32) + obj.len() * DURATION_SIZE
+    }
+}
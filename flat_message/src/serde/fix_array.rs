@@ -1,46 +1,76 @@
 use super::{SerDe, SerDeSlice, SerDeVec};
 use crate::size;
+use crate::validate::fixed_slice_layout;
+use crate::Storage;
 use common::data_format::DataFormat;
 
-unsafe impl<'a, const N: usize> SerDe<'a> for [u8; N] {
-    const DATA_FORMAT: DataFormat = DataFormat::FixArray;
+// [T; N] is serialized as a slice of N elements of type T (the same format as &[T] / Vec<T>).
+// The only exception are arrays of bytes that use a dedicated data format (FixArray).
+const fn array_data_format(element: DataFormat) -> DataFormat {
+    match element {
+        DataFormat::U8 => DataFormat::FixArray,
+        _ => element,
+    }
+}
+
+#[inline(always)]
+fn is_aligned<T>(buf: &[u8], pos: usize) -> bool {
+    (buf.as_ptr() as usize + pos).is_multiple_of(std::mem::align_of::<T>())
+}
+
+unsafe impl<'a, T, const N: usize> SerDe<'a> for [T; N]
+where
+    T: for<'b> SerDeSlice<'b> + Copy,
+{
+    const DATA_FORMAT: DataFormat = array_data_format(<T as SerDeSlice<'a>>::DATA_FORMAT);
 
     unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self
     where
         Self: Sized,
     {
-        let p = buf.as_ptr();
-        let (_, slen) = size::read_unchecked(p, pos, size::Format::U8withExtension);
-        let ptr = buf.as_ptr().add(pos + slen) as *const [u8; N];
-        unsafe { *ptr }
+        if is_aligned::<T>(buf, pos) {
+            let slice: &[T] = SerDeSlice::from_buffer_unchecked(buf, pos);
+            unsafe { *(slice.as_ptr() as *const [T; N]) }
+        } else {
+            // every element type that needs an alignment has a fixed layout
+            let (hash_size, format, _) =
+                fixed_slice_layout(Self::DATA_FORMAT).unwrap_or((0, size::Format::U8withExtension, 0));
+            let p = buf.as_ptr();
+            let (_, size_len) = size::read_unchecked(p, pos + hash_size, format);
+            let values = unsafe { p.add(pos + hash_size + size_len) as *const T };
+            std::array::from_fn(|index| unsafe { std::ptr::read_unaligned(values.add(index)) })
+        }
     }
 
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self>
     where
         Self: Sized,
     {
-        let (count, slen) =
-            size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
-        if count != N {
-            None
+        if is_aligned::<T>(buf, pos) {
+            let slice: &[T] = SerDeSlice::from_buffer(buf, pos)?;
+            slice.try_into().ok()
         } else {
-            unsafe {
-                let ptr = buf.as_ptr().add(pos + slen) as *const [u8; N];
-                Some(*ptr)
+            // only the N elements of this field (and their size) are copied into an aligned buffer
+            let (hash_size, format, item_size) = fixed_slice_layout(Self::DATA_FORMAT)?;
+            let (count, size_len) = size::read(buf.as_ptr(), pos.checked_add(hash_size)?, buf.len(), format)?;
+            if count != N || item_size != std::mem::size_of::<T>() {
+                return None;
             }
+            let end = (pos + hash_size + size_len).checked_add(N * item_size)?;
+            let field = buf.get(pos..end)?;
+            crate::limits::allocate::<u8>(field.len())?;
+            let storage = Storage::from_buffer(field);
+            let slice: &[T] = SerDeSlice::from_buffer(storage.as_slice(), 0)?;
+            slice.try_into().ok()
         }
     }
 
     unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
-        unsafe {
-            let slen = size::write(p, pos, N as u32, size::Format::U8withExtension);
-            std::ptr::copy_nonoverlapping(obj.as_ptr(), p.add(pos + slen), obj.len());
-            pos + slen + N
-        }
+        SerDeSlice::write(obj.as_slice(), p, pos)
     }
 
-    fn size(_: &Self) -> usize {
-        crate::size::len(N as u32, crate::size::Format::U8withExtension) + N
+    fn size(obj: &Self) -> usize {
+        <T as SerDeSlice>::size(obj.as_slice())
     }
 }
 
@@ -65,7 +95,8 @@ unsafe impl<'a, const N: usize> SerDeSlice<'a> for [u8; N] {
             buf.len(),
             size::Format::U8withExtension,
         )?;
-        let end = pos + count * N + slen1 + slen2;
+        crate::limits::check_list_len(count)?;
+        let end = count.checked_mul(N)?.checked_add(pos + slen1 + slen2)?;
         if end > buf.len() {
             None
         } else {
@@ -80,11 +111,11 @@ unsafe impl<'a, const N: usize> SerDeSlice<'a> for [u8; N] {
     #[inline(always)]
     unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
         unsafe {
-            let slen1 = size::write(p, pos, N as u32, size::Format::U8withExtension);
+            let slen1 = size::write(p, pos, N, size::Format::U8withExtension);
             let slen2 = size::write(
                 p,
                 pos + slen1,
-                obj.len() as u32,
+                obj.len(),
                 size::Format::U8withExtension,
             );
             std::ptr::copy_nonoverlapping(
@@ -98,8 +129,8 @@ unsafe impl<'a, const N: usize> SerDeSlice<'a> for [u8; N] {
     #[inline(always)]
     fn size(obj: &[Self]) -> usize {
         obj.len() * N
-            + crate::size::len(N as u32, crate::size::Format::U8withExtension)
-            + crate::size::len(obj.len() as u32, crate::size::Format::U8withExtension)
+            + crate::size::len(N, crate::size::Format::U8withExtension)
+            + crate::size::len(obj.len(), crate::size::Format::U8withExtension)
     }
 }
 
@@ -113,6 +144,7 @@ unsafe impl<'a, const N: usize> SerDeVec<'a> for [u8; N] {
     #[inline(always)]
     fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
         let res: &[[u8; N]] = SerDeSlice::from_buffer(buf, pos)?;
+        crate::limits::allocate::<Self>(res.len())?;
         Some(res.to_vec())
     }
     #[inline(always)]
@@ -125,44 +157,37 @@ unsafe impl<'a, const N: usize> SerDeVec<'a> for [u8; N] {
     }
 }
 
-unsafe impl<'a, const N: usize> SerDe<'a> for &'a [u8; N] {
-    const DATA_FORMAT: DataFormat = DataFormat::FixArray;
+/// Zero-copy implementation for &[T; N] (only possible if the elements are properly aligned in the buffer)
+unsafe impl<'a, T, const N: usize> SerDe<'a> for &'a [T; N]
+where
+    T: SerDeSlice<'a>,
+{
+    const DATA_FORMAT: DataFormat = array_data_format(<T as SerDeSlice<'a>>::DATA_FORMAT);
 
     unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self
     where
         Self: Sized,
     {
-        let p = buf.as_ptr();
-        let (_, slen) = size::read_unchecked(p, pos, size::Format::U8withExtension);
-        let ptr = buf.as_ptr().add(pos + slen) as *const [u8; N];
-        unsafe { &*ptr }
+        let slice: &[T] = SerDeSlice::from_buffer_unchecked(buf, pos);
+        unsafe { &*(slice.as_ptr() as *const [T; N]) }
     }
 
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self>
     where
         Self: Sized,
     {
-        let (count, slen) =
-            size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
-        if count != N {
-            None
-        } else {
-            unsafe {
-                let ptr = buf.as_ptr().add(pos + slen) as *const [u8; N];
-                Some(&*ptr)
-            }
+        if !is_aligned::<T>(buf, pos) {
+            return None;
         }
+        let slice: &[T] = SerDeSlice::from_buffer(buf, pos)?;
+        slice.try_into().ok()
     }
 
     unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
-        unsafe {
-            let slen = size::write(p, pos, N as u32, size::Format::U8withExtension);
-            std::ptr::copy_nonoverlapping(obj.as_ptr(), p.add(pos + slen), obj.len());
-            pos + slen + N
-        }
+        SerDeSlice::write(obj.as_slice(), p, pos)
     }
 
-    fn size(_: &Self) -> usize {
-        crate::size::len(N as u32, crate::size::Format::U8withExtension) + N
+    fn size(obj: &Self) -> usize {
+        <T as SerDeSlice>::size(obj.as_slice())
     }
 }
@@ -1,8 +1,22 @@
-use super::SerDe;
+use super::{SerDe, SerDeSlice, SerDeVec};
+use crate::size;
 use common::data_format::DataFormat;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::ptr;
 
+// In a slice / vector `Ipv4Addr` and `Ipv6Addr` are stored as their octets (in network order). Both types are
+// represented in memory exactly as their octets, so slices can be read without copying (this is checked below).
+const _: () = {
+    assert!(std::mem::size_of::<Ipv4Addr>() == 4 && std::mem::align_of::<Ipv4Addr>() == 1);
+    assert!(std::mem::size_of::<Ipv6Addr>() == 16 && std::mem::align_of::<Ipv6Addr>() == 1);
+    let v4: Ipv4Addr = unsafe { std::mem::transmute([1u8, 2, 3, 4]) };
+    let octets = v4.octets();
+    assert!(octets[0] == 1 && octets[1] == 2 && octets[2] == 3 && octets[3] == 4);
+    let v6: Ipv6Addr = unsafe { std::mem::transmute([1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]) };
+    let octets = v6.octets();
+    assert!(octets[0] == 1 && octets[7] == 8 && octets[15] == 16);
+};
+
 unsafe impl<'a> SerDe<'a> for IpAddr {
     const DATA_FORMAT: DataFormat = DataFormat::IP;
     #[inline(always)]
@@ -22,6 +36,9 @@ unsafe impl<'a> SerDe<'a> for IpAddr {
     }
     #[inline(always)]
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        if pos >= buf.len() {
+            return None;
+        }
         let p = buf.as_ptr();
         let first_byte = unsafe { *p.add(pos) };
         match first_byte {
@@ -139,3 +156,124 @@ unsafe impl<'a> SerDe<'a> for Ipv6Addr {
         16
     }
 }
+
+macro_rules! IMPLEMENT_SERDE_SLICE_FOR_IP {
+    ($t:ty, $size:expr) => {
+        unsafe impl<'a> SerDeSlice<'a> for $t {
+            const DATA_FORMAT: DataFormat = <$t as SerDe<'a>>::DATA_FORMAT;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> &'a [Self] {
+                let p = buf.as_ptr();
+                let (count, size_len) = size::read_unchecked(p, pos, size::Format::U8withExtension);
+                std::slice::from_raw_parts(p.add(pos + size_len) as *const $t, count)
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
+                let (count, size_len) =
+                    size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+                crate::limits::check_list_len(count)?;
+                let end = count
+                    .checked_mul($size)
+                    .and_then(|sz| sz.checked_add(pos + size_len))?;
+                if end > buf.len() {
+                    None
+                } else {
+                    Some(unsafe {
+                        std::slice::from_raw_parts(buf.as_ptr().add(pos + size_len) as *const $t, count)
+                    })
+                }
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
+                unsafe {
+                    let size_len = size::write(p, pos, obj.len(), size::Format::U8withExtension);
+                    std::ptr::copy_nonoverlapping(
+                        obj.as_ptr() as *const u8,
+                        p.add(pos + size_len),
+                        obj.len() * $size,
+                    );
+                    pos + size_len + obj.len() * $size
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &[Self]) -> usize {
+                size::len(obj.len(), size::Format::U8withExtension) + obj.len() * $size
+            }
+        }
+
+        unsafe impl SerDeVec<'_> for $t {
+            const DATA_FORMAT: DataFormat = <$t as SerDe>::DATA_FORMAT;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+                let res: &[$t] = SerDeSlice::from_buffer_unchecked(buf, pos);
+                res.to_vec()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+                let res: &[$t] = SerDeSlice::from_buffer(buf, pos)?;
+                crate::limits::allocate::<Self>(res.len())?;
+                Some(res.to_vec())
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+                SerDeSlice::write(obj.as_slice(), p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Vec<Self>) -> usize {
+                SerDeSlice::size(obj.as_slice())
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_SLICE_FOR_IP!(Ipv4Addr, 4);
+IMPLEMENT_SERDE_SLICE_FOR_IP!(Ipv6Addr, 16);
+
+// IpAddr has a variable size (5 or 17 bytes) so it can only be used in vectors.
+// Each element is stored in the same way as an IpAddr object.
+unsafe impl SerDeVec<'_> for IpAddr {
+    const DATA_FORMAT: DataFormat = DataFormat::IP;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+        let (count, size_len) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+        let mut result = Vec::with_capacity(count);
+        let mut pos = pos + size_len;
+        for _ in 0..count {
+            let addr: IpAddr = SerDe::from_buffer_unchecked(buf, pos);
+            pos += SerDe::size(&addr);
+            result.push(addr);
+        }
+        result
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+        let (count, size_len) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<Self>(count)?;
+        // each address needs at least 5 bytes
+        if count.checked_mul(5)?.checked_add(pos + size_len)? > buf.len() {
+            return None;
+        }
+        let mut result = Vec::with_capacity(count.min(1024));
+        let mut pos = pos + size_len;
+        for _ in 0..count {
+            let addr: IpAddr = SerDe::from_buffer(buf, pos)?;
+            pos += SerDe::size(&addr);
+            result.push(addr);
+        }
+        Some(result)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+        let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+        for addr in obj.iter() {
+            pos = SerDe::write(addr, p, pos);
+        }
+        pos
+    }
+    #[inline(always)]
+    fn size(obj: &Vec<Self>) -> usize {
+        size::len(obj.len(), size::Format::U8withExtension)
+            + obj.iter().map(<IpAddr as SerDe>::size).sum::<usize>()
+    }
+}
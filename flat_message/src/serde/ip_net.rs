@@ -0,0 +1,183 @@
+use super::{SerDe, SerDeVec};
+use crate::size;
+use common::data_format::DataFormat;
+use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::ptr;
+
+// A network is stored as its address (4 or 16 bytes, same format as Ipv4Addr / Ipv6Addr) followed by the
+// prefix length (1 byte). IpNet starts with a tag (0 for IPv4 and 1 for IPv6) just like IpAddr.
+
+#[inline(always)]
+fn read_v4(buf: &[u8], pos: usize) -> Option<Ipv4Net> {
+    let bytes: [u8; 5] = buf.get(pos..pos + 5)?.try_into().ok()?;
+    Ipv4Net::new(Ipv4Addr::new(bytes[0], bytes[1], bytes[2], bytes[3]), bytes[4]).ok()
+}
+
+#[inline(always)]
+fn read_v6(buf: &[u8], pos: usize) -> Option<Ipv6Net> {
+    let bytes: [u8; 17] = buf.get(pos..pos + 17)?.try_into().ok()?;
+    let octets: [u8; 16] = bytes[..16].try_into().ok()?;
+    Ipv6Net::new(Ipv6Addr::from(octets), bytes[16]).ok()
+}
+
+#[inline(always)]
+unsafe fn write_v4(obj: &Ipv4Net, p: *mut u8, pos: usize) -> usize {
+    unsafe {
+        ptr::copy_nonoverlapping(obj.addr().octets().as_ptr(), p.add(pos), 4);
+        ptr::write_unaligned(p.add(pos + 4), obj.prefix_len());
+    }
+    pos + 5
+}
+
+#[inline(always)]
+unsafe fn write_v6(obj: &Ipv6Net, p: *mut u8, pos: usize) -> usize {
+    unsafe {
+        ptr::copy_nonoverlapping(obj.addr().octets().as_ptr(), p.add(pos), 16);
+        ptr::write_unaligned(p.add(pos + 16), obj.prefix_len());
+    }
+    pos + 17
+}
+
+unsafe impl SerDe<'_> for Ipv4Net {
+    const DATA_FORMAT: DataFormat = DataFormat::Ipv4Net;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+        let p = buf.as_ptr();
+        unsafe {
+            let octets = ptr::read_unaligned(p.add(pos) as *const [u8; 4]);
+            Ipv4Net::new_assert(Ipv4Addr::from(octets), *p.add(pos + 4))
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        read_v4(buf, pos)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        write_v4(obj, p, pos)
+    }
+    #[inline(always)]
+    fn size(_: &Self) -> usize {
+        5
+    }
+}
+
+unsafe impl SerDe<'_> for Ipv6Net {
+    const DATA_FORMAT: DataFormat = DataFormat::Ipv6Net;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+        let p = buf.as_ptr();
+        unsafe {
+            let octets = ptr::read_unaligned(p.add(pos) as *const [u8; 16]);
+            Ipv6Net::new_assert(Ipv6Addr::from(octets), *p.add(pos + 16))
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        read_v6(buf, pos)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        write_v6(obj, p, pos)
+    }
+    #[inline(always)]
+    fn size(_: &Self) -> usize {
+        17
+    }
+}
+
+unsafe impl SerDe<'_> for IpNet {
+    const DATA_FORMAT: DataFormat = DataFormat::IpNet;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+        if *buf.as_ptr().add(pos) == 0 {
+            IpNet::V4(<Ipv4Net as SerDe>::from_buffer_unchecked(buf, pos + 1))
+        } else {
+            IpNet::V6(<Ipv6Net as SerDe>::from_buffer_unchecked(buf, pos + 1))
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        match buf.get(pos)? {
+            0 => Some(IpNet::V4(read_v4(buf, pos + 1)?)),
+            1 => Some(IpNet::V6(read_v6(buf, pos + 1)?)),
+            _ => None,
+        }
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        match obj {
+            IpNet::V4(net) => unsafe {
+                ptr::write_unaligned(p.add(pos), 0);
+                write_v4(net, p, pos + 1)
+            },
+            IpNet::V6(net) => unsafe {
+                ptr::write_unaligned(p.add(pos), 1);
+                write_v6(net, p, pos + 1)
+            },
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        match obj {
+            IpNet::V4(_) => 6,
+            IpNet::V6(_) => 18,
+        }
+    }
+}
+
+// vectors: number of elements followed by the elements (stored in the same way as an object)
+macro_rules! IMPLEMENT_SERDE_VEC_FOR_NET {
+    ($t:ty, $min_size:expr) => {
+        unsafe impl SerDeVec<'_> for $t {
+            const DATA_FORMAT: DataFormat = <$t as SerDe>::DATA_FORMAT;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+                let (count, size_len) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+                let mut result = Vec::with_capacity(count);
+                let mut pos = pos + size_len;
+                for _ in 0..count {
+                    let net: $t = SerDe::from_buffer_unchecked(buf, pos);
+                    pos += SerDe::size(&net);
+                    result.push(net);
+                }
+                result
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+                let (count, size_len) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+                crate::limits::check_list_len(count)?;
+                crate::limits::allocate::<Self>(count)?;
+                if count.checked_mul($min_size)?.checked_add(pos + size_len)? > buf.len() {
+                    return None;
+                }
+                let mut result = Vec::with_capacity(count.min(1024));
+                let mut pos = pos + size_len;
+                for _ in 0..count {
+                    let net: $t = SerDe::from_buffer(buf, pos)?;
+                    pos += SerDe::size(&net);
+                    result.push(net);
+                }
+                Some(result)
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+                let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+                for net in obj.iter() {
+                    pos = SerDe::write(net, p, pos);
+                }
+                pos
+            }
+            #[inline(always)]
+            fn size(obj: &Vec<Self>) -> usize {
+                size::len(obj.len(), size::Format::U8withExtension)
+                    + obj.iter().map(|net| SerDe::size(net)).sum::<usize>()
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_VEC_FOR_NET!(Ipv4Net, 5);
+IMPLEMENT_SERDE_VEC_FOR_NET!(Ipv6Net, 17);
+IMPLEMENT_SERDE_VEC_FOR_NET!(IpNet, 6);
@@ -0,0 +1,231 @@
+use super::{SerDe, SerDeIter, SerDeSlice, SerDeVec};
+use crate::size;
+use common::data_format::DataFormat;
+use std::fmt;
+
+// An `IterField<T>` is serialized exactly like a `Vec<T>`, but its elements are written directly
+// from an iterator (no vector is built). The iterator is cloned for every pass over the elements
+// (computing the size of the field and writing it), so it must be `Clone`. Since a clone could return
+// other elements than the original iterator, the number of elements and the size of the list are
+// checked while writing (a mismatch panics instead of writing outside the reserved space).
+
+trait Values<'a, T>: Iterator<Item = T> {
+    fn boxed_clone(&self) -> Box<dyn Values<'a, T> + 'a>;
+}
+
+impl<'a, T, I> Values<'a, T> for I
+where
+    I: Iterator<Item = T> + Clone + 'a,
+{
+    fn boxed_clone(&self) -> Box<dyn Values<'a, T> + 'a> {
+        Box::new(self.clone())
+    }
+}
+
+/// A list field that is serialized from an iterator, without building a `Vec<T>` first.
+///
+/// The field uses the same format as a `Vec<T>` (a message with an `IterField<T>` field can be
+/// deserialized in a structure that has a `Vec<T>` field with the same name and the other way around).
+/// When deserialized, an `IterField` iterates over the elements from the buffer (zero-copy for numbers
+/// and booleans).
+///
+/// ```rust
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Snapshot<'a> {
+///     ids: IterField<'a, u32>,
+/// }
+///
+/// let records = vec![(1u32, "a"), (2, "b"), (3, "c")];
+/// let snapshot = Snapshot { ids: IterField::new(records.iter().map(|r| r.0)) };
+/// let mut storage = Storage::default();
+/// snapshot.serialize_to(&mut storage, Config::default()).unwrap();
+/// let s = Snapshot::deserialize_from(&storage).unwrap();
+/// assert_eq!(s.ids.iter().collect::<Vec<_>>(), vec![1, 2, 3]);
+/// ```
+pub struct IterField<'a, T> {
+    len: usize,
+    values: Box<dyn Values<'a, T> + 'a>,
+}
+
+impl<'a, T: 'a> IterField<'a, T> {
+    /// Creates a field from an iterator that knows its number of elements.
+    pub fn new<I>(values: I) -> Self
+    where
+        I: ExactSizeIterator<Item = T> + Clone + 'a,
+    {
+        Self {
+            len: values.len(),
+            values: Box::new(values),
+        }
+    }
+    /// Number of elements.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns `true` if the field has no elements.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Iterates over the elements (a new iterator is created on every call).
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = T> + 'a {
+        self.values.boxed_clone()
+    }
+    /// Iterates over exactly `len()` elements (panics if the iterator returns fewer elements).
+    fn exact(&self) -> impl Iterator<Item = T> + 'a {
+        let mut values = self.iter();
+        (0..self.len).map(move |_| values.next().expect("IterField: the iterator returned fewer elements than its length"))
+    }
+}
+
+impl<'a, T: Clone + 'a> From<Vec<T>> for IterField<'a, T> {
+    fn from(values: Vec<T>) -> Self {
+        Self::new(values.into_iter())
+    }
+}
+
+impl<'a, T: 'a> Default for IterField<'a, T> {
+    fn default() -> Self {
+        Self::new(std::iter::empty())
+    }
+}
+
+impl<T> Clone for IterField<'_, T> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            values: self.values.boxed_clone(),
+        }
+    }
+}
+
+impl<'a, T: fmt::Debug + 'a> fmt::Debug for IterField<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T: PartialEq + 'a> PartialEq for IterField<'a, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T: Eq + 'a> Eq for IterField<'a, T> {}
+
+unsafe impl<'a, T: SerDeIter<'a>> SerDe<'a> for IterField<'a, T> {
+    const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        unsafe { T::from_buffer_unchecked(buf, pos) }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        T::from_buffer(buf, pos)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe { T::write(obj, p, pos) }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        T::size(obj)
+    }
+}
+
+// numbers and booleans (same layout as `SerDeSlice`: the number of elements followed by the values)
+macro_rules! IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE {
+    ($t:ty, $data_format:ident, $align_method:ident) => {
+        unsafe impl<'a> SerDeIter<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> IterField<'a, Self> {
+                let values: &'a [$t] = unsafe { SerDeSlice::from_buffer_unchecked(buf, pos) };
+                IterField::new(values.iter().copied())
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<IterField<'a, Self>> {
+                let values: &'a [$t] = SerDeSlice::from_buffer(buf, pos)?;
+                Some(IterField::new(values.iter().copied()))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &IterField<'a, Self>, p: *mut u8, pos: usize) -> usize {
+                unsafe {
+                    let mut pos = pos + size::write(p, pos, obj.len(), size::Format::$align_method);
+                    for value in obj.exact() {
+                        (p.add(pos) as *mut $t).write_unaligned(value);
+                        pos += std::mem::size_of::<$t>();
+                    }
+                    pos
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &IterField<'a, Self>) -> usize {
+                size::len(obj.len(), size::Format::$align_method) + obj.len() * std::mem::size_of::<$t>()
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(u8, U8, U8withExtension);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(i8, I8, U8withExtension);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(bool, Bool, U8withExtension);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(u16, U16, U16withExtension);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(i16, I16, U16withExtension);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(u32, U32, U32);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(i32, I32, U32);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(f32, F32, U32);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(u64, U64, U32on64bits);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(i64, I64, U32on64bits);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(f64, F64, U32on64bits);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(u128, U128, U32on128bits);
+IMPLEMENT_SERDE_ITER_FOR_FIXED_SIZE!(i128, I128, U32on128bits);
+
+// strings (same layout as `SerDeVec` for strings: the number of strings followed by every string)
+macro_rules! IMPLEMENT_SERDE_ITER_FOR_STRING {
+    ($t:ty) => {
+        unsafe impl<'a> SerDeIter<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::String;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> IterField<'a, Self> {
+                let values: Vec<$t> = unsafe { SerDeVec::from_buffer_unchecked(buf, pos) };
+                values.into()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<IterField<'a, Self>> {
+                let values: Vec<$t> = SerDeVec::from_buffer(buf, pos)?;
+                Some(values.into())
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &IterField<'a, Self>, p: *mut u8, pos: usize) -> usize {
+                // only `size(obj)` bytes are available (the strings are checked against that size)
+                let end = pos + <Self as SerDeIter>::size(obj);
+                unsafe {
+                    let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+                    for value in obj.exact() {
+                        let value: &str = value.as_ref();
+                        assert!(
+                            pos + SerDe::size(&value) <= end,
+                            "IterField: the iterator returned other strings than the ones used to compute the size"
+                        );
+                        pos = SerDe::write(&value, p, pos);
+                    }
+                    assert_eq!(pos, end, "IterField: the iterator returned other strings than the ones used to compute the size");
+                    pos
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &IterField<'a, Self>) -> usize {
+                size::len(obj.len(), size::Format::U8withExtension)
+                    + obj.iter().take(obj.len()).map(|value| SerDe::size(&AsRef::<str>::as_ref(&value))).sum::<usize>()
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_ITER_FOR_STRING!(&'a str);
+IMPLEMENT_SERDE_ITER_FOR_STRING!(String);
@@ -0,0 +1,137 @@
+use super::{SerDe, SerDeSlice, SerDeVec};
+use common::data_format::DataFormat;
+use std::ptr;
+
+// Layout of a list of lists (jagged array):
+// - number of lists (u32)
+// - padding up to the alignment of the data format of the elements
+// - each list (stored in the same way as a slice / vector of T, including its own size prefix)
+//
+// Since every list keeps its elements aligned (the size prefix of a slice has the same alignment
+// as its elements), the lists can be referenced directly from the buffer.
+
+/// Offset of the first list (relative to the start of the field).
+#[inline(always)]
+const fn lists_offset(data_format: DataFormat) -> usize {
+    let alignament = data_format.alignament() as usize;
+    (4 + alignament - 1) & !(alignament - 1)
+}
+
+#[inline(always)]
+fn read_count(buf: &[u8], pos: usize) -> Option<usize> {
+    if pos + 4 > buf.len() {
+        None
+    } else {
+        Some(unsafe { ptr::read_unaligned(buf.as_ptr().add(pos) as *const u32) } as usize)
+    }
+}
+
+unsafe impl<'a, T> SerDe<'a> for Vec<Vec<T>>
+where
+    T: SerDeVec<'a>,
+{
+    const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        unsafe {
+            let count = ptr::read_unaligned(buf.as_ptr().add(pos) as *const u32) as usize;
+            let mut pos = pos + lists_offset(T::DATA_FORMAT);
+            let mut result = Vec::with_capacity(count);
+            for _ in 0..count {
+                let list = T::from_buffer_unchecked(buf, pos);
+                pos += T::size(&list);
+                result.push(list);
+            }
+            result
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let count = read_count(buf, pos)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<Vec<T>>(count)?;
+        let mut pos = pos + lists_offset(T::DATA_FORMAT);
+        // each list needs at least one byte (the size prefix)
+        if count.checked_add(pos)? > buf.len() {
+            return None;
+        }
+        let mut result = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let list = T::from_buffer(buf, pos)?;
+            pos += T::size(&list);
+            result.push(list);
+        }
+        Some(result)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            ptr::write_unaligned(p.add(pos) as *mut u32, obj.len() as u32);
+            ptr::write_bytes(p.add(pos + 4), 0, lists_offset(T::DATA_FORMAT) - 4);
+            let mut pos = pos + lists_offset(T::DATA_FORMAT);
+            for list in obj.iter() {
+                pos = T::write(list, p, pos);
+            }
+            pos
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        lists_offset(T::DATA_FORMAT) + obj.iter().map(|list| T::size(list)).sum::<usize>()
+    }
+}
+
+unsafe impl<'a, T> SerDe<'a> for Vec<&'a [T]>
+where
+    T: SerDeSlice<'a>,
+{
+    const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        unsafe {
+            let count = ptr::read_unaligned(buf.as_ptr().add(pos) as *const u32) as usize;
+            let mut pos = pos + lists_offset(T::DATA_FORMAT);
+            let mut result = Vec::with_capacity(count);
+            for _ in 0..count {
+                let list = T::from_buffer_unchecked(buf, pos);
+                pos += T::size(list);
+                result.push(list);
+            }
+            result
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let count = read_count(buf, pos)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<&[T]>(count)?;
+        let mut pos = pos + lists_offset(T::DATA_FORMAT);
+        // each list needs at least one byte (the size prefix)
+        if count.checked_add(pos)? > buf.len() {
+            return None;
+        }
+        let mut result = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            let list = T::from_buffer(buf, pos)?;
+            pos += T::size(list);
+            result.push(list);
+        }
+        Some(result)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            ptr::write_unaligned(p.add(pos) as *mut u32, obj.len() as u32);
+            ptr::write_bytes(p.add(pos + 4), 0, lists_offset(T::DATA_FORMAT) - 4);
+            let mut pos = pos + lists_offset(T::DATA_FORMAT);
+            for list in obj.iter() {
+                pos = T::write(list, p, pos);
+            }
+            pos
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        lists_offset(T::DATA_FORMAT) + obj.iter().map(|list| T::size(list)).sum::<usize>()
+    }
+}
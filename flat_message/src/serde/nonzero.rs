@@ -0,0 +1,91 @@
+use super::SerDe;
+use super::SerDeSlice;
+use super::SerDeVec;
+use common::data_format::DataFormat;
+use std::num::*;
+
+// Non-zero integers are stored exactly like the integer they wrap (they share the same data format).
+// The only difference is that a `0` value is considered invalid when deserializing.
+macro_rules! IMPLEMENT_SERDE_FOR_NONZERO {
+    ($t:ty, $inner:ty, $data_format:ident) => {
+        unsafe impl<'a> SerDe<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+                <$t>::new_unchecked(<$inner as SerDe>::from_buffer_unchecked(buf, pos))
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+                <$t>::new(<$inner as SerDe>::from_buffer(buf, pos)?)
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                <$inner as SerDe>::write(&obj.get(), p, pos)
+            }
+            #[inline(always)]
+            fn size(_: &Self) -> usize {
+                std::mem::size_of::<$inner>()
+            }
+        }
+        // NonZero types are guaranteed to have the same layout as the integer they wrap
+        unsafe impl<'a> SerDeSlice<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> &'a [Self] {
+                let res: &[$inner] = SerDeSlice::from_buffer_unchecked(buf, pos);
+                std::slice::from_raw_parts(res.as_ptr() as *const $t, res.len())
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
+                let res: &[$inner] = SerDeSlice::from_buffer(buf, pos)?;
+                if res.contains(&0) {
+                    return None;
+                }
+                Some(unsafe { std::slice::from_raw_parts(res.as_ptr() as *const $t, res.len()) })
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
+                let values = std::slice::from_raw_parts(obj.as_ptr() as *const $inner, obj.len());
+                SerDeSlice::write(values, p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &[Self]) -> usize {
+                let values = unsafe { std::slice::from_raw_parts(obj.as_ptr() as *const $inner, obj.len()) };
+                <$inner as SerDeSlice>::size(values)
+            }
+        }
+        unsafe impl SerDeVec<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+                let res: &[$t] = SerDeSlice::from_buffer_unchecked(buf, pos);
+                res.to_vec()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+                let res: &[$t] = SerDeSlice::from_buffer(buf, pos)?;
+                crate::limits::allocate::<Self>(res.len())?;
+                Some(res.to_vec())
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+                SerDeSlice::write(obj.as_slice(), p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Vec<Self>) -> usize {
+                <$t as SerDeSlice>::size(obj.as_slice())
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroU8, u8, U8);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroU16, u16, U16);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroU32, u32, U32);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroU64, u64, U64);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroU128, u128, U128);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroI8, i8, I8);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroI16, i16, I16);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroI32, i32, I32);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroI64, i64, I64);
+IMPLEMENT_SERDE_FOR_NONZERO!(NonZeroI128, i128, I128);
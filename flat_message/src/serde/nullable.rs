@@ -0,0 +1,382 @@
+use super::{SerDe, SerDeNullable};
+use common::data_format::DataFormat;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::ptr;
+
+// Layout of a list with missing values:
+// - number of elements (u32)
+// - type hash (u32) - see SerDeNullable::TYPE_HASH
+// - validity bitmap ((count + 7) / 8 bytes, bit `i` is set if the element `i` is present)
+// - padding up to the alignment of the data format of the elements
+// - the values that are present (one after another)
+const HEADER_SIZE: usize = 8;
+
+#[inline(always)]
+fn bitmap_len(count: usize) -> usize {
+    count.div_ceil(8)
+}
+
+#[inline(always)]
+fn is_set(bitmap: &[u8], index: usize) -> bool {
+    bitmap[index >> 3] & (1 << (index & 7)) != 0
+}
+
+#[inline(always)]
+fn count_set(bitmap: &[u8]) -> usize {
+    bitmap.iter().map(|b| b.count_ones() as usize).sum()
+}
+
+/// A bitmap is valid if it has exactly `count` bits (rounded up to a byte) and all
+/// the bits after the last element are 0.
+#[inline(always)]
+fn is_valid_bitmap(bitmap: &[u8], count: usize) -> bool {
+    if bitmap.len() != bitmap_len(count) {
+        return false;
+    }
+    let extra_bits = count & 7;
+    extra_bits == 0 || (bitmap[bitmap.len() - 1] >> extra_bits) == 0
+}
+
+/// Offset of the first value (relative to the start of the list).
+#[inline(always)]
+fn values_offset<'a, T: SerDeNullable<'a> + ?Sized>(count: usize) -> usize {
+    let alignament = T::DATA_FORMAT.alignament() as usize;
+    (HEADER_SIZE + bitmap_len(count) + alignament - 1) & !(alignament - 1)
+}
+
+/// Reads and validates the header and the validity bitmap of a list.
+/// Returns the number of elements, the bitmap and the position of the first value.
+#[inline(always)]
+fn read_header<'a, T: SerDeNullable<'a>>(buf: &'a [u8], pos: usize) -> Option<(usize, &'a [u8], usize)> {
+    if pos + HEADER_SIZE > buf.len() {
+        return None;
+    }
+    let p = buf.as_ptr();
+    let (count, type_hash) = unsafe {
+        (
+            ptr::read_unaligned(p.add(pos) as *const u32) as usize,
+            ptr::read_unaligned(p.add(pos + 4) as *const u32),
+        )
+    };
+    if type_hash != T::TYPE_HASH {
+        return None;
+    }
+    crate::limits::check_list_len(count)?;
+    let bitmap_end = pos + HEADER_SIZE + bitmap_len(count);
+    if bitmap_end > buf.len() {
+        return None;
+    }
+    let bitmap = &buf[pos + HEADER_SIZE..bitmap_end];
+    if !is_valid_bitmap(bitmap, count) {
+        return None;
+    }
+    Some((count, bitmap, pos + values_offset::<T>(count)))
+}
+
+/// Writes the header of a list (and the padding after the bitmap) and returns the position where the bitmap starts.
+#[inline(always)]
+unsafe fn write_header<'a, T: SerDeNullable<'a>>(count: usize, p: *mut u8, pos: usize) -> usize {
+    let bitmap_end = HEADER_SIZE + bitmap_len(count);
+    unsafe {
+        ptr::write_unaligned(p.add(pos) as *mut u32, count as u32);
+        ptr::write_unaligned(p.add(pos + 4) as *mut u32, T::TYPE_HASH);
+        ptr::write_bytes(p.add(pos + bitmap_end), 0, values_offset::<T>(count) - bitmap_end);
+    }
+    pos + HEADER_SIZE
+}
+
+/// A zero-copy view over a list with missing values. The values that are present are stored
+/// contiguously (in `values`) and the validity bitmap tells which elements are present
+/// (bit `i` is set if the element `i` is present).
+///
+/// A `NullableSlice<T>` uses the same binary format as a `Vec<Option<T>>` (the two types are
+/// interchangeable) but can only be deserialized for element types that are stored with the
+/// same memory layout as in the buffer (numeric types and enums).
+///
+/// # Example
+/// ```
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Samples<'a> {
+///     values: NullableSlice<'a, f32>,
+/// }
+///
+/// let mut storage = Storage::default();
+/// let values = NullableSlice::new(4, &[0b1011], &[1.0, 2.0, 4.0]).unwrap();
+/// Samples { values }.serialize_to(&mut storage, Config::default()).unwrap();
+/// let s = Samples::deserialize_from(&storage).unwrap();
+/// assert_eq!(s.values.values(), &[1.0, 2.0, 4.0]);
+/// assert_eq!(s.values.get(2), None);
+/// assert_eq!(s.values.get(3), Some(&4.0));
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct NullableSlice<'a, T> {
+    len: usize,
+    validity: &'a [u8],
+    values: &'a [T],
+}
+
+impl<'a, T> NullableSlice<'a, T> {
+    /// Creates a new view from the number of elements, the validity bitmap and the values that are present.
+    /// Returns `None` if the bitmap does not have exactly `(len + 7) / 8` bytes, if it has bits set after
+    /// the last element or if the number of bits that are set is different from the number of values.
+    pub fn new(len: usize, validity: &'a [u8], values: &'a [T]) -> Option<Self> {
+        if !is_valid_bitmap(validity, len) || count_set(validity) != values.len() {
+            return None;
+        }
+        Some(Self { len, validity, values })
+    }
+    /// Number of elements (including the missing ones).
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Number of missing elements.
+    #[inline(always)]
+    pub fn null_count(&self) -> usize {
+        self.len - self.values.len()
+    }
+    /// The values that are present (in order).
+    #[inline(always)]
+    pub fn values(&self) -> &'a [T] {
+        self.values
+    }
+    /// The validity bitmap (bit `i` is set if the element `i` is present).
+    #[inline(always)]
+    pub fn validity(&self) -> &'a [u8] {
+        self.validity
+    }
+    /// Returns `true` if the element at `index` is present.
+    #[inline(always)]
+    pub fn is_present(&self, index: usize) -> bool {
+        index < self.len && is_set(self.validity, index)
+    }
+    /// Returns the element at `index` or `None` if the element is missing (or `index` is out of bounds).
+    /// The position of the value is computed by counting the bits set before `index` - use `iter()`
+    /// to go through all the elements.
+    pub fn get(&self, index: usize) -> Option<&'a T> {
+        if !self.is_present(index) {
+            return None;
+        }
+        let full_bytes = index >> 3;
+        let partial = self.validity[full_bytes] & ((1u8 << (index & 7)) - 1);
+        let rank = count_set(&self.validity[..full_bytes]) + partial.count_ones() as usize;
+        self.values.get(rank)
+    }
+    /// Iterates through all the elements (`None` for the missing ones).
+    pub fn iter(&self) -> impl Iterator<Item = Option<&'a T>> + 'a {
+        let validity = self.validity;
+        let mut values = self.values.iter();
+        (0..self.len).map(move |index| if is_set(validity, index) { values.next() } else { None })
+    }
+    /// Converts the view into a vector.
+    pub fn to_vec(&self) -> Vec<Option<T>>
+    where
+        T: Clone,
+    {
+        self.iter().map(|v| v.cloned()).collect()
+    }
+}
+
+impl<T> Clone for NullableSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for NullableSlice<'_, T> {}
+
+impl<T> Default for NullableSlice<'_, T> {
+    fn default() -> Self {
+        Self {
+            len: 0,
+            validity: &[],
+            values: &[],
+        }
+    }
+}
+
+unsafe impl<'a, T> SerDe<'a> for NullableSlice<'a, T>
+where
+    T: SerDeNullable<'a> + 'a,
+{
+    const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        const { assert!(T::ZERO_COPY, "NullableSlice<T> requires a type that can be referenced directly from the buffer (use Vec<Option<T>> instead)") };
+        let p = buf.as_ptr();
+        unsafe {
+            let len = ptr::read_unaligned(p.add(pos) as *const u32) as usize;
+            let validity = std::slice::from_raw_parts(p.add(pos + HEADER_SIZE), bitmap_len(len));
+            let count = count_set(validity);
+            let values = if count == 0 {
+                &[]
+            } else {
+                std::slice::from_raw_parts(p.add(pos + values_offset::<T>(len)) as *const T, count)
+            };
+            Self { len, validity, values }
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        const { assert!(T::ZERO_COPY, "NullableSlice<T> requires a type that can be referenced directly from the buffer (use Vec<Option<T>> instead)") };
+        let (len, validity, values_pos) = read_header::<T>(buf, pos)?;
+        let count = count_set(validity);
+        if count == 0 {
+            return Some(Self { len, validity, values: &[] });
+        }
+        let end = count.checked_mul(std::mem::size_of::<T>())?.checked_add(values_pos)?;
+        if end > buf.len() {
+            return None;
+        }
+        // values are referenced directly from the buffer (the buffer must be properly aligned)
+        let p = unsafe { buf.as_ptr().add(values_pos) };
+        if !(p as usize).is_multiple_of(std::mem::align_of::<T>()) {
+            return None;
+        }
+        // validate each value (e.g. the variants of an enum)
+        let mut pos = values_pos;
+        for _ in 0..count {
+            let (_, next) = T::from_buffer(buf, pos)?;
+            pos = next;
+        }
+        let values = unsafe { std::slice::from_raw_parts(p as *const T, count) };
+        Some(Self { len, validity, values })
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            let bitmap_pos = write_header::<T>(obj.len, p, pos);
+            ptr::copy_nonoverlapping(obj.validity.as_ptr(), p.add(bitmap_pos), obj.validity.len());
+            let mut pos = pos + values_offset::<T>(obj.len);
+            for value in obj.values.iter() {
+                pos = T::write(value, p, pos);
+            }
+            pos
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        values_offset::<T>(obj.len) + obj.values.iter().map(|v| T::size(v)).sum::<usize>()
+    }
+}
+
+unsafe impl<'a, T> SerDe<'a> for Vec<Option<T>>
+where
+    T: SerDeNullable<'a>,
+{
+    const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        let p = buf.as_ptr();
+        unsafe {
+            let len = ptr::read_unaligned(p.add(pos) as *const u32) as usize;
+            let validity = std::slice::from_raw_parts(p.add(pos + HEADER_SIZE), bitmap_len(len));
+            let mut pos = pos + values_offset::<T>(len);
+            let mut result = Vec::with_capacity(len);
+            for index in 0..len {
+                if is_set(validity, index) {
+                    let (value, next) = T::from_buffer_unchecked(buf, pos);
+                    result.push(Some(value));
+                    pos = next;
+                } else {
+                    result.push(None);
+                }
+            }
+            result
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let (len, validity, mut pos) = read_header::<T>(buf, pos)?;
+        crate::limits::allocate::<Option<T>>(len)?;
+        // the number of elements is not validated yet (limit the initial allocation)
+        let mut result = Vec::with_capacity(len.min(1024));
+        for index in 0..len {
+            if is_set(validity, index) {
+                let (value, next) = T::from_buffer(buf, pos)?;
+                result.push(Some(value));
+                pos = next;
+            } else {
+                result.push(None);
+            }
+        }
+        Some(result)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            let bitmap_pos = write_header::<T>(obj.len(), p, pos);
+            for (index, chunk) in obj.chunks(8).enumerate() {
+                let mut bits = 0u8;
+                for (bit, value) in chunk.iter().enumerate() {
+                    if value.is_some() {
+                        bits |= 1 << bit;
+                    }
+                }
+                ptr::write_unaligned(p.add(bitmap_pos + index), bits);
+            }
+            let mut pos = pos + values_offset::<T>(obj.len());
+            for value in obj.iter().flatten() {
+                pos = T::write(value, p, pos);
+            }
+            pos
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        values_offset::<T>(obj.len()) + obj.iter().flatten().map(|v| T::size(v)).sum::<usize>()
+    }
+}
+
+// for these types a value in a list has the same format as the value itself
+macro_rules! IMPLEMENT_SERDE_NULLABLE_FOR {
+    ($t:ty, $zero_copy:expr) => {
+        unsafe impl<'a> SerDeNullable<'a> for $t {
+            const DATA_FORMAT: DataFormat = <$t as SerDe<'a>>::DATA_FORMAT;
+            const ZERO_COPY: bool = $zero_copy;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> (Self, usize) {
+                let value: $t = unsafe { SerDe::from_buffer_unchecked(buf, pos) };
+                let size = SerDe::size(&value);
+                (value, pos + size)
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<(Self, usize)> {
+                let value: $t = SerDe::from_buffer(buf, pos)?;
+                let size = SerDe::size(&value);
+                Some((value, pos + size))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                unsafe { SerDe::write(obj, p, pos) }
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                SerDe::size(obj)
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_NULLABLE_FOR!(u8, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(u16, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(u32, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(u64, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(u128, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(i8, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(i16, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(i32, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(i64, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(i128, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(f32, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(f64, true);
+IMPLEMENT_SERDE_NULLABLE_FOR!(String, false);
+IMPLEMENT_SERDE_NULLABLE_FOR!(&'a str, false);
+IMPLEMENT_SERDE_NULLABLE_FOR!(Ipv4Addr, false);
+IMPLEMENT_SERDE_NULLABLE_FOR!(Ipv6Addr, false);
+IMPLEMENT_SERDE_NULLABLE_FOR!(IpAddr, false);
@@ -0,0 +1,206 @@
+use super::SerDe;
+use super::SerDeVec;
+use crate::size;
+use common::data_format::DataFormat;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+// Paths and OS strings are stored like a string (length + bytes), but using the `Path` data format.
+// The bytes are the ones returned by `OsStr::as_encoded_bytes`:
+// - on Unix these are the raw bytes of the path (any byte sequence is preserved, even if it is not valid UTF-8)
+// - on other platforms (e.g. Windows) the encoding is not byte-preserving across platforms, so only values
+//   that are valid UTF-8 can be deserialized (otherwise `from_buffer` returns `None` and the deserialization
+//   fails with an error). Serialization never fails.
+const SIZE_FORMAT: size::Format = size::Format::U8withExtension;
+
+#[cfg(unix)]
+#[inline(always)]
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    use std::os::unix::ffi::OsStrExt;
+    Some(OsStr::from_bytes(bytes))
+}
+
+#[cfg(not(unix))]
+#[inline(always)]
+fn os_str_from_bytes(bytes: &[u8]) -> Option<&OsStr> {
+    std::str::from_utf8(bytes).ok().map(OsStr::new)
+}
+
+#[inline(always)]
+unsafe fn os_str_from_bytes_unchecked(bytes: &[u8]) -> &OsStr {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        OsStr::from_bytes(bytes)
+    }
+    #[cfg(not(unix))]
+    {
+        OsStr::from_encoded_bytes_unchecked(bytes)
+    }
+}
+
+#[inline(always)]
+unsafe fn read_unchecked(buf: &[u8], pos: usize) -> (&OsStr, usize) {
+    let p = buf.as_ptr();
+    let (len, slen) = size::read_unchecked(p, pos, SIZE_FORMAT);
+    let bytes = std::slice::from_raw_parts(p.add(pos + slen), len);
+    (os_str_from_bytes_unchecked(bytes), pos + slen + len)
+}
+
+#[inline(always)]
+fn read(buf: &[u8], pos: usize) -> Option<(&OsStr, usize)> {
+    let (len, slen) = size::read(buf.as_ptr(), pos, buf.len(), SIZE_FORMAT)?;
+    crate::limits::check_string_len(len)?;
+    let end = (pos + slen).checked_add(len)?;
+    if end > buf.len() {
+        None
+    } else {
+        Some((os_str_from_bytes(&buf[pos + slen..end])?, end))
+    }
+}
+
+#[inline(always)]
+unsafe fn write(value: &OsStr, p: *mut u8, pos: usize) -> usize {
+    let bytes = value.as_encoded_bytes();
+    unsafe {
+        let slen = size::write(p, pos, bytes.len(), SIZE_FORMAT);
+        std::ptr::copy_nonoverlapping(bytes.as_ptr(), p.add(pos + slen), bytes.len());
+        pos + slen + bytes.len()
+    }
+}
+
+#[inline(always)]
+fn len(value: &OsStr) -> usize {
+    let len = value.as_encoded_bytes().len();
+    size::len(len, SIZE_FORMAT) + len
+}
+
+// types that are stored as an `OsStr`
+trait OsStrRepr<'a> {
+    // owned values allocate a copy of the bytes from the buffer
+    const OWNED: bool;
+    fn from_os_str(value: &'a OsStr) -> Self;
+    fn as_os_str(&self) -> &OsStr;
+}
+impl<'a> OsStrRepr<'a> for &'a OsStr {
+    const OWNED: bool = false;
+    fn from_os_str(value: &'a OsStr) -> Self {
+        value
+    }
+    fn as_os_str(&self) -> &OsStr {
+        self
+    }
+}
+impl OsStrRepr<'_> for OsString {
+    const OWNED: bool = true;
+    fn from_os_str(value: &OsStr) -> Self {
+        value.to_os_string()
+    }
+    fn as_os_str(&self) -> &OsStr {
+        self.as_os_str()
+    }
+}
+impl<'a> OsStrRepr<'a> for &'a Path {
+    const OWNED: bool = false;
+    fn from_os_str(value: &'a OsStr) -> Self {
+        Path::new(value)
+    }
+    fn as_os_str(&self) -> &OsStr {
+        Path::as_os_str(self)
+    }
+}
+impl OsStrRepr<'_> for PathBuf {
+    const OWNED: bool = true;
+    fn from_os_str(value: &OsStr) -> Self {
+        PathBuf::from(value)
+    }
+    fn as_os_str(&self) -> &OsStr {
+        self.as_path().as_os_str()
+    }
+}
+
+#[inline(always)]
+fn read_value<'a, T: OsStrRepr<'a>>(buf: &'a [u8], pos: usize) -> Option<(T, usize)> {
+    let (value, next) = read(buf, pos)?;
+    if T::OWNED {
+        crate::limits::allocate::<u8>(value.len())?;
+    }
+    Some((T::from_os_str(value), next))
+}
+
+macro_rules! IMPLEMENT_SERDE_FOR_PATH {
+    ($t:ty) => {
+        unsafe impl<'a> SerDe<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::Path;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                <$t as OsStrRepr>::from_os_str(read_unchecked(buf, pos).0)
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                Some(read_value::<$t>(buf, pos)?.0)
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                write(OsStrRepr::as_os_str(obj), p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                len(OsStrRepr::as_os_str(obj))
+            }
+        }
+        unsafe impl<'a> SerDeVec<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::Path;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Vec<Self> {
+                let (count, slen) = size::read_unchecked(buf.as_ptr(), pos, SIZE_FORMAT);
+                let mut result = Vec::with_capacity(count);
+                let mut pos = pos + slen;
+                for _ in 0..count {
+                    let (value, next) = read_unchecked(buf, pos);
+                    result.push(<$t as OsStrRepr>::from_os_str(value));
+                    pos = next;
+                }
+                result
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Vec<Self>> {
+                let (count, slen) = size::read(buf.as_ptr(), pos, buf.len(), SIZE_FORMAT)?;
+                crate::limits::check_list_len(count)?;
+                crate::limits::allocate::<Self>(count)?;
+                // each value uses at least one byte (its size)
+                if (pos + slen).checked_add(count)? > buf.len() {
+                    return None;
+                }
+                let mut result = Vec::with_capacity(count.min(1024));
+                let mut pos = pos + slen;
+                for _ in 0..count {
+                    let (value, next) = read_value::<$t>(buf, pos)?;
+                    result.push(value);
+                    pos = next;
+                }
+                Some(result)
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+                let mut pos = pos + size::write(p, pos, obj.len(), SIZE_FORMAT);
+                for value in obj.iter() {
+                    pos = write(OsStrRepr::as_os_str(value), p, pos);
+                }
+                pos
+            }
+            #[inline(always)]
+            fn size(obj: &Vec<Self>) -> usize {
+                obj.iter()
+                    .fold(size::len(obj.len(), SIZE_FORMAT), |total, value| {
+                        total + len(OsStrRepr::as_os_str(value))
+                    })
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_FOR_PATH!(&'a OsStr);
+IMPLEMENT_SERDE_FOR_PATH!(OsString);
+IMPLEMENT_SERDE_FOR_PATH!(&'a Path);
+IMPLEMENT_SERDE_FOR_PATH!(PathBuf);
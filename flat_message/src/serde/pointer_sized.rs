@@ -0,0 +1,92 @@
+use super::SerDe;
+use super::SerDeSlice;
+use super::SerDeVec;
+use crate::size;
+use common::data_format::DataFormat;
+use std::ptr;
+
+// `usize` and `isize` are always stored on 64 bits (as `u64` and `i64`) so that a message
+// serialized on a 64-bit platform can be read on a 32-bit one (and vice-versa). Reading a value
+// that does not fit in the platform `usize` / `isize` is considered invalid.
+macro_rules! IMPLEMENT_SERDE_FOR_POINTER_SIZED {
+    ($t:ty, $wire:ty, $data_format:ident) => {
+        unsafe impl SerDe<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+                <$wire as SerDe>::from_buffer_unchecked(buf, pos) as $t
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+                <$t>::try_from(<$wire as SerDe>::from_buffer(buf, pos)?).ok()
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                <$wire as SerDe>::write(&(*obj as $wire), p, pos)
+            }
+            #[inline(always)]
+            fn size(_: &Self) -> usize {
+                8
+            }
+        }
+
+        // zero-copy slices are only possible if the platform representation matches the wire one
+        #[cfg(target_pointer_width = "64")]
+        unsafe impl<'a> SerDeSlice<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> &'a [Self] {
+                let res: &[$wire] = SerDeSlice::from_buffer_unchecked(buf, pos);
+                std::slice::from_raw_parts(res.as_ptr() as *const $t, res.len())
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
+                let res: &[$wire] = SerDeSlice::from_buffer(buf, pos)?;
+                Some(unsafe { std::slice::from_raw_parts(res.as_ptr() as *const $t, res.len()) })
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
+                let values = std::slice::from_raw_parts(obj.as_ptr() as *const $wire, obj.len());
+                SerDeSlice::write(values, p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &[Self]) -> usize {
+                size::len(obj.len(), size::Format::U32on64bits) + obj.len() * 8
+            }
+        }
+
+        unsafe impl SerDeVec<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::$data_format;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+                let res: &[$wire] = SerDeSlice::from_buffer_unchecked(buf, pos);
+                res.iter().map(|value| *value as $t).collect()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+                let res: &[$wire] = SerDeSlice::from_buffer(buf, pos)?;
+                crate::limits::allocate::<Self>(res.len())?;
+                res.iter().map(|value| <$t>::try_from(*value).ok()).collect()
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+                unsafe {
+                    let size_len = size::write(p, pos, obj.len(), size::Format::U32on64bits);
+                    let mut pos = pos + size_len;
+                    for value in obj {
+                        ptr::write_unaligned(p.add(pos) as *mut $wire, *value as $wire);
+                        pos += 8;
+                    }
+                    pos
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &Vec<Self>) -> usize {
+                size::len(obj.len(), size::Format::U32on64bits) + obj.len() * 8
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_FOR_POINTER_SIZED!(usize, u64, U64);
+IMPLEMENT_SERDE_FOR_POINTER_SIZED!(isize, i64, I64);
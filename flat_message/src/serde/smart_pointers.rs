@@ -0,0 +1,103 @@
+use super::{SerDe, SerDeSlice, SerDeVec, SerDeVecPointer};
+use common::data_format::DataFormat;
+use std::rc::Rc;
+use std::sync::Arc;
+
+// Box<T>, Rc<T> and Arc<T> are serialized exactly like T (str and [T] like &str and &[T], Vec<T> like a vector).
+// They are the only way to build recursive structures, but a recursion always goes through a
+// structure or a variant, and every one of those is counted against the maximum depth when deserializing.
+macro_rules! IMPLEMENT_SERDE_FOR_POINTER {
+    ($ptr:ident) => {
+        unsafe impl<'a, T: SerDe<'a>> SerDe<'a> for $ptr<T> {
+            const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                $ptr::new(T::from_buffer_unchecked(buf, pos))
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                Some($ptr::new(T::from_buffer(buf, pos)?))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                T::write(obj, p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                T::size(obj)
+            }
+        }
+
+        unsafe impl<'a> SerDe<'a> for $ptr<str> {
+            const DATA_FORMAT: DataFormat = DataFormat::String;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                let s: &str = SerDe::from_buffer_unchecked(buf, pos);
+                $ptr::from(s)
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                let s: &str = SerDe::from_buffer(buf, pos)?;
+                Some($ptr::from(s))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                SerDe::write(&&**obj, p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                <&str as SerDe>::size(&&**obj)
+            }
+        }
+
+        unsafe impl<'a, T> SerDe<'a> for $ptr<[T]>
+        where
+            T: SerDeSlice<'a> + SerDeVec<'a>,
+        {
+            const DATA_FORMAT: DataFormat = <T as SerDeSlice<'a>>::DATA_FORMAT;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                $ptr::from(<T as SerDeVec>::from_buffer_unchecked(buf, pos))
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                Some($ptr::from(<T as SerDeVec>::from_buffer(buf, pos)?))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                <T as SerDeSlice>::write(obj, p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                <T as SerDeSlice>::size(obj)
+            }
+        }
+
+        unsafe impl<'a, T> SerDeVecPointer<'a> for $ptr<Vec<T>>
+        where
+            T: SerDeVec<'a>,
+        {
+            const DATA_FORMAT: DataFormat = <T as SerDeVec<'a>>::DATA_FORMAT;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                $ptr::new(<T as SerDeVec>::from_buffer_unchecked(buf, pos))
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                Some($ptr::new(<T as SerDeVec>::from_buffer(buf, pos)?))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                <T as SerDeVec>::write(obj, p, pos)
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                <T as SerDeVec>::size(obj)
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_FOR_POINTER!(Box);
+IMPLEMENT_SERDE_FOR_POINTER!(Rc);
+IMPLEMENT_SERDE_FOR_POINTER!(Arc);
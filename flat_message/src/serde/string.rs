@@ -15,7 +15,8 @@ unsafe impl<'a> SerDe<'a> for &'a str {
     #[inline(always)]
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
         let (len, slen) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
-        let end = pos + slen + len;
+        crate::limits::check_string_len(len)?;
+        let end = (pos + slen).checked_add(len)?;
         if end > buf.len() {
             None
         } else {
@@ -25,16 +26,16 @@ unsafe impl<'a> SerDe<'a> for &'a str {
     }
     #[inline(always)]
     unsafe fn write(obj: &&str, p: *mut u8, pos: usize) -> usize {
-        let len = obj.len() as u32;
+        let len = obj.len();
         unsafe {
             let slen = size::write(p, pos, len, size::Format::U8withExtension);
             std::ptr::copy_nonoverlapping(obj.as_ptr(), p.add(pos + slen), obj.len());
-            pos + slen + len as usize
+            pos + slen + len
         }
     }
     #[inline(always)]
     fn size(obj: &&str) -> usize {
-        size::len(obj.len() as u32, size::Format::U8withExtension) + obj.len()
+        size::len(obj.len(), size::Format::U8withExtension) + obj.len()
     }
 }
 
@@ -49,6 +50,7 @@ unsafe impl SerDe<'_> for String {
     #[inline(always)]
     fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
         let v: &str = SerDe::from_buffer(buf, pos)?;
+        crate::limits::allocate::<u8>(v.len())?;
         Some(v.to_string())
     }
     #[inline(always)]
@@ -57,6 +59,6 @@ unsafe impl SerDe<'_> for String {
     }
     #[inline(always)]
     fn size(obj: &String) -> usize {
-        size::len(obj.len() as u32, size::Format::U8withExtension) + obj.len()
+        size::len(obj.len(), size::Format::U8withExtension) + obj.len()
     }
 }
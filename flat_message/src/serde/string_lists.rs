@@ -30,13 +30,15 @@ unsafe impl<'a> SerDeVec<'a> for &'a str {
     #[inline(always)]
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Vec<Self>> {
         let (count, slen) = size::read(buf.as_ptr(), pos, buf.len(), SIZE_FORMAT)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<Self>(count)?;
         if count == 0 {
             Some(Vec::new())
         } else {
             let p = buf.as_ptr();
             // assume -> minim one byte per string (with value 0 for pottential mpty strings)
             // minimal size should be count * 1+sled for all strings
-            let min_size = pos + slen + count;
+            let min_size = (pos + slen).checked_add(count)?;
             if min_size > buf.len() {
                 return None;
             }
@@ -44,7 +46,8 @@ unsafe impl<'a> SerDeVec<'a> for &'a str {
             let mut pos = pos + slen;
             for _ in 0..count {
                 let (len, size_len) = size::read(p, pos, buf.len(), SIZE_FORMAT)?;
-                let end = pos + size_len + len;
+                crate::limits::check_string_len(len)?;
+                let end = (pos + size_len).checked_add(len)?;
                 if end > buf.len() {
                     return None;
                 }
@@ -61,25 +64,25 @@ unsafe impl<'a> SerDeVec<'a> for &'a str {
     }
     #[inline(always)]
     unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
-        let count = obj.len() as u32;
+        let count = obj.len();
         unsafe {
             let count_len = size::write(p, pos, count, SIZE_FORMAT);
             let mut offset = pos + count_len;
             for s in obj.iter() {
-                let string_len = s.len() as u32;
+                let string_len = s.len();
                 let string_len_size = size::write(p, offset, string_len, SIZE_FORMAT);
                 offset += string_len_size;
                 std::ptr::copy_nonoverlapping(s.as_ptr(), p.add(offset), s.len());
-                offset += string_len as usize;
+                offset += string_len;
             }
             offset
         }
     }
     #[inline(always)]
     fn size(obj: &Vec<Self>) -> usize {
-        let mut total_size = size::len(obj.len() as u32, SIZE_FORMAT);
+        let mut total_size = size::len(obj.len(), SIZE_FORMAT);
         for s in obj.iter() {
-            total_size += size::len(s.len() as u32, SIZE_FORMAT) + s.len();
+            total_size += size::len(s.len(), SIZE_FORMAT) + s.len();
         }
         total_size
     }
@@ -114,13 +117,15 @@ unsafe impl<'a> SerDeVec<'a> for String {
     #[inline(always)]
     fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Vec<Self>> {
         let (count, slen) = size::read(buf.as_ptr(), pos, buf.len(), SIZE_FORMAT)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<Self>(count)?;
         if count == 0 {
             Some(Vec::new())
         } else {
             let p = buf.as_ptr();
             // assume -> minim one byte per string (with value 0 for pottential mpty strings)
             // minimal size should be count * 1+sled for all strings
-            let min_size = pos + slen + count;
+            let min_size = (pos + slen).checked_add(count)?;
             if min_size > buf.len() {
                 return None;
             }
@@ -128,12 +133,14 @@ unsafe impl<'a> SerDeVec<'a> for String {
             let mut pos = pos + slen;
             for _ in 0..count {
                 let (len, size_len) = size::read(p, pos, buf.len(), SIZE_FORMAT)?;
-                let end = pos + size_len + len;
+                crate::limits::check_string_len(len)?;
+                let end = (pos + size_len).checked_add(len)?;
                 if end > buf.len() {
                     return None;
                 }
                 let s = &buf[pos + size_len..end];
                 if let Ok(new_string_slice) = std::str::from_utf8(s) {
+                    crate::limits::allocate::<u8>(len)?;
                     result.push(new_string_slice.to_string());
                 } else {
                     return None;
@@ -145,25 +152,25 @@ unsafe impl<'a> SerDeVec<'a> for String {
     }
     #[inline(always)]
     unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
-        let count = obj.len() as u32;
+        let count = obj.len();
         unsafe {
             let count_len = size::write(p, pos, count, SIZE_FORMAT);
             let mut offset = pos + count_len;
             for s in obj.iter() {
-                let string_len = s.len() as u32;
+                let string_len = s.len();
                 let string_len_size = size::write(p, offset, string_len, SIZE_FORMAT);
                 offset += string_len_size;
                 std::ptr::copy_nonoverlapping(s.as_ptr(), p.add(offset), s.len());
-                offset += string_len as usize;
+                offset += string_len;
             }
             offset
         }
     }
     #[inline(always)]
     fn size(obj: &Vec<Self>) -> usize {
-        let mut total_size = size::len(obj.len() as u32, SIZE_FORMAT);
+        let mut total_size = size::len(obj.len(), SIZE_FORMAT);
         for s in obj.iter() {
-            total_size += size::len(s.len() as u32, SIZE_FORMAT) + s.len();
+            total_size += size::len(s.len(), SIZE_FORMAT) + s.len();
         }
         total_size
     }
@@ -0,0 +1,156 @@
+use super::varint::{len_u64, read_u64, read_u64_unchecked, write_u64};
+use super::SerDeStringRef;
+use crate::size;
+use common::data_format::DataFormat;
+use std::collections::HashMap;
+
+// When a message uses a string table (`#[flat_message_options(string_table = true)]`), every
+// distinct string is stored only once, in a hidden field that uses the format of a `Vec<&str>`.
+// String fields store the index of their value in that table:
+// - a string is stored as its index (LEB128)
+// - a list of strings is stored as the number of elements (size::Format::U8withExtension)
+//   followed by the index of each element (LEB128)
+//
+// The strings are added to the table in the order they are first found (the order of the fields
+// in the structure), so the same message is always serialized in the same way.
+
+/// Distinct strings of a message (built by the code generated for messages that use a string table).
+#[doc(hidden)]
+#[derive(Default)]
+pub struct StringTable<'a> {
+    indexes: HashMap<&'a str, u32>,
+    pub strings: Vec<&'a str>,
+}
+
+impl<'a> StringTable<'a> {
+    /// Adds a string to the table (if it was not already added).
+    #[inline(always)]
+    pub fn add(&mut self, value: &'a str) {
+        let next = self.strings.len() as u32;
+        if *self.indexes.entry(value).or_insert(next) == next {
+            self.strings.push(value);
+        }
+    }
+    /// Index of a string that was previously added to the table.
+    #[inline(always)]
+    pub fn index(&self, value: &str) -> u64 {
+        self.indexes[value] as u64
+    }
+}
+
+#[inline(always)]
+fn read_index<'a>(buf: &[u8], pos: usize, strings: &[&'a str]) -> Option<(&'a str, usize)> {
+    let (index, next) = read_u64(buf, pos)?;
+    Some((*strings.get(usize::try_from(index).ok()?)?, next))
+}
+
+#[inline(always)]
+unsafe fn read_index_unchecked<'a>(buf: &[u8], pos: usize, strings: &[&'a str]) -> (&'a str, usize) {
+    unsafe {
+        let index = read_u64_unchecked(buf, pos);
+        (*strings.get_unchecked(index as usize), pos + len_u64(index))
+    }
+}
+
+/// Reads the number of elements of a list and checks that the buffer has at least one byte for each of them.
+#[inline(always)]
+fn read_count(buf: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let (count, buf_len) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+    crate::limits::check_list_len(count)?;
+    let pos = pos + buf_len;
+    if count > buf.len() - pos {
+        None
+    } else {
+        Some((count, pos))
+    }
+}
+
+macro_rules! IMPLEMENT_STRING_REF_FOR {
+    ($t:ty, $owned:expr, $convert:expr) => {
+        unsafe impl<'a> SerDeStringRef<'a> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::StringRef;
+            #[inline(always)]
+            fn collect<'t>(obj: &'t Self, table: &mut StringTable<'t>) {
+                table.add(obj);
+            }
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize, strings: &[&'a str]) -> Self {
+                let value = unsafe { read_index_unchecked(buf, pos, strings).0 };
+                $convert(value)
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize, strings: &[&'a str]) -> Option<Self> {
+                let value = read_index(buf, pos, strings)?.0;
+                if $owned {
+                    crate::limits::allocate::<u8>(value.len())?;
+                }
+                Some($convert(value))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize, table: &StringTable) -> usize {
+                unsafe { write_u64(table.index(obj), p, pos) }
+            }
+            #[inline(always)]
+            fn size(obj: &Self, table: &StringTable) -> usize {
+                len_u64(table.index(obj))
+            }
+        }
+
+        unsafe impl<'a> SerDeStringRef<'a> for Vec<$t> {
+            const DATA_FORMAT: DataFormat = DataFormat::StringRef;
+            #[inline(always)]
+            fn collect<'t>(obj: &'t Self, table: &mut StringTable<'t>) {
+                for value in obj.iter() {
+                    table.add(value);
+                }
+            }
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize, strings: &[&'a str]) -> Self {
+                unsafe {
+                    let (count, buf_len) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+                    let mut pos = pos + buf_len;
+                    let mut result = Vec::with_capacity(count);
+                    for _ in 0..count {
+                        let (value, next) = read_index_unchecked(buf, pos, strings);
+                        result.push($convert(value));
+                        pos = next;
+                    }
+                    result
+                }
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize, strings: &[&'a str]) -> Option<Self> {
+                let (count, mut pos) = read_count(buf, pos)?;
+                crate::limits::allocate::<$t>(count)?;
+                let mut result = Vec::with_capacity(count.min(1024));
+                for _ in 0..count {
+                    let (value, next) = read_index(buf, pos, strings)?;
+                    if $owned {
+                        crate::limits::allocate::<u8>(value.len())?;
+                    }
+                    result.push($convert(value));
+                    pos = next;
+                }
+                Some(result)
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize, table: &StringTable) -> usize {
+                unsafe {
+                    let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+                    for value in obj.iter() {
+                        pos = write_u64(table.index(value), p, pos);
+                    }
+                    pos
+                }
+            }
+            #[inline(always)]
+            fn size(obj: &Self, table: &StringTable) -> usize {
+                size::len(obj.len(), size::Format::U8withExtension)
+                    + obj.iter().map(|value| len_u64(table.index(value))).sum::<usize>()
+            }
+        }
+    };
+}
+
+IMPLEMENT_STRING_REF_FOR!(&'a str, false, |value: &'a str| value);
+IMPLEMENT_STRING_REF_FOR!(String, true, |value: &'a str| value.to_string());
@@ -0,0 +1,201 @@
+use super::SerDe;
+use common::data_format::DataFormat;
+use common::hashes;
+use std::ptr;
+
+// Tuples are serialized as anonymous nested structures: they use the same layout as a
+// `FlatMessageStruct` (header, fields, hash table and offsets table) with the fields
+// named after their position in the tuple ("0", "1", ...).
+
+const NAMES: [&str; 8] = ["0", "1", "2", "3", "4", "5", "6", "7"];
+const TUPLE_NAME_HASH: u32 = hashes::fnv_32("tuple");
+
+#[inline(always)]
+const fn field_hash(index: usize, data_format: DataFormat) -> u32 {
+    (hashes::fnv_32(NAMES[index]) & 0xFFFF_FF00) | data_format as u32
+}
+
+const fn tuple_data_format(formats: &[DataFormat]) -> DataFormat {
+    // minimum alignment is 4 bytes (for hash table)
+    let mut align = 4;
+    let mut idx = 0;
+    while idx < formats.len() {
+        if formats[idx].alignament() > align {
+            align = formats[idx].alignament();
+        }
+        idx += 1;
+    }
+    match align {
+        8 => DataFormat::Struct8,
+        16 => DataFormat::Struct16,
+        _ => DataFormat::Struct4,
+    }
+}
+
+struct Layout<const N: usize> {
+    offsets: [usize; N],
+    ends: [usize; N],
+    hash_table_offset: usize,
+    offset_size: usize,
+    size: usize,
+}
+
+impl<const N: usize> Layout<N> {
+    #[inline(always)]
+    fn new(items: [(usize, DataFormat); N]) -> Self {
+        let mut offsets = [0; N];
+        let mut ends = [0; N];
+        let mut size = 8usize;
+        for (idx, (item_size, data_format)) in items.iter().enumerate() {
+            let align = data_format.alignament() as usize;
+            size = (size + align - 1) & !(align - 1);
+            offsets[idx] = size;
+            size += item_size;
+            ends[idx] = size;
+        }
+        let offset_size = if size < 0x100 {
+            1
+        } else if size < 0x10000 {
+            2
+        } else {
+            4
+        };
+        let hash_table_offset = (size + 3) & !3;
+        Layout {
+            offsets,
+            ends,
+            hash_table_offset,
+            offset_size,
+            size: hash_table_offset + N * (4 + offset_size),
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn write_header_and_tables(&self, p: *mut u8, pos: usize, formats: [DataFormat; N]) {
+        let flags = match self.offset_size {
+            1 => 0b0000_0000,
+            2 => 0b0000_0001,
+            _ => 0b0000_0010,
+        };
+        let sz_flags_pack: u32 = ((self.size as u32) << 8) | flags | (((N as u32) << 2) & 0xFF);
+        let buffer = p.add(pos);
+        ptr::write_unaligned(buffer as *mut u32, TUPLE_NAME_HASH);
+        ptr::write_unaligned(buffer.add(4) as *mut u32, sz_flags_pack);
+        let mut order: [usize; N] = std::array::from_fn(|idx| idx);
+        order.sort_unstable_by_key(|&idx| field_hash(idx, formats[idx]));
+        // padding before each element and before the hash table
+        let mut padding_start = 8;
+        for idx in 0..N {
+            ptr::write_bytes(buffer.add(padding_start), 0, self.offsets[idx] - padding_start);
+            padding_start = self.ends[idx];
+        }
+        ptr::write_bytes(buffer.add(padding_start), 0, self.hash_table_offset - padding_start);
+        let hash_table = buffer.add(self.hash_table_offset);
+        let offsets_table = hash_table.add(4 * N);
+        for (table_idx, &idx) in order.iter().enumerate() {
+            ptr::write_unaligned(hash_table.add(4 * table_idx) as *mut u32, field_hash(idx, formats[idx]));
+            let offset = self.offsets[idx];
+            match self.offset_size {
+                1 => ptr::write_unaligned(offsets_table.add(table_idx), offset as u8),
+                2 => ptr::write_unaligned(offsets_table.add(table_idx * 2) as *mut u16, offset as u16),
+                _ => ptr::write_unaligned(offsets_table.add(table_idx * 4) as *mut u32, offset as u32),
+            }
+        }
+    }
+}
+
+/// Returns the buffer of the tuple stored at `pos` and the offsets of its elements.
+fn read_offsets<const N: usize>(buf: &[u8], pos: usize, formats: [DataFormat; N]) -> Option<(&[u8], [usize; N])> {
+    let input = buf.get(pos..)?;
+    if input.len() < 8 {
+        return None;
+    }
+    let hash: u32 = unsafe { ptr::read_unaligned(input.as_ptr() as *const u32) };
+    let size_and_flags: u32 = unsafe { ptr::read_unaligned(input.as_ptr().add(4) as *const u32) };
+    if hash != TUPLE_NAME_HASH {
+        return None;
+    }
+    let fields_count = ((size_and_flags & 0xFF) >> 2) as usize;
+    let offset_size = match size_and_flags & 0b0000_0011 {
+        0 => 1,
+        1 => 2,
+        2 => 4,
+        _ => return None,
+    };
+    let len = (size_and_flags >> 8) as usize;
+    if len > input.len() || 8 + fields_count * (4 + offset_size) > len {
+        return None;
+    }
+    let input = &input[..len];
+    let hash_table_offset = len - fields_count * (4 + offset_size);
+    let p = input.as_ptr();
+    let hash_at = |idx: usize| unsafe { ptr::read_unaligned(p.add(hash_table_offset + idx * 4) as *const u32) };
+    let offsets_table = hash_table_offset + fields_count * 4;
+    let mut offsets = [0; N];
+    for (idx, data_format) in formats.iter().enumerate() {
+        // the hash table is sorted
+        let hash = field_hash(idx, *data_format);
+        let (mut left, mut right) = (0, fields_count);
+        while left < right {
+            let mid = (left + right) / 2;
+            if hash_at(mid) < hash {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+        let table_idx = left;
+        if table_idx == fields_count || hash_at(table_idx) != hash {
+            return None;
+        }
+        let offset = unsafe {
+            match offset_size {
+                1 => *p.add(offsets_table + table_idx) as usize,
+                2 => ptr::read_unaligned(p.add(offsets_table + table_idx * 2) as *const u16) as usize,
+                _ => ptr::read_unaligned(p.add(offsets_table + table_idx * 4) as *const u32) as usize,
+            }
+        };
+        if !(8..hash_table_offset).contains(&offset) {
+            return None;
+        }
+        offsets[idx] = offset;
+    }
+    Some((input, offsets))
+}
+
+macro_rules! IMPLEMENT_SERDE_FOR_TUPLE {
+    ($($t:ident : $idx:tt),+) => {
+        unsafe impl<'a, $($t: SerDe<'a>),+> SerDe<'a> for ($($t,)+) {
+            const DATA_FORMAT: DataFormat = tuple_data_format(&[$(<$t as SerDe<'a>>::DATA_FORMAT),+]);
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+                SerDe::from_buffer(buf, pos).unwrap()
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+                let (input, offsets) = read_offsets(buf, pos, [$(<$t as SerDe<'a>>::DATA_FORMAT),+])?;
+                Some(($(<$t as SerDe<'a>>::from_buffer(input, offsets[$idx])?,)+))
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                let layout = Layout::new([$((<$t as SerDe<'a>>::size(&obj.$idx), <$t as SerDe<'a>>::DATA_FORMAT)),+]);
+                layout.write_header_and_tables(p, pos, [$(<$t as SerDe<'a>>::DATA_FORMAT),+]);
+                $(<$t as SerDe<'a>>::write(&obj.$idx, p, pos + layout.offsets[$idx]);)+
+                pos + layout.size
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                Layout::new([$((<$t as SerDe<'a>>::size(&obj.$idx), <$t as SerDe<'a>>::DATA_FORMAT)),+]).size
+            }
+        }
+    };
+}
+
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1, T2: 2);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1, T2: 2, T3: 3);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6);
+IMPLEMENT_SERDE_FOR_TUPLE!(T0: 0, T1: 1, T2: 2, T3: 3, T4: 4, T5: 5, T6: 6, T7: 7);
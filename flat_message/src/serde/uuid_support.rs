@@ -0,0 +1,102 @@
+use super::SerDe;
+use super::SerDeSlice;
+use super::SerDeVec;
+use crate::size;
+use common::data_format::DataFormat;
+use uuid::Uuid;
+
+// An UUID is stored as its 16 bytes (big-endian, in the same order as `Uuid::as_bytes`).
+// `Uuid` is a transparent wrapper over `[u8; 16]` so slices can be read without copying.
+const UUID_SIZE: usize = 16;
+
+unsafe impl SerDe<'_> for Uuid {
+    const DATA_FORMAT: DataFormat = DataFormat::Uuid;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+        unsafe {
+            let ptr = buf.as_ptr().add(pos) as *const [u8; UUID_SIZE];
+            Uuid::from_bytes(std::ptr::read_unaligned(ptr))
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+        let bytes: [u8; UUID_SIZE] = buf.get(pos..pos + UUID_SIZE)?.try_into().ok()?;
+        Some(Uuid::from_bytes(bytes))
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            std::ptr::copy_nonoverlapping(obj.as_bytes().as_ptr(), p.add(pos), UUID_SIZE);
+            pos + UUID_SIZE
+        }
+    }
+    #[inline(always)]
+    fn size(_: &Self) -> usize {
+        UUID_SIZE
+    }
+}
+
+unsafe impl<'a> SerDeSlice<'a> for Uuid {
+    const DATA_FORMAT: DataFormat = DataFormat::Uuid;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> &'a [Self] {
+        let p = buf.as_ptr();
+        let (count, size_len) = size::read_unchecked(p, pos, size::Format::U8withExtension);
+        std::slice::from_raw_parts(p.add(pos + size_len) as *const Uuid, count)
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<&'a [Self]> {
+        let (count, size_len) =
+            size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+        crate::limits::check_list_len(count)?;
+        let end = count
+            .checked_mul(UUID_SIZE)
+            .and_then(|sz| sz.checked_add(pos + size_len))?;
+        if end > buf.len() {
+            None
+        } else {
+            Some(unsafe {
+                std::slice::from_raw_parts(buf.as_ptr().add(pos + size_len) as *const Uuid, count)
+            })
+        }
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            let size_len = size::write(p, pos, obj.len(), size::Format::U8withExtension);
+            std::ptr::copy_nonoverlapping(
+                obj.as_ptr() as *const u8,
+                p.add(pos + size_len),
+                obj.len() * UUID_SIZE,
+            );
+            pos + size_len + obj.len() * UUID_SIZE
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &[Self]) -> usize {
+        size::len(obj.len(), size::Format::U8withExtension) + obj.len() * UUID_SIZE
+    }
+}
+
+unsafe impl SerDeVec<'_> for Uuid {
+    const DATA_FORMAT: DataFormat = DataFormat::Uuid;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Vec<Self> {
+        let res: &[Uuid] = SerDeSlice::from_buffer_unchecked(buf, pos);
+        res.to_vec()
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &[u8], pos: usize) -> Option<Vec<Self>> {
+        let res: &[Uuid] = SerDeSlice::from_buffer(buf, pos)?;
+        crate::limits::allocate::<Self>(res.len())?;
+        Some(res.to_vec())
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Vec<Self>, p: *mut u8, pos: usize) -> usize {
+        SerDeSlice::write(obj.as_slice(), p, pos)
+    }
+    #[inline(always)]
+    fn size(obj: &Vec<Self>) -> usize {
+        size::len(obj.len(), size::Format::U8withExtension) + obj.len() * UUID_SIZE
+    }
+}
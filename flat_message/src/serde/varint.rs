@@ -0,0 +1,213 @@
+use super::SerDeVarint;
+use crate::size;
+use common::data_format::DataFormat;
+
+// A value is stored in LEB128 format: 7 bits per byte (the least significant group first) and the
+// most significant bit of each byte is set if more bytes follow. Signed values are zigzag encoded
+// first (0, -1, 1, -2, 2, ... are stored as 0, 1, 2, 3, 4, ...).
+// Only the shortest encoding of a value is accepted (e.g. `0x80 0x00` is not a valid encoding of 0).
+//
+// Layout of a vector:
+// - number of elements (size::Format::U8withExtension)
+// - each value (one after another)
+//
+// Integers of different sizes use the same format (a value written from an u64 field can be
+// read into an u32 field as long as it fits).
+
+macro_rules! IMPLEMENT_LEB128 {
+    ($t:ty, $len:ident, $write:ident, $read:ident, $read_unchecked:ident) => {
+        #[inline(always)]
+        pub(super) fn $len(mut value: $t) -> usize {
+            let mut len = 1;
+            while value >= 0x80 {
+                value >>= 7;
+                len += 1;
+            }
+            len
+        }
+        #[inline(always)]
+        pub(super) unsafe fn $write(mut value: $t, p: *mut u8, mut pos: usize) -> usize {
+            unsafe {
+                while value >= 0x80 {
+                    p.add(pos).write((value as u8) | 0x80);
+                    value >>= 7;
+                    pos += 1;
+                }
+                p.add(pos).write(value as u8);
+            }
+            pos + 1
+        }
+        /// Returns the value and the position right after it or `None` if the value does not fit
+        /// in the type or if it is not stored using the shortest encoding.
+        #[inline(always)]
+        pub(super) fn $read(buf: &[u8], mut pos: usize) -> Option<($t, usize)> {
+            let mut result: $t = 0;
+            let mut shift = 0;
+            loop {
+                let byte = *buf.get(pos)?;
+                pos += 1;
+                if shift >= <$t>::BITS {
+                    return None;
+                }
+                let bits = (byte & 0x7F) as $t;
+                if (bits << shift) >> shift != bits {
+                    return None;
+                }
+                result |= bits << shift;
+                if byte & 0x80 == 0 {
+                    return if byte == 0 && shift > 0 { None } else { Some((result, pos)) };
+                }
+                shift += 7;
+            }
+        }
+        #[inline(always)]
+        pub(super) unsafe fn $read_unchecked(buf: &[u8], mut pos: usize) -> $t {
+            let mut result: $t = 0;
+            let mut shift = 0u32;
+            loop {
+                let byte = unsafe { *buf.get_unchecked(pos) };
+                pos += 1;
+                result |= ((byte & 0x7F) as $t).wrapping_shl(shift);
+                if byte & 0x80 == 0 {
+                    return result;
+                }
+                shift += 7;
+            }
+        }
+    };
+}
+
+IMPLEMENT_LEB128!(u64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_LEB128!(u128, len_u128, write_u128, read_u128, read_u128_unchecked);
+
+#[inline(always)]
+fn zigzag_i64(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+#[inline(always)]
+fn unzigzag_i64(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+#[inline(always)]
+pub(super) fn zigzag_i128(value: i128) -> u128 {
+    ((value << 1) ^ (value >> 127)) as u128
+}
+#[inline(always)]
+pub(super) fn unzigzag_i128(value: u128) -> i128 {
+    ((value >> 1) as i128) ^ -((value & 1) as i128)
+}
+
+macro_rules! IMPLEMENT_VARINT_FOR_UNSIGNED {
+    ($t:ty, $base:ty, $len:ident, $write:ident, $read:ident, $read_unchecked:ident) => {
+        unsafe impl SerDeVarint<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::VarUInt;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+                unsafe { $read_unchecked(buf, pos) as $t }
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+                <$t>::try_from($read(buf, pos)?.0).ok()
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                unsafe { $write(*obj as $base, p, pos) }
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                $len(*obj as $base)
+            }
+        }
+    };
+}
+
+macro_rules! IMPLEMENT_VARINT_FOR_SIGNED {
+    ($t:ty, $base:ty, $zigzag:ident, $unzigzag:ident, $len:ident, $write:ident, $read:ident, $read_unchecked:ident) => {
+        unsafe impl SerDeVarint<'_> for $t {
+            const DATA_FORMAT: DataFormat = DataFormat::VarInt;
+            #[inline(always)]
+            unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> Self {
+                unsafe { $unzigzag($read_unchecked(buf, pos)) as $t }
+            }
+            #[inline(always)]
+            fn from_buffer(buf: &[u8], pos: usize) -> Option<Self> {
+                <$t>::try_from($unzigzag($read(buf, pos)?.0)).ok()
+            }
+            #[inline(always)]
+            unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                unsafe { $write($zigzag(*obj as $base), p, pos) }
+            }
+            #[inline(always)]
+            fn size(obj: &Self) -> usize {
+                $len($zigzag(*obj as $base))
+            }
+        }
+    };
+}
+
+IMPLEMENT_VARINT_FOR_UNSIGNED!(u8, u64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_UNSIGNED!(u16, u64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_UNSIGNED!(u32, u64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_UNSIGNED!(u64, u64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_UNSIGNED!(usize, u64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_UNSIGNED!(u128, u128, len_u128, write_u128, read_u128, read_u128_unchecked);
+IMPLEMENT_VARINT_FOR_SIGNED!(i8, i64, zigzag_i64, unzigzag_i64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_SIGNED!(i16, i64, zigzag_i64, unzigzag_i64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_SIGNED!(i32, i64, zigzag_i64, unzigzag_i64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_SIGNED!(i64, i64, zigzag_i64, unzigzag_i64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_SIGNED!(isize, i64, zigzag_i64, unzigzag_i64, len_u64, write_u64, read_u64, read_u64_unchecked);
+IMPLEMENT_VARINT_FOR_SIGNED!(i128, i128, zigzag_i128, unzigzag_i128, len_u128, write_u128, read_u128, read_u128_unchecked);
+
+unsafe impl<'a, T> SerDeVarint<'a> for Vec<T>
+where
+    T: SerDeVarint<'a>,
+{
+    const DATA_FORMAT: DataFormat = T::DATA_FORMAT;
+    #[inline(always)]
+    unsafe fn from_buffer_unchecked(buf: &'a [u8], pos: usize) -> Self {
+        unsafe {
+            let (count, buf_len) = size::read_unchecked(buf.as_ptr(), pos, size::Format::U8withExtension);
+            let mut pos = pos + buf_len;
+            let mut result = Vec::with_capacity(count);
+            for _ in 0..count {
+                let value = T::from_buffer_unchecked(buf, pos);
+                pos += T::size(&value);
+                result.push(value);
+            }
+            result
+        }
+    }
+    #[inline(always)]
+    fn from_buffer(buf: &'a [u8], pos: usize) -> Option<Self> {
+        let (count, buf_len) = size::read(buf.as_ptr(), pos, buf.len(), size::Format::U8withExtension)?;
+        crate::limits::check_list_len(count)?;
+        crate::limits::allocate::<T>(count)?;
+        let mut pos = pos + buf_len;
+        // every value needs at least one byte
+        if count > buf.len() - pos {
+            return None;
+        }
+        let mut result = Vec::with_capacity(count.min(1024));
+        for _ in 0..count {
+            // only the shortest encoding is accepted, so `size` is the number of bytes that were read
+            let value = T::from_buffer(buf, pos)?;
+            pos += T::size(&value);
+            result.push(value);
+        }
+        Some(result)
+    }
+    #[inline(always)]
+    unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+        unsafe {
+            let mut pos = pos + size::write(p, pos, obj.len(), size::Format::U8withExtension);
+            for value in obj.iter() {
+                pos = T::write(value, p, pos);
+            }
+            pos
+        }
+    }
+    #[inline(always)]
+    fn size(obj: &Self) -> usize {
+        size::len(obj.len(), size::Format::U8withExtension) + obj.iter().map(|v| T::size(v)).sum::<usize>()
+    }
+}
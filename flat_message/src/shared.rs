@@ -0,0 +1,286 @@
+use crate::Storage;
+use bytes::Bytes;
+use std::borrow::Borrow;
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::Arc;
+
+// While a message is deserialized with `FlatMessage::deserialize_from_shared`, the buffer it is read
+// from is stored in a thread-local state (`SerDe::from_buffer` only receives a `&[u8]`). `Bytes` and
+// `SharedStr` fields are then created as reference counted views into that buffer. Outside of
+// `deserialize_from_shared` (or for data that is not part of the buffer) the data is copied.
+
+thread_local! {
+    static SOURCE: RefCell<Option<Bytes>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `source` as the buffer that `Bytes` and `SharedStr` values are shared from.
+pub(crate) fn with_source<R>(source: Bytes, f: impl FnOnce() -> R) -> R {
+    struct Restore(Option<Bytes>);
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            let previous = self.0.take();
+            SOURCE.with(|s| *s.borrow_mut() = previous);
+        }
+    }
+    let _restore = Restore(SOURCE.with(|s| s.replace(Some(source))));
+    f()
+}
+
+/// Returns `data` as a `Bytes` value (a view into the current source if `data` is part of it, or a copy).
+pub(crate) fn share(data: &[u8]) -> Option<Bytes> {
+    let shared = SOURCE.with(|s| {
+        s.borrow().as_ref().and_then(|source| {
+            let start = source.as_ptr() as usize;
+            let data_start = data.as_ptr() as usize;
+            if data_start >= start && data_start + data.len() <= start + source.len() {
+                Some(source.slice_ref(data))
+            } else {
+                None
+            }
+        })
+    });
+    match shared {
+        Some(bytes) => Some(bytes),
+        None => {
+            crate::limits::allocate::<u8>(data.len())?;
+            Some(Bytes::copy_from_slice(data))
+        }
+    }
+}
+
+/// Returns `s` as a `SharedStr` (see [`share`]).
+#[inline(always)]
+pub(crate) fn share_str(s: &str) -> Option<SharedStr> {
+    Some(SharedStr(share(s.as_bytes())?))
+}
+
+/// A [`Storage`] that can be shared between threads (reference counted).
+///
+/// Deserializing a message from a `SharedStorage` (with [`FlatMessage::deserialize_from_shared`](crate::FlatMessage::deserialize_from_shared))
+/// creates `bytes::Bytes` and [`SharedStr`] fields as views into the buffer (no copy). A structure
+/// without lifetimes that only uses these types for its strings and buffers is `'static`, so it can
+/// be moved to other threads or tasks while the buffer is kept alive by the fields that use it.
+///
+/// ```rust
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Packet {
+///     name: SharedStr,
+///     payload: bytes::Bytes,
+/// }
+///
+/// let mut storage = Storage::default();
+/// let packet = Packet { name: SharedStr::from("sensor"), payload: bytes::Bytes::from_static(&[1, 2, 3]) };
+/// packet.serialize_to(&mut storage, Config::default()).unwrap();
+///
+/// let shared = SharedStorage::from(storage);
+/// let packet = Packet::deserialize_from_shared(&shared).unwrap();
+/// drop(shared);
+/// let handle = std::thread::spawn(move || (packet.name.len(), packet.payload.len()));
+/// assert_eq!(handle.join().unwrap(), (6, 3));
+/// ```
+#[derive(Clone, Default, PartialEq)]
+pub struct SharedStorage {
+    storage: Arc<Storage>,
+}
+
+impl SharedStorage {
+    /// Creates a shared buffer from a `Storage` (no copy).
+    pub fn new(storage: Storage) -> Self {
+        Self {
+            storage: Arc::new(storage),
+        }
+    }
+
+    /// Returns the buffer as a `Bytes` value (no copy - the buffer is kept alive by the result).
+    pub fn to_bytes(&self) -> Bytes {
+        Bytes::from_owner(self.clone())
+    }
+
+    /// Returns the `Storage` if this is the only reference to it (otherwise returns `self`).
+    pub fn try_into_storage(self) -> Result<Storage, Self> {
+        Arc::try_unwrap(self.storage).map_err(|storage| Self { storage })
+    }
+}
+
+impl Deref for SharedStorage {
+    type Target = Storage;
+    #[inline(always)]
+    fn deref(&self) -> &Storage {
+        &self.storage
+    }
+}
+
+impl AsRef<[u8]> for SharedStorage {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.storage.as_slice()
+    }
+}
+
+impl fmt::Debug for SharedStorage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&*self.storage, f)
+    }
+}
+
+impl From<Storage> for SharedStorage {
+    fn from(storage: Storage) -> Self {
+        Self::new(storage)
+    }
+}
+
+/// Copies the content of `bytes` (a `Storage` is always aligned to 16 bytes).
+impl From<Bytes> for SharedStorage {
+    fn from(bytes: Bytes) -> Self {
+        Self::new(Storage::from(bytes))
+    }
+}
+
+/// Converts a `Storage` into `Bytes` without copying the content.
+impl From<Storage> for Bytes {
+    fn from(storage: Storage) -> Self {
+        Bytes::from_owner(storage)
+    }
+}
+
+/// Copies the content of `bytes` (a `Storage` is always aligned to 16 bytes).
+impl From<Bytes> for Storage {
+    fn from(bytes: Bytes) -> Self {
+        Storage::from_buffer(&bytes)
+    }
+}
+
+/// A reference counted UTF-8 string (a `bytes::Bytes` value that is known to hold a valid string).
+///
+/// A `SharedStr` is serialized in the same way as a `String` or a `&str` (the types are interchangeable).
+/// When it is deserialized with [`FlatMessage::deserialize_from_shared`](crate::FlatMessage::deserialize_from_shared)
+/// the string is a view into the buffer of the message (otherwise it is copied).
+#[derive(Clone, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SharedStr(Bytes);
+
+impl SharedStr {
+    /// Creates a `SharedStr` from a static string (no copy).
+    pub const fn from_static(s: &'static str) -> Self {
+        Self(Bytes::from_static(s.as_bytes()))
+    }
+
+    /// Creates a `SharedStr` from a `Bytes` value (returns an error if it is not a valid UTF-8 string).
+    pub fn from_utf8(bytes: Bytes) -> Result<Self, std::str::Utf8Error> {
+        std::str::from_utf8(&bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// Creates a `SharedStr` from a `Bytes` value without checking that it is a valid UTF-8 string.
+    ///
+    /// # Safety
+    /// `bytes` must be a valid UTF-8 string.
+    pub unsafe fn from_utf8_unchecked(bytes: Bytes) -> Self {
+        Self(bytes)
+    }
+
+    /// Returns the string.
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        // the content is validated when the value is created
+        unsafe { std::str::from_utf8_unchecked(&self.0) }
+    }
+
+    /// Returns the bytes of the string.
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &Bytes {
+        &self.0
+    }
+
+    /// Converts the string into a `Bytes` value.
+    #[inline(always)]
+    pub fn into_bytes(self) -> Bytes {
+        self.0
+    }
+}
+
+impl Deref for SharedStr {
+    type Target = str;
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl AsRef<str> for SharedStr {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for SharedStr {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Hash for SharedStr {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.as_str().hash(state)
+    }
+}
+
+impl fmt::Debug for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for SharedStr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl From<&str> for SharedStr {
+    fn from(s: &str) -> Self {
+        Self(Bytes::copy_from_slice(s.as_bytes()))
+    }
+}
+
+impl From<String> for SharedStr {
+    fn from(s: String) -> Self {
+        Self(Bytes::from(s))
+    }
+}
+
+impl From<SharedStr> for Bytes {
+    fn from(s: SharedStr) -> Self {
+        s.0
+    }
+}
+
+impl From<SharedStr> for String {
+    fn from(s: SharedStr) -> Self {
+        s.as_str().to_string()
+    }
+}
+
+impl PartialEq<str> for SharedStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for SharedStr {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for SharedStr {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
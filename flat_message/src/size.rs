@@ -1,3 +1,8 @@
+/// Formats used to store the size of a value (the length of a string, the number of elements of a slice).
+///
+/// Sizes that do not fit in 32 bits are stored as the largest 32 bit value (`0xFFFF_FFFF`) followed by
+/// the size on 64 bits (except for the `U32onNNbits` formats that already reserve 8 bytes or more and
+/// store the size on 64 bits directly).
 #[derive(Copy, Clone)]
 pub enum Format {
     U8withExtension,
@@ -8,23 +13,78 @@ pub enum Format {
     U32on128bits,
 }
 
+/// Marks a size that is stored on 64 bits (after the 32 bit value).
+const U64_MARKER: u32 = 0xFFFF_FFFF;
+
 #[inline(always)]
-pub unsafe fn write(p: *mut u8, pos: usize, value: u32, method: Format) -> usize {
-    match method {
-        Format::U32 => unsafe {
-            (p.add(pos) as *mut u32).write_unaligned(value);
+unsafe fn write_u32_or_u64(p: *mut u8, value: usize) -> usize {
+    unsafe {
+        if value < U64_MARKER as usize {
+            (p as *mut u32).write_unaligned(value as u32);
             4
-        },
+        } else {
+            (p as *mut u32).write_unaligned(U64_MARKER);
+            (p.add(4) as *mut u64).write_unaligned(value as u64);
+            12
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn read_u32_or_u64_unchecked(p: *const u8) -> (usize, usize) {
+    unsafe {
+        let value = (p as *const u32).read_unaligned();
+        if value < U64_MARKER {
+            (value as usize, 4)
+        } else {
+            ((p.add(4) as *const u64).read_unaligned() as usize, 12)
+        }
+    }
+}
+
+/// Reads a size stored on 32 bits (or 64 bits) starting from `pos`.
+#[inline(always)]
+fn read_u32_or_u64(p: *const u8, pos: usize, len: usize) -> Option<(usize, usize)> {
+    if pos + 4 > len {
+        return None;
+    }
+    let value = unsafe { (p.add(pos) as *const u32).read_unaligned() };
+    if value < U64_MARKER {
+        Some((value as usize, 4))
+    } else if pos + 12 > len {
+        None
+    } else {
+        let value = unsafe { (p.add(pos + 4) as *const u64).read_unaligned() };
+        Some((usize::try_from(value).ok()?, 12))
+    }
+}
+
+#[inline(always)]
+fn read_u64(p: *const u8, pos: usize, len: usize, size: usize) -> Option<(usize, usize)> {
+    if pos + size > len {
+        None
+    } else {
+        let value = unsafe { (p.add(pos) as *const u64).read_unaligned() };
+        Some((usize::try_from(value).ok()?, size))
+    }
+}
+
+#[inline(always)]
+pub unsafe fn write(p: *mut u8, pos: usize, value: usize, method: Format) -> usize {
+    match method {
+        Format::U32 => unsafe { write_u32_or_u64(p.add(pos), value) },
         Format::U32on64bits => unsafe {
-            (p.add(pos) as *mut u32).write_unaligned(value);
+            (p.add(pos) as *mut u64).write_unaligned(value as u64);
             8
         },
         Format::U32on96bits => unsafe {
-            (p.add(pos) as *mut u32).write_unaligned(value);
+            (p.add(pos) as *mut u64).write_unaligned(value as u64);
+            (p.add(pos + 8) as *mut u32).write_unaligned(0);
             12
-        },        
+        },
         Format::U32on128bits => unsafe {
-            (p.add(pos) as *mut u32).write_unaligned(value);
+            (p.add(pos) as *mut u64).write_unaligned(value as u64);
+            (p.add(pos + 8) as *mut u64).write_unaligned(0);
             16
         },
         Format::U16withExtension => unsafe {
@@ -34,8 +94,7 @@ pub unsafe fn write(p: *mut u8, pos: usize, value: u32, method: Format) -> usize
             } else {
                 let p = p.add(pos);
                 (p as *mut u16).write_unaligned(0xFFFFu16);
-                (p.add(2) as *mut u32).write_unaligned(value);
-                6
+                2 + write_u32_or_u64(p.add(2), value)
             }
         },
         Format::U8withExtension => unsafe {
@@ -50,8 +109,7 @@ pub unsafe fn write(p: *mut u8, pos: usize, value: u32, method: Format) -> usize
             } else {
                 let p = p.add(pos);
                 p.write_unaligned(0xFFu8);
-                (p.add(1) as *mut u32).write_unaligned(value);
-                5
+                1 + write_u32_or_u64(p.add(1), value)
             }
         },
     }
@@ -60,17 +118,18 @@ pub unsafe fn write(p: *mut u8, pos: usize, value: u32, method: Format) -> usize
 #[inline(always)]
 pub unsafe fn read_unchecked(p: *const u8, pos: usize, method: Format) -> (usize, usize) {
     match method {
-        Format::U32 => ((p.add(pos) as *mut u32).read_unaligned() as usize, 4),
-        Format::U32on64bits => ((p.add(pos) as *mut u32).read_unaligned() as usize, 8),
-        Format::U32on96bits => ((p.add(pos) as *mut u32).read_unaligned() as usize, 12),
-        Format::U32on128bits => ((p.add(pos) as *mut u32).read_unaligned() as usize, 16),
+        Format::U32 => read_u32_or_u64_unchecked(p.add(pos)),
+        Format::U32on64bits => ((p.add(pos) as *mut u64).read_unaligned() as usize, 8),
+        Format::U32on96bits => ((p.add(pos) as *mut u64).read_unaligned() as usize, 12),
+        Format::U32on128bits => ((p.add(pos) as *mut u64).read_unaligned() as usize, 16),
         Format::U16withExtension => {
             let p = p.add(pos);
             let first = (p as *const u16).read_unaligned();
             if first < 0xFFFF {
                 (first as usize, 2)
             } else {
-                ((p.add(2) as *mut u32).read_unaligned() as usize, 6)
+                let (value, len) = read_u32_or_u64_unchecked(p.add(2));
+                (value, len + 2)
             }
         }
         Format::U8withExtension => {
@@ -78,7 +137,10 @@ pub unsafe fn read_unchecked(p: *const u8, pos: usize, method: Format) -> (usize
             let first = p.read_unaligned();
             match first {
                 0xFE => ((p.add(1) as *mut u16).read_unaligned() as usize, 3),
-                0xFF => ((p.add(1) as *mut u32).read_unaligned() as usize, 5),
+                0xFF => {
+                    let (value, len) = read_u32_or_u64_unchecked(p.add(1));
+                    (value, len + 1)
+                }
                 _ => (first as usize, 1),
             }
         }
@@ -88,81 +150,39 @@ pub unsafe fn read_unchecked(p: *const u8, pos: usize, method: Format) -> (usize
 #[inline(always)]
 pub fn read(p: *const u8, pos: usize, len: usize, method: Format) -> Option<(usize, usize)> {
     match method {
-        Format::U32 => {
-            if pos + 4 > len {
-                None
-            } else {
-                Some((
-                    unsafe { (p.add(pos) as *mut u32).read_unaligned() as usize },
-                    4,
-                ))
-            }
-        }
-        Format::U32on64bits => {
-            if pos + 8 > len {
-                None
-            } else {
-                Some((
-                    unsafe { (p.add(pos) as *mut u32).read_unaligned() as usize },
-                    8,
-                ))
-            }
-        }
-        Format::U32on96bits => {
-            if pos + 12 > len {
-                None
-            } else {
-                Some((
-                    unsafe { (p.add(pos) as *mut u32).read_unaligned() as usize },
-                    12,
-                ))
-            }
-        }        
-        Format::U32on128bits => {
-            if pos + 16 > len {
-                None
-            } else {
-                Some((
-                    unsafe { (p.add(pos) as *mut u32).read_unaligned() as usize },
-                    16,
-                ))
-            }
-        }
+        Format::U32 => read_u32_or_u64(p, pos, len),
+        Format::U32on64bits => read_u64(p, pos, len, 8),
+        Format::U32on96bits => read_u64(p, pos, len, 12),
+        Format::U32on128bits => read_u64(p, pos, len, 16),
         Format::U16withExtension => {
             if pos + 2 > len {
                 None
             } else {
-                let p = unsafe { p.add(pos) };
-                let first = unsafe { (p as *const u16).read_unaligned() };
+                let first = unsafe { (p.add(pos) as *const u16).read_unaligned() };
                 if first < 0xFFFF {
                     Some((first as usize, 2))
-                } else if pos + 6 > len {
-                    None
                 } else {
-                    Some((
-                        unsafe { (p.add(2) as *mut u32).read_unaligned() as usize },
-                        6,
-                    ))
+                    let (value, size_len) = read_u32_or_u64(p, pos + 2, len)?;
+                    Some((value, size_len + 2))
                 }
             }
         }
         Format::U8withExtension => unsafe {
-            let p = p.add(pos);
-            let first = p.read_unaligned();
+            if pos >= len {
+                return None;
+            }
+            let first = p.add(pos).read_unaligned();
             match first {
                 0xFE => {
                     if pos + 3 > len {
                         None
                     } else {
-                        Some(((p.add(1) as *mut u16).read_unaligned() as usize, 3))
+                        Some(((p.add(pos + 1) as *mut u16).read_unaligned() as usize, 3))
                     }
                 }
                 0xFF => {
-                    if pos + 5 > len {
-                        None
-                    } else {
-                        Some(((p.add(1) as *mut u32).read_unaligned() as usize, 5))
-                    }
+                    let (value, size_len) = read_u32_or_u64(p, pos + 1, len)?;
+                    Some((value, size_len + 1))
                 }
                 _ => Some((first as usize, 1)),
             }
@@ -171,9 +191,10 @@ pub fn read(p: *const u8, pos: usize, len: usize, method: Format) -> Option<(usi
 }
 
 #[inline(always)]
-pub fn len(value: u32, method: Format) -> usize {
+pub fn len(value: usize, method: Format) -> usize {
+    let u32_or_u64 = if value < U64_MARKER as usize { 4 } else { 12 };
     match method {
-        Format::U32 => 4,
+        Format::U32 => u32_or_u64,
         Format::U32on64bits => 8,
         Format::U32on96bits => 12,
         Format::U32on128bits => 16,
@@ -183,14 +204,14 @@ pub fn len(value: u32, method: Format) -> usize {
             } else if value < 0x10000 {
                 3
             } else {
-                5
+                1 + u32_or_u64
             }
         }
         Format::U16withExtension => {
             if value < 0xFFFF {
                 2
             } else {
-                6
+                2 + u32_or_u64
             }
         }
     }
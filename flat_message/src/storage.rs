@@ -1,3 +1,4 @@
+use std::io::{self, Read, Write};
 use std::{fmt::Debug, slice};
 
 #[derive(Default)]
@@ -48,6 +49,12 @@ impl Storage {
         self.size
     }
 
+    /// Returns the number of bytes the buffer can hold without allocating more memory.
+    #[inline(always)]
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity() * std::mem::size_of::<u128>()
+    }
+
     /// Returns whether the length of the data stored in the `Storage` instance is zero.
     pub fn is_empty(&self) -> bool {
         self.size == 0
@@ -63,11 +70,58 @@ impl Storage {
     /// Resizes the buffer to the specified length, initializing additional bytes to 0.
     #[inline]
     pub fn resize_zero(&mut self, new_len: usize) {
-        self.vec
-            .resize(new_len / std::mem::size_of::<u128>() + 1, 0);
+        let old_len = self.size;
+        self.resize(new_len);
+        if new_len > old_len {
+            self.as_mut_slice()[old_len..].fill(0);
+        }
+    }
+
+    /// Resizes the buffer to the specified length without clearing it (the bytes that are already
+    /// part of the buffer keep their values and only newly allocated memory is set to 0).
+    ///
+    /// This is what serialization uses: a reused `Storage` is not zeroed again for every message
+    /// (the serialization code writes every byte of a message, including the padding bytes).
+    #[inline]
+    pub fn resize(&mut self, new_len: usize) {
+        let units = new_len / std::mem::size_of::<u128>() + 1;
+        if units > self.vec.len() {
+            self.vec.resize(units, 0);
+        }
         self.size = new_len;
     }
 
+    /// Writes the content of the buffer as a frame: the size of the message (u64, little endian)
+    /// followed by the message. Frames are read back with [`Storage::read_frame`].
+    pub fn write_frame<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&(self.size as u64).to_le_bytes())?;
+        writer.write_all(self.as_slice())
+    }
+
+    /// Reads a frame (written with [`Storage::write_frame`]) into the buffer. Returns `Ok(false)` if the
+    /// reader has no more data and an `InvalidData` error if the frame is larger than `max_size` bytes.
+    pub fn read_frame<R: Read>(&mut self, reader: &mut R, max_size: u64) -> io::Result<bool> {
+        let mut header = [0u8; 8];
+        let mut read = 0;
+        while read < header.len() {
+            match reader.read(&mut header[read..]) {
+                Ok(0) if read == 0 => return Ok(false),
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(count) => read += count,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        let size = u64::from_le_bytes(header);
+        if size > max_size {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "frame is larger than the maximum size"));
+        }
+        let size = usize::try_from(size).map_err(|_| io::Error::from(io::ErrorKind::InvalidData))?;
+        self.resize(size);
+        reader.read_exact(self.as_mut_slice())?;
+        Ok(true)
+    }
+
     /// Returns a slice of the buffer.
     #[inline]
     pub fn as_slice(&self) -> &[u8] {
@@ -87,6 +141,13 @@ impl Debug for Storage {
     }
 }
 
+impl AsRef<[u8]> for Storage {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
 impl PartialEq<Storage> for Storage {
     fn eq(&self, other: &Storage) -> bool {
         self.as_slice() == other.as_slice()
@@ -0,0 +1,148 @@
+use crate::Storage;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+/// A thread-safe pool of [`Storage`] buffers.
+///
+/// Serializing into a buffer that was already used does not zero it again (only the padding bytes
+/// of a message are written with 0), so reusing buffers avoids both the allocation and the cost of
+/// clearing the memory for every message. A pool keeps at most `max_pooled` buffers, and buffers
+/// that grew over `max_capacity` bytes are released instead of being kept (so that one very large
+/// message does not keep a large allocation alive).
+///
+/// ```rust
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Ping {
+///     seq: u64,
+///     source: String,
+/// }
+///
+/// static POOL: StoragePool = StoragePool::new(16, 64 * 1024);
+///
+/// let handles: Vec<_> = (0..4)
+///     .map(|seq| {
+///         std::thread::spawn(move || {
+///             let mut storage = POOL.get();
+///             let ping = Ping { seq, source: "client".to_string() };
+///             ping.serialize_to(&mut storage, Config::default()).unwrap();
+///             storage.len()
+///         })
+///     })
+///     .collect();
+/// for handle in handles {
+///     assert!(handle.join().unwrap() > 0);
+/// }
+/// // the buffers were returned to the pool
+/// assert!(!POOL.is_empty());
+/// ```
+pub struct StoragePool {
+    storages: Mutex<Vec<Storage>>,
+    max_pooled: usize,
+    max_capacity: usize,
+}
+
+impl StoragePool {
+    /// Creates an empty pool that keeps at most `max_pooled` buffers, each with a capacity of at most
+    /// `max_capacity` bytes.
+    pub const fn new(max_pooled: usize, max_capacity: usize) -> Self {
+        Self {
+            storages: Mutex::new(Vec::new()),
+            max_pooled,
+            max_capacity,
+        }
+    }
+
+    /// Returns a buffer from the pool (or a new one if the pool is empty). The buffer has a length of 0
+    /// and is returned to the pool when the [`PooledStorage`] is dropped.
+    pub fn get(&self) -> PooledStorage<'_> {
+        let storage = self.lock().pop().unwrap_or_default();
+        PooledStorage {
+            pool: self,
+            storage: Some(storage),
+        }
+    }
+
+    /// Adds a buffer to the pool. The buffer is dropped if the pool is full or if its capacity is
+    /// larger than the maximum capacity of the pool.
+    pub fn put(&self, mut storage: Storage) {
+        if storage.capacity() > self.max_capacity {
+            return;
+        }
+        let mut storages = self.lock();
+        if storages.len() < self.max_pooled {
+            // the content is kept (it will be overwritten by the next serialization)
+            storage.resize(0);
+            storages.push(storage);
+        }
+    }
+
+    /// Number of buffers that are currently in the pool.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if there are no buffers in the pool.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops all the buffers from the pool.
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    /// The maximum number of buffers kept by the pool.
+    #[inline(always)]
+    pub fn max_pooled(&self) -> usize {
+        self.max_pooled
+    }
+
+    /// The maximum capacity (in bytes) of a buffer kept by the pool.
+    #[inline(always)]
+    pub fn max_capacity(&self) -> usize {
+        self.max_capacity
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Storage>> {
+        // a panic while holding the lock can not leave the list of buffers in an invalid state
+        self.storages.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// A [`Storage`] borrowed from a [`StoragePool`] (returned to the pool when dropped).
+pub struct PooledStorage<'a> {
+    pool: &'a StoragePool,
+    storage: Option<Storage>,
+}
+
+impl PooledStorage<'_> {
+    /// Takes the buffer out of the pool (it will not be returned to the pool when dropped).
+    pub fn into_inner(mut self) -> Storage {
+        self.storage.take().unwrap_or_default()
+    }
+}
+
+impl Deref for PooledStorage<'_> {
+    type Target = Storage;
+    #[inline(always)]
+    fn deref(&self) -> &Storage {
+        self.storage.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledStorage<'_> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Storage {
+        self.storage.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledStorage<'_> {
+    fn drop(&mut self) {
+        if let Some(storage) = self.storage.take() {
+            self.pool.put(storage);
+        }
+    }
+}
@@ -29,8 +29,15 @@ impl TryFrom<&Storage> for StructureInformation {
     type Error = Error;
 
     fn try_from(buf: &Storage) -> Result<Self, Self::Error> {
+        StructureInformation::try_from(buf.as_slice())
+    }
+}
+
+impl TryFrom<&[u8]> for StructureInformation {
+    type Error = Error;
+
+    fn try_from(buf: &[u8]) -> Result<Self, Self::Error> {
         // validate buf length - minimum 8 bytes
-        let buf = buf.as_slice();
         let len = buf.len();
         if len < size_of::<headers::HeaderV1>() {
             return Err(Error::InvalidHeaderLength(len));
@@ -55,8 +62,8 @@ impl TryFrom<&Storage> for StructureInformation {
         }
         if metadata_size + size_of::<headers::HeaderV1>() > len {
             return Err(Error::InvalidSizeToStoreMetaData((
-                len as u32,
-                (metadata_size + size_of::<headers::HeaderV1>()) as u32,
+                len as u64,
+                (metadata_size + size_of::<headers::HeaderV1>()) as u64,
             )));
         }
         // read the metadata
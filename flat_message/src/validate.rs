@@ -0,0 +1,524 @@
+use crate::{buffer, headers, size, Config, Error};
+use common::constants;
+use common::data_format::DataFormat;
+use std::mem::size_of;
+
+/// Maximum number of nested structures / variants that `validate` will descend into.
+const MAX_DEPTH: u32 = Config::DEFAULT_MAX_DEPTH;
+/// Bits from the serialization flags that are not used by the current format version.
+const RESERVED_FLAGS: u8 = 0b1100_0000;
+/// The low byte of a field hash has this bit set for slices and vectors.
+const SLICE_BIT: u8 = 0x80;
+/// The low byte of a variant hash has this bit set when the value is `None`.
+const VARIANT_NONE_BIT: u8 = 0x40;
+/// The low byte of a field hash has this bit set (together with `SLICE_BIT`) for lists with missing values.
+const NULLABLE_BIT: u8 = 0x40;
+/// The low byte of a field hash has this bit set (without `SLICE_BIT`) for lists of lists.
+const NESTED_LIST_BIT: u8 = 0x40;
+/// The low byte of a variant hash for unit variants.
+const VARIANT_UNIT: u8 = 0xFF;
+
+/// Checks that a buffer is a structurally valid FlatMessage without knowing the type that was serialized in it.
+///
+/// The following checks are performed:
+/// - the header (length, magic) and the serialization flags (offset size, reserved bits)
+/// - the metadata, the hash table and the offset table fit in the buffer
+/// - the checksum (if the buffer contains one)
+/// - the hash table is sorted and each hash is unique
+/// - every offset points inside the data region and is properly aligned for its data format
+/// - the size prefixes of every known data format (strings, slices, vectors, nested structures, variants)
+///   stay inside the data region
+///
+/// A buffer that passes this validation can still fail to deserialize into a specific type (for example if a
+/// field is missing, if a string is not valid UTF-8 or if an enum value is unknown), but the structure of the
+/// buffer can be safely walked.
+///
+/// # Example
+/// ```
+/// use flat_message::*;
+///
+/// #[derive(FlatMessage)]
+/// struct Point {
+///     x: i32,
+///     y: i32,
+/// }
+///
+/// let mut storage = Storage::default();
+/// Point { x: 1, y: 2 }.serialize_to(&mut storage, Config::default()).unwrap();
+/// assert!(flat_message::validate(storage.as_slice()).is_ok());
+/// assert!(flat_message::validate(&storage.as_slice()[..10]).is_err());
+/// ```
+pub fn validate(buf: &[u8]) -> Result<(), Error> {
+    let len = buf.len();
+    if len < size_of::<headers::HeaderV1>() {
+        return Err(Error::InvalidHeaderLength(len));
+    }
+    let p = buf.as_ptr();
+    let header: headers::HeaderV1 = unsafe { buffer::read(p, 0) };
+    if header.magic != constants::MAGIC_V1 {
+        return Err(Error::InvalidMagic);
+    }
+    if header.flags & RESERVED_FLAGS != 0 {
+        return Err(Error::InvalidFlags(header.flags));
+    }
+    let offset_size = offset_size(header.flags);
+    let mut metadata_size = 0usize;
+    if header.flags & constants::FLAG_HAS_CHECKSUM != 0 {
+        metadata_size += 4;
+    }
+    if header.flags & constants::FLAG_HAS_NAME_HASH != 0 {
+        metadata_size += 4;
+    }
+    if header.flags & constants::FLAG_HAS_TIMESTAMP != 0 {
+        metadata_size += 8;
+    }
+    if header.flags & constants::FLAG_HAS_UNIQUEID != 0 {
+        metadata_size += 8;
+    }
+    if metadata_size + size_of::<headers::HeaderV1>() > len {
+        return Err(Error::InvalidSizeToStoreMetaData((
+            len as u64,
+            (metadata_size + size_of::<headers::HeaderV1>()) as u64,
+        )));
+    }
+    let fields_count = header.fields_count as usize;
+    let hash_table_size = fields_count * 4;
+    let ref_table_size = fields_count * offset_size;
+    let min_size = size_of::<headers::HeaderV1>() + metadata_size + hash_table_size + ref_table_size;
+    if min_size > len {
+        return Err(Error::InvalidSizeToStoreFieldsTable((len as u64, min_size as u64)));
+    }
+    if header.flags & constants::FLAG_HAS_CHECKSUM != 0 {
+        let checksum = common::hashes::crc32(&buf[..len - 4]);
+        let stored = unsafe { buffer::read::<u32>(p, len - 4) };
+        if checksum != stored {
+            return Err(Error::InvalidChecksum((checksum, stored)));
+        }
+    }
+    let hash_table_offset = len - metadata_size - ref_table_size - hash_table_size;
+    validate_fields(buf, 0, hash_table_offset, fields_count, offset_size, 0)
+}
+
+#[inline(always)]
+fn offset_size(flags: u8) -> usize {
+    match flags & constants::FLAGS_OFFSET_SIZE {
+        0 => 1,
+        1 => 2,
+        2 => 4,
+        _ => 8,
+    }
+}
+
+/// Validates the hash table, the offset table and every field of a structure that starts at `base`.
+/// The data region of the structure is `[base + 8, hash_table_offset)`.
+fn validate_fields(
+    buf: &[u8],
+    base: usize,
+    hash_table_offset: usize,
+    fields_count: usize,
+    offset_size: usize,
+    depth: u32,
+) -> Result<(), Error> {
+    if !hash_table_offset.is_multiple_of(4) {
+        return Err(Error::UnalignedHashTable(hash_table_offset as u64));
+    }
+    let p = buf.as_ptr();
+    let ref_table_offset = hash_table_offset + fields_count * 4;
+    let mut last_hash = 0u32;
+    for index in 0..fields_count {
+        let hash: u32 = unsafe { buffer::read(p, hash_table_offset + index * 4) };
+        if index > 0 {
+            if hash == last_hash {
+                return Err(Error::DuplicateFieldHash(hash));
+            }
+            if hash < last_hash {
+                return Err(Error::UnsortedHashTable(hash));
+            }
+        }
+        last_hash = hash;
+        let offset = unsafe {
+            match offset_size {
+                1 => buffer::read::<u8>(p, ref_table_offset + index) as usize,
+                2 => buffer::read::<u16>(p, ref_table_offset + index * 2) as usize,
+                4 => buffer::read::<u32>(p, ref_table_offset + index * 4) as usize,
+                _ => {
+                    let offset = buffer::read::<u64>(p, ref_table_offset + index * 8);
+                    usize::try_from(offset).map_err(|_| Error::InvalidFieldOffset((offset, (hash_table_offset - base) as u64)))?
+                }
+            }
+        };
+        if offset == 0 {
+            // Option<T> fields that are None
+            continue;
+        }
+        if offset < size_of::<headers::HeaderV1>() || offset >= hash_table_offset - base {
+            return Err(Error::InvalidFieldOffset((
+                offset as u64,
+                (hash_table_offset - base) as u64,
+            )));
+        }
+        validate_value(buf, base + offset, hash_table_offset, hash as u8, hash, depth)?;
+    }
+    Ok(())
+}
+
+/// Serialization alignment of a value (this mirrors the alignment used by the derive macros).
+#[inline(always)]
+fn alignment(data_format: DataFormat, is_slice: bool) -> usize {
+    if is_slice || data_format.is_object_container() {
+        data_format.alignament() as usize
+    } else {
+        1
+    }
+}
+
+/// Returns `pos + count * item_size` if the result fits in the data region.
+#[inline(always)]
+fn advance(pos: usize, count: usize, item_size: usize, end: usize, hash: u32) -> Result<usize, Error> {
+    count
+        .checked_mul(item_size)
+        .and_then(|sz| pos.checked_add(sz))
+        .filter(|new_pos| *new_pos <= end)
+        .ok_or(Error::InvalidFieldSize(hash))
+}
+
+/// Reads a size prefix and returns the value and the position right after it.
+#[inline(always)]
+fn read_size(buf: &[u8], pos: usize, end: usize, format: size::Format, hash: u32) -> Result<(usize, usize), Error> {
+    let (value, len) = size::read(buf.as_ptr(), pos, end, format).ok_or(Error::InvalidFieldSize(hash))?;
+    Ok((value, pos + len))
+}
+
+/// Size of the representation of an enum or a flags value.
+#[inline(always)]
+fn repr_size(data_format: DataFormat) -> usize {
+    match data_format {
+        DataFormat::EnumI8 | DataFormat::EnumU8 | DataFormat::Flags8 => 1,
+        DataFormat::EnumI16 | DataFormat::EnumU16 | DataFormat::Flags16 => 2,
+        DataFormat::EnumI32 | DataFormat::EnumU32 | DataFormat::Flags32 => 4,
+        DataFormat::EnumI64 | DataFormat::EnumU64 | DataFormat::Flags64 => 8,
+        _ => 16,
+    }
+}
+
+/// Variable length integers (LEB128) use at most 19 bytes (for a 128 bit value) and must use the shortest encoding.
+/// Returns the position right after the value.
+fn skip_varint(buf: &[u8], pos: usize, end: usize, hash: u32) -> Result<usize, Error> {
+    for index in 0..19 {
+        let byte = *buf[..end].get(pos + index).ok_or(Error::InvalidFieldSize(hash))?;
+        if byte & 0x80 == 0 {
+            if byte == 0 && index > 0 {
+                return Err(Error::InvalidFieldSize(hash));
+            }
+            return Ok(pos + index + 1);
+        }
+    }
+    Err(Error::InvalidFieldSize(hash))
+}
+
+/// Validates a value of type `code` that starts at `pos` and must end before `end`.
+/// Returns the position right after the value.
+fn validate_value(buf: &[u8], pos: usize, end: usize, code: u8, hash: u32, depth: u32) -> Result<usize, Error> {
+    let is_slice = code & SLICE_BIT != 0;
+    let is_nullable = is_slice && code & NULLABLE_BIT != 0;
+    let is_nested_list = !is_slice && code & NESTED_LIST_BIT != 0;
+    let format_code = code & !(SLICE_BIT | NULLABLE_BIT | NESTED_LIST_BIT);
+    let data_format = DataFormat::try_from(format_code).map_err(|_| Error::UnknownDataFormat(hash))?;
+    if !pos.is_multiple_of(alignment(data_format, is_slice || is_nested_list)) {
+        return Err(Error::UnalignedField(hash));
+    }
+    if is_nullable {
+        validate_nullable(buf, pos, end, data_format, hash, depth)
+    } else if is_nested_list {
+        validate_nested_list(buf, pos, end, data_format, hash)
+    } else if is_slice {
+        validate_slice(buf, pos, end, data_format, hash)
+    } else {
+        validate_object(buf, pos, end, data_format, hash, depth)
+    }
+}
+
+fn validate_object(
+    buf: &[u8],
+    pos: usize,
+    end: usize,
+    data_format: DataFormat,
+    hash: u32,
+    depth: u32,
+) -> Result<usize, Error> {
+    match data_format {
+        DataFormat::U8 | DataFormat::I8 | DataFormat::Bool => advance(pos, 1, 1, end, hash),
+        DataFormat::U16 | DataFormat::I16 => advance(pos, 1, 2, end, hash),
+        DataFormat::U32 | DataFormat::I32 | DataFormat::F32 | DataFormat::Char => advance(pos, 1, 4, end, hash),
+        DataFormat::U64 | DataFormat::I64 | DataFormat::F64 => advance(pos, 1, 8, end, hash),
+        DataFormat::U128 | DataFormat::I128 => advance(pos, 1, 16, end, hash),
+        DataFormat::Duration | DataFormat::UtcDateTime | DataFormat::NaiveDateTime => advance(pos, 1, 12, end, hash),
+        DataFormat::OffsetDateTime => advance(pos, 1, 16, end, hash),
+        DataFormat::Date => advance(pos, 1, 4, end, hash),
+        DataFormat::String | DataFormat::FixArray | DataFormat::Path => {
+            let (len, pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            advance(pos, len, 1, end, hash)
+        }
+        DataFormat::VarUInt | DataFormat::VarInt | DataFormat::StringRef => skip_varint(buf, pos, end, hash),
+        DataFormat::Bits => {
+            let (count, pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            let bits_end = advance(pos, count.div_ceil(8), 1, end, hash)?;
+            // the bits after the last element must be 0
+            if !count.is_multiple_of(8) && buf[bits_end - 1] >> (count % 8) != 0 {
+                return Err(Error::InvalidFieldSize(hash));
+            }
+            Ok(bits_end)
+        }
+        DataFormat::IPv4 => advance(pos, 1, 4, end, hash),
+        DataFormat::IPv6 | DataFormat::Uuid => advance(pos, 1, 16, end, hash),
+        DataFormat::IP => {
+            advance(pos, 1, 1, end, hash)?;
+            match buf[pos] {
+                0 => advance(pos, 1, 5, end, hash),
+                1 => advance(pos, 1, 17, end, hash),
+                _ => Err(Error::InvalidFieldSize(hash)),
+            }
+        }
+        // address + prefix length
+        DataFormat::Ipv4Net => advance(pos, 1, 5, end, hash),
+        DataFormat::Ipv6Net => advance(pos, 1, 17, end, hash),
+        DataFormat::IpNet => {
+            advance(pos, 1, 1, end, hash)?;
+            match buf[pos] {
+                0 => advance(pos, 1, 6, end, hash),
+                1 => advance(pos, 1, 18, end, hash),
+                _ => Err(Error::InvalidFieldSize(hash)),
+            }
+        }
+        DataFormat::EnumI8
+        | DataFormat::EnumI16
+        | DataFormat::EnumI32
+        | DataFormat::EnumI64
+        | DataFormat::EnumU8
+        | DataFormat::EnumU16
+        | DataFormat::EnumU32
+        | DataFormat::EnumU64
+        | DataFormat::Flags8
+        | DataFormat::Flags16
+        | DataFormat::Flags32
+        | DataFormat::Flags64
+        | DataFormat::Flags128 => advance(pos, 1, 4 + repr_size(data_format), end, hash),
+        DataFormat::Struct4 | DataFormat::Struct8 | DataFormat::Struct16 => {
+            validate_struct(buf, pos, end, hash, depth)
+        }
+        DataFormat::Variant8
+        | DataFormat::Variant16
+        | DataFormat::Variant32
+        | DataFormat::Variant64
+        | DataFormat::Variant128 => validate_variant(buf, pos, end, hash, depth),
+        DataFormat::PackedStruct8 | DataFormat::PackedStruct16 | DataFormat::PackedStruct32 => {
+            // packed structures are not self describing - only the structure hash can be checked
+            advance(pos, 1, 4, end, hash)
+        }
+        DataFormat::PackedStruct64 => advance(pos, 1, 8, end, hash),
+        DataFormat::PackedStruct128 => advance(pos, 1, 16, end, hash),
+        // delta encoded values are only stored as lists
+        DataFormat::Delta | DataFormat::Unknwon => Err(Error::UnknownDataFormat(hash)),
+    }
+}
+
+/// Layout of a slice whose elements have a fixed size: the number of bytes stored before the
+/// size (the name hash of an enum or flags type), the format of the size and the size of an element.
+pub(crate) fn fixed_slice_layout(data_format: DataFormat) -> Option<(usize, size::Format, usize)> {
+    let layout = match data_format {
+        DataFormat::U8 | DataFormat::I8 | DataFormat::Bool => (0, size::Format::U8withExtension, 1),
+        DataFormat::U16 | DataFormat::I16 => (0, size::Format::U16withExtension, 2),
+        DataFormat::U32 | DataFormat::I32 | DataFormat::F32 | DataFormat::Char | DataFormat::Date => {
+            (0, size::Format::U32, 4)
+        }
+        DataFormat::Duration | DataFormat::UtcDateTime | DataFormat::NaiveDateTime => (0, size::Format::U32, 12),
+        DataFormat::OffsetDateTime => (0, size::Format::U32, 16),
+        DataFormat::U64 | DataFormat::I64 | DataFormat::F64 => (0, size::Format::U32on64bits, 8),
+        DataFormat::U128 | DataFormat::I128 => (0, size::Format::U32on128bits, 16),
+        DataFormat::Uuid | DataFormat::IPv6 => (0, size::Format::U8withExtension, 16),
+        DataFormat::IPv4 => (0, size::Format::U8withExtension, 4),
+        DataFormat::EnumI8
+        | DataFormat::EnumI16
+        | DataFormat::EnumI32
+        | DataFormat::EnumI64
+        | DataFormat::EnumU8
+        | DataFormat::EnumU16
+        | DataFormat::EnumU32
+        | DataFormat::EnumU64
+        | DataFormat::Flags8
+        | DataFormat::Flags16
+        | DataFormat::Flags32
+        | DataFormat::Flags64
+        | DataFormat::Flags128 => {
+            let item_size = repr_size(data_format);
+            let format = match item_size {
+                1 => size::Format::U8withExtension,
+                2 => size::Format::U16withExtension,
+                4 | 8 => size::Format::U32,
+                _ => size::Format::U32on96bits,
+            };
+            (4, format, item_size)
+        }
+        _ => return None,
+    };
+    Some(layout)
+}
+
+pub(crate) fn validate_slice(buf: &[u8], pos: usize, end: usize, data_format: DataFormat, hash: u32) -> Result<usize, Error> {
+    if let Some((hash_size, format, item_size)) = fixed_slice_layout(data_format) {
+        // (name hash) + count + values
+        let pos = advance(pos, 1, hash_size, end, hash)?;
+        let (count, pos) = read_size(buf, pos, end, format, hash)?;
+        return advance(pos, count, item_size, end, hash);
+    }
+    match data_format {
+        DataFormat::String | DataFormat::Path => {
+            let (count, mut pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            for _ in 0..count {
+                let (len, next) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+                pos = advance(next, len, 1, end, hash)?;
+            }
+            Ok(pos)
+        }
+        // delta encoded lists store each difference as a variable length integer and lists of
+        // string references store each index as a variable length integer
+        DataFormat::VarUInt | DataFormat::VarInt | DataFormat::Delta | DataFormat::StringRef => {
+            let (count, mut pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            for _ in 0..count {
+                pos = skip_varint(buf, pos, end, hash)?;
+            }
+            Ok(pos)
+        }
+        DataFormat::IP | DataFormat::Ipv4Net | DataFormat::Ipv6Net | DataFormat::IpNet => {
+            // variable size elements - each one is stored as an object
+            let (count, mut pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            for _ in 0..count {
+                pos = validate_object(buf, pos, end, data_format, hash, 0)?;
+            }
+            Ok(pos)
+        }
+        DataFormat::FixArray => {
+            let (item_size, pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            let (count, pos) = read_size(buf, pos, end, size::Format::U8withExtension, hash)?;
+            advance(pos, count, item_size, end, hash)
+        }
+        _ => Err(Error::UnknownDataFormat(hash)),
+    }
+}
+
+/// Lists with missing values: count (u32) + type hash (u32) + validity bitmap + (aligned) values that are present.
+fn validate_nullable(
+    buf: &[u8],
+    pos: usize,
+    end: usize,
+    data_format: DataFormat,
+    hash: u32,
+    depth: u32,
+) -> Result<usize, Error> {
+    let bitmap_pos = advance(pos, 1, 8, end, hash)?;
+    let count = unsafe { buffer::read::<u32>(buf.as_ptr(), pos) } as usize;
+    let bitmap_end = advance(bitmap_pos, count.div_ceil(8), 1, end, hash)?;
+    let bitmap = &buf[bitmap_pos..bitmap_end];
+    if !count.is_multiple_of(8) && bitmap[bitmap.len() - 1] >> (count % 8) != 0 {
+        return Err(Error::InvalidFieldSize(hash));
+    }
+    let present: usize = bitmap.iter().map(|b| b.count_ones() as usize).sum();
+    if present == 0 {
+        return Ok(bitmap_end);
+    }
+    let align = data_format.alignament() as usize;
+    let mut pos = (bitmap_end + align - 1) & !(align - 1);
+    for _ in 0..present {
+        pos = match data_format {
+            DataFormat::U8
+            | DataFormat::U16
+            | DataFormat::U32
+            | DataFormat::U64
+            | DataFormat::U128
+            | DataFormat::I8
+            | DataFormat::I16
+            | DataFormat::I32
+            | DataFormat::I64
+            | DataFormat::I128
+            | DataFormat::F32
+            | DataFormat::F64
+            | DataFormat::String
+            | DataFormat::IPv4
+            | DataFormat::IPv6
+            | DataFormat::IP => validate_object(buf, pos, end, data_format, hash, depth)?,
+            // enums are stored without the name hash (the hash is stored once, in the header)
+            DataFormat::EnumI8
+            | DataFormat::EnumI16
+            | DataFormat::EnumI32
+            | DataFormat::EnumI64
+            | DataFormat::EnumU8
+            | DataFormat::EnumU16
+            | DataFormat::EnumU32
+            | DataFormat::EnumU64 => advance(pos, 1, repr_size(data_format), end, hash)?,
+            _ => return Err(Error::UnknownDataFormat(hash)),
+        };
+    }
+    Ok(pos)
+}
+
+/// Lists of lists: count (u32) + padding up to the alignment of the elements + each list (stored as a slice).
+fn validate_nested_list(buf: &[u8], pos: usize, end: usize, data_format: DataFormat, hash: u32) -> Result<usize, Error> {
+    let count_end = advance(pos, 1, 4, end, hash)?;
+    let count = unsafe { buffer::read::<u32>(buf.as_ptr(), pos) } as usize;
+    let align = data_format.alignament() as usize;
+    let mut pos = (count_end + align - 1) & !(align - 1);
+    for _ in 0..count {
+        // every list has at least a size prefix (this stops huge counts early)
+        if pos >= end {
+            return Err(Error::InvalidFieldSize(hash));
+        }
+        if !pos.is_multiple_of(align) {
+            return Err(Error::UnalignedField(hash));
+        }
+        pos = validate_slice(buf, pos, end, data_format, hash)?;
+    }
+    Ok(pos)
+}
+
+fn validate_struct(buf: &[u8], pos: usize, end: usize, hash: u32, depth: u32) -> Result<usize, Error> {
+    if depth >= MAX_DEPTH {
+        return Err(Error::MaxDepthExceeded(MAX_DEPTH));
+    }
+    // structure name hash + (size | fields count | offset size)
+    advance(pos, 1, 8, end, hash)?;
+    let size_and_flags: u32 = unsafe { buffer::read(buf.as_ptr(), pos + 4) };
+    let struct_len = (size_and_flags >> 8) as usize;
+    let fields_count = ((size_and_flags & 0xFF) >> 2) as usize;
+    let offset_size = offset_size(size_and_flags as u8);
+    let struct_end = advance(pos, 1, struct_len, end, hash)?;
+    let min_size = 8 + fields_count * (4 + offset_size);
+    if min_size > struct_len {
+        return Err(Error::InvalidSizeToStoreFieldsTable((struct_len as u64, min_size as u64)));
+    }
+    let hash_table_offset = struct_end - fields_count * (4 + offset_size);
+    validate_fields(buf, pos, hash_table_offset, fields_count, offset_size, depth + 1)?;
+    Ok(struct_end)
+}
+
+fn validate_variant(buf: &[u8], pos: usize, end: usize, hash: u32, depth: u32) -> Result<usize, Error> {
+    if depth >= MAX_DEPTH {
+        return Err(Error::MaxDepthExceeded(MAX_DEPTH));
+    }
+    // variant name hash + hash of the current variant
+    let header_end = advance(pos, 1, 8, end, hash)?;
+    let variant_hash: u32 = unsafe { buffer::read(buf.as_ptr(), pos + 4) };
+    let code = variant_hash as u8;
+    if code == VARIANT_UNIT {
+        return Ok(header_end);
+    }
+    let payload_code = code & !VARIANT_NONE_BIT;
+    let is_slice = payload_code & SLICE_BIT != 0;
+    let payload_format =
+        DataFormat::try_from(payload_code & !SLICE_BIT).map_err(|_| Error::UnknownDataFormat(hash))?;
+    let payload_offset = if alignment(payload_format, is_slice) == 16 { 16 } else { 8 };
+    let payload_pos = advance(pos, 1, payload_offset, end, hash)?;
+    if code & VARIANT_NONE_BIT != 0 {
+        return Ok(payload_pos);
+    }
+    validate_value(buf, payload_pos, end, payload_code, hash, depth + 1)
+}
@@ -0,0 +1,279 @@
+use crate::data_type::{Encoding, FieldType};
+use crate::field_info::FieldInfo;
+use crate::struct_info::StructInfo;
+use common::data_format::DataFormat;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+
+// A batch (`#[flat_message_options(batch: true)]`) stores every field of a structure as a list.
+// Two structures are generated with the same name (`<Name>Columns`) and the same fields:
+// - a public one used to read a batch (zero-copy columns: `&[T]`, `NullableSlice<T>`, ...)
+// - a private one (inside `serialize_batch`) used to write the columns directly from the rows (`IterField<T>`)
+// Both structures derive `FlatMessage`, so a batch is a regular message (the number of rows is stored
+// in the hidden `__rows` field).
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+enum ColumnKind {
+    Values,
+    Strings,
+    NullableValues,
+    NullableStrings,
+    UniqueID,
+    Timestamp,
+}
+
+struct Column<'a> {
+    field: &'a FieldInfo,
+    kind: ColumnKind,
+}
+
+fn is_fixed_size(data_format: DataFormat) -> bool {
+    matches!(
+        data_format,
+        DataFormat::U8
+            | DataFormat::U16
+            | DataFormat::U32
+            | DataFormat::U64
+            | DataFormat::U128
+            | DataFormat::I8
+            | DataFormat::I16
+            | DataFormat::I32
+            | DataFormat::I64
+            | DataFormat::I128
+            | DataFormat::F32
+            | DataFormat::F64
+            | DataFormat::Bool
+    )
+}
+
+impl<'a> Column<'a> {
+    fn new(field: &'a FieldInfo, struct_name: &syn::Ident) -> Result<Self, String> {
+        let dt = &field.data_type;
+        if dt.unique_id {
+            return Ok(Column { field, kind: ColumnKind::UniqueID });
+        }
+        if dt.timestamp {
+            return Ok(Column { field, kind: ColumnKind::Timestamp });
+        }
+        if field.string_table || dt.encoding != Encoding::Fixed {
+            return Err(format!("Field '{}' from structure {} can not be stored in a batch (string tables, field adapters, varint and delta encodings are not supported in a batch) !", field.name, struct_name));
+        }
+        if dt.field_type != FieldType::Object {
+            return Err(format!("Field '{}' from structure {} can not be stored in a batch (only numbers, booleans, strings and Option of numbers or strings are supported) !", field.name, struct_name));
+        }
+        let kind = match (dt.data_format, dt.option) {
+            (DataFormat::String, false) => ColumnKind::Strings,
+            (DataFormat::String, true) => ColumnKind::NullableStrings,
+            (DataFormat::Bool, true) => {
+                return Err(format!("Field '{}' from structure {} can not be stored in a batch (Option<bool> is not supported) !", field.name, struct_name));
+            }
+            (df, false) if is_fixed_size(df) => ColumnKind::Values,
+            (df, true) if is_fixed_size(df) => ColumnKind::NullableValues,
+            _ => {
+                return Err(format!("Field '{}' from structure {} can not be stored in a batch (only numbers, booleans, strings and Option of numbers or strings are supported) !", field.name, struct_name));
+            }
+        };
+        Ok(Column { field, kind })
+    }
+
+    fn element_type(&self) -> TokenStream {
+        syn::parse_str::<syn::Type>(&self.field.data_type.name)
+            .map(|ty| quote! { #ty })
+            .unwrap_or_else(|_| quote! { u64 })
+    }
+
+    /// Converts a value read from a column into the type of the field (for Box<T>, Cow<str>, String, ...).
+    fn convert(&self, value: TokenStream) -> TokenStream {
+        match self.kind {
+            ColumnKind::Values if self.field.data_type.pointer => quote! { ::core::convert::From::from(#value) },
+            ColumnKind::Values => value,
+            ColumnKind::Strings => quote! { ::core::convert::From::from(#value) },
+            ColumnKind::NullableValues if self.field.data_type.pointer => quote! { (#value).copied().map(::core::convert::From::from) },
+            ColumnKind::NullableValues => quote! { (#value).copied() },
+            ColumnKind::NullableStrings => quote! { (#value).map(::core::convert::From::from) },
+            ColumnKind::UniqueID => quote! { flat_message::UniqueID::with_value(#value) },
+            ColumnKind::Timestamp => quote! { flat_message::Timestamp::with_value(#value) },
+        }
+    }
+
+    fn column_type(&self, lifetime: &syn::Lifetime) -> TokenStream {
+        let element = self.element_type();
+        match self.kind {
+            ColumnKind::Values => quote! { &#lifetime [#element] },
+            ColumnKind::Strings => quote! { Vec<&#lifetime str> },
+            ColumnKind::NullableValues => quote! { flat_message::NullableSlice<#lifetime, #element> },
+            ColumnKind::NullableStrings => quote! { Vec<Option<&#lifetime str>> },
+            ColumnKind::UniqueID | ColumnKind::Timestamp => quote! { &#lifetime [u64] },
+        }
+    }
+
+    fn writer_type(&self) -> TokenStream {
+        let element = self.element_type();
+        match self.kind {
+            ColumnKind::Values => quote! { flat_message::IterField<'r, #element> },
+            ColumnKind::Strings => quote! { flat_message::IterField<'r, &'r str> },
+            ColumnKind::NullableValues => quote! { Vec<Option<#element>> },
+            ColumnKind::NullableStrings => quote! { Vec<Option<&'r str>> },
+            ColumnKind::UniqueID | ColumnKind::Timestamp => quote! { flat_message::IterField<'r, u64> },
+        }
+    }
+
+    fn writer_value(&self) -> TokenStream {
+        let name = self.field.name_ident();
+        let pointer = self.field.data_type.pointer;
+        match self.kind {
+            ColumnKind::Values if pointer => quote! { flat_message::IterField::new(rows.iter().map(|row| *row.#name)) },
+            ColumnKind::Values => quote! { flat_message::IterField::new(rows.iter().map(|row| row.#name)) },
+            ColumnKind::Strings => quote! { flat_message::IterField::new(rows.iter().map(|row| &*row.#name)) },
+            ColumnKind::NullableValues if pointer => quote! { rows.iter().map(|row| row.#name.as_deref().copied()).collect() },
+            ColumnKind::NullableValues => quote! { rows.iter().map(|row| row.#name).collect() },
+            ColumnKind::NullableStrings => quote! { rows.iter().map(|row| row.#name.as_deref()).collect() },
+            ColumnKind::UniqueID | ColumnKind::Timestamp => quote! { flat_message::IterField::new(rows.iter().map(|row| row.#name.value())) },
+        }
+    }
+
+    /// The value of the field for the row at `index` (returns `None` from the function if a column is too short).
+    fn row_value(&self) -> TokenStream {
+        let name = self.field.name_ident();
+        let value = match self.kind {
+            ColumnKind::Values | ColumnKind::Strings | ColumnKind::UniqueID | ColumnKind::Timestamp => {
+                quote! { *columns.#name.get(index)? }
+            }
+            ColumnKind::NullableValues => quote! {
+                if index < columns.#name.len() { columns.#name.get(index) } else { return None; }
+            },
+            ColumnKind::NullableStrings => quote! { *columns.#name.get(index)? },
+        };
+        self.convert(value)
+    }
+
+    /// The value of the field for the next row (from the iterator over the column).
+    fn next_value(&self, iterator: &syn::Ident) -> TokenStream {
+        let value = match self.kind {
+            ColumnKind::NullableValues => quote! { #iterator.next()? },
+            _ => quote! { *#iterator.next()? },
+        };
+        self.convert(value)
+    }
+}
+
+impl StructInfo<'_> {
+    pub(crate) fn generate_batch_code(&self) -> Result<TokenStream, String> {
+        let name = self.name;
+        if self.generics.type_params().count() > 0 || self.generics.const_params().count() > 0 || self.generics.lifetimes().count() > 1 {
+            return Err(format!("Structure {} can not be stored in a batch (only structures without generic parameters or with one lifetime are supported) !", name));
+        }
+        let mut columns = Vec::new();
+        for field in self.fields.iter().chain(self.unique_id.iter()).chain(self.timestamp.iter()) {
+            columns.push(Column::new(field, name)?);
+        }
+        if columns.is_empty() {
+            return Err(format!("Structure {} can not be stored in a batch (it has no fields) !", name));
+        }
+        let (lifetime, row_type) = match self.generics.lifetimes().next() {
+            Some(l) => {
+                let lifetime = l.lifetime.clone();
+                (lifetime.clone(), quote! { #name<#lifetime> })
+            }
+            None => (syn::Lifetime::new("'a", proc_macro2::Span::call_site()), quote! { #name }),
+        };
+        let columns_name = format_ident!("{}Columns", name);
+        let visibility = self.visibility;
+        let checksum = if self.config.checksum {
+            quote! { #[flat_message_options(checksum: true)] }
+        } else {
+            quote! {}
+        };
+        // lists of numbers with missing values are written from vectors (no borrowed data)
+        let writer_lifetime = if columns.iter().all(|c| c.kind == ColumnKind::NullableValues) {
+            quote! {}
+        } else {
+            quote! { <'r> }
+        };
+        let columns_doc = format!("The columns of a batch of `{}` values (see `flat_message::Batch`).", name);
+
+        let field_names: Vec<syn::Ident> = columns.iter().map(|c| c.field.name_ident()).collect();
+        let column_types = columns.iter().map(|c| c.column_type(&lifetime));
+        let writer_types = columns.iter().map(|c| c.writer_type());
+        let writer_values = columns.iter().map(|c| c.writer_value());
+        let row_values = columns.iter().map(|c| c.row_value());
+        let iterators: Vec<syn::Ident> = columns.iter().map(|c| format_ident!("__column_{}", c.field.name)).collect();
+        let next_values = columns.iter().zip(iterators.iter()).map(|(c, it)| c.next_value(it));
+        let ignored_fields = self.ignored_fields.iter().map(|field| {
+            let field_name = field.name_ident();
+            let default_value = field.data_type.default_value(true);
+            quote! { #field_name: #default_value, }
+        });
+        let ignored_fields: Vec<TokenStream> = ignored_fields.collect();
+
+        Ok(quote! {
+            #[doc = #columns_doc]
+            #[derive(Debug, flat_message::FlatMessage)]
+            #[flat_message_options(validate_name: true)]
+            #checksum
+            #visibility struct #columns_name<#lifetime> {
+                __rows: u64,
+                #(pub #field_names: #column_types,)*
+            }
+
+            impl<#lifetime> flat_message::BatchRow<#lifetime> for #row_type {
+                type Columns = #columns_name<#lifetime>;
+
+                fn serialize_batch(rows: &[Self], output: &mut flat_message::Storage, config: flat_message::Config) -> core::result::Result<(), flat_message::Error> {
+                    // same name (and fields) as the public structure - the columns are written directly from the rows
+                    #[derive(flat_message::FlatMessage)]
+                    #checksum
+                    struct #columns_name #writer_lifetime {
+                        __rows: u64,
+                        #(#field_names: #writer_types,)*
+                    }
+                    let columns = #columns_name {
+                        __rows: rows.len() as u64,
+                        #(#field_names: #writer_values,)*
+                    };
+                    flat_message::FlatMessage::serialize_to(&columns, output, config)
+                }
+
+                #[inline(always)]
+                fn len(columns: &Self::Columns) -> usize {
+                    columns.__rows as usize
+                }
+
+                fn check(columns: &Self::Columns) -> core::result::Result<(), flat_message::Error> {
+                    let rows = columns.__rows;
+                    #(
+                        if columns.#field_names.len() as u64 != rows {
+                            return Err(flat_message::Error::InvalidColumnLength((columns.#field_names.len() as u64, rows)));
+                        }
+                    )*
+                    Ok(())
+                }
+
+                fn row(columns: &Self::Columns, index: usize) -> Option<Self> {
+                    if index >= columns.__rows as usize {
+                        return None;
+                    }
+                    Some(Self {
+                        #(#field_names: #row_values,)*
+                        #(#ignored_fields)*
+                    })
+                }
+
+                fn rows(columns: &Self::Columns) -> impl Iterator<Item = Self> + '_ {
+                    let mut remaining = columns.__rows as usize;
+                    #(let mut #iterators = columns.#field_names.iter();)*
+                    std::iter::from_fn(move || {
+                        if remaining == 0 {
+                            return None;
+                        }
+                        remaining -= 1;
+                        Some(Self {
+                            #(#field_names: #next_values,)*
+                            #(#ignored_fields)*
+                        })
+                    })
+                }
+            }
+        })
+    }
+}
@@ -14,6 +14,8 @@ pub(crate) struct Config {
     pub(crate) validate_checksum: ValidateChecksum,
     pub(crate) optimized_unchecked_code: bool,
     pub(crate) use_default_if_deserialize_fails: Option<bool>,
+    pub(crate) string_table: bool,
+    pub(crate) batch: bool,
 }
 
 impl Config {
@@ -26,6 +28,8 @@ impl Config {
         let mut validate_checksum = ValidateChecksum::Auto;
         let mut optimized_unchecked_code = true;
         let mut use_default_if_deserialize_fails = None;
+        let mut string_table = false;
+        let mut batch = false;
         //println!("--Parsing attributes: '{}'", args.to_string());
         let attrs = attribute_parser::parse(args);
         for (attr_name, attr_value) in attrs.iter() {
@@ -50,8 +54,10 @@ impl Config {
                         _ => panic!("Invalid value for attribute 'validate': {}. Allowed values are 'strict' or 'fallback' !", attr_value.as_str()),
                     }
                 }
+                "string_table" => string_table = utils::to_bool(attr_value.as_str()).unwrap_or_else(|| panic!("Invalid boolean value ('{}') for attribute '{}'. Allowed values are 'true' or 'false' !",attr_value.as_str(), attr_name)),
+                "batch" => batch = utils::to_bool(attr_value.as_str()).unwrap_or_else(|| panic!("Invalid boolean value ('{}') for attribute '{}'. Allowed values are 'true' or 'false' !",attr_value.as_str(), attr_name)),
                 _ => {
-                    panic!("Unknown attribute: {attr_name}. Supported attributes are: 'store_name', 'metadata', 'checksum', validate_name', 'optimized_unchecked_code', 'validate', 'compatible_versions' and 'version' !");
+                    panic!("Unknown attribute: {attr_name}. Supported attributes are: 'store_name', 'metadata', 'checksum', validate_name', 'optimized_unchecked_code', 'validate', 'compatible_versions', 'string_table', 'batch' and 'version' !");
                 }
             }
         }
@@ -69,6 +75,8 @@ impl Config {
             compatible_versions,
             optimized_unchecked_code,
             use_default_if_deserialize_fails,
+            string_table,
+            batch,
         }
     }
 }
@@ -84,6 +92,8 @@ impl Default for Config {
             validate_checksum: ValidateChecksum::Auto,
             optimized_unchecked_code: true,
             use_default_if_deserialize_fails: None,
+            string_table: false,
+            batch: false,
         }
     }
 }
@@ -23,7 +23,7 @@ impl ConstAssertions {
     }
     pub(crate) fn for_struct(structure_name: Ident, field_name: &str, datatype: &DataType) -> proc_macro2::TokenStream {
         let path_str = datatype.name.replace(' ', ""); 
-        let ty: syn::Path = syn::parse_str(&path_str).unwrap();
+        let ty: syn::Type = syn::parse_str(&path_str).unwrap();
         let const_assert_name = format_ident!("_CONST_ASSERT_STRUCT_{}_{}",structure_name,field_name);        
         let df = format_ident!("{}",datatype.data_format.to_string());
         let field_name = format!("{structure_name}::{field_name}");
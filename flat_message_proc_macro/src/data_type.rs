@@ -9,11 +9,16 @@ use quote::{quote, ToTokens};
 use syn::parse_str;
 use syn::Attribute;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum FieldType {
     Object,
     Slice,
     Vector,
+    WrappedSlice,
+    Nullable,
+    NestedList,
+    /// IterField<T> (written from an iterator, with the same format as a Vec<T>)
+    Iter,
 }
 
 impl FieldType {
@@ -22,10 +27,26 @@ impl FieldType {
             FieldType::Object => "SerDe",
             FieldType::Slice => "SerDeSlice",
             FieldType::Vector => "SerDeVec",
+            FieldType::WrappedSlice => "SerDe",
+            FieldType::Nullable => "SerDe",
+            FieldType::NestedList => "SerDe",
+            FieldType::Iter => "SerDe",
         }
     }
 }
 
+/// How the values of a field are stored (`#[flat_message_item(encoding = "...")]`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Encoding {
+    Fixed,
+    Varint,
+    Delta,
+    /// index in the string table of the message (`#[flat_message_options(string_table = true)]`)
+    StringTable,
+    /// written and read by the functions of a module (`#[flat_message_item(with = "path")]`)
+    Adapter,
+}
+
 pub(crate) struct DataType {
     pub(crate) field_type: FieldType,
     pub(crate) data_format: DataFormat,
@@ -34,25 +55,90 @@ pub(crate) struct DataType {
     pub(crate) unique_id: bool,
     pub(crate) timestamp: bool,
     pub(crate) ignore_field: bool,
+    pub(crate) pointer: bool,
     pub(crate) option: bool,
+    pub(crate) encoding: Encoding,
     pub(crate) mandatory: bool,
     pub(crate) use_default_if_deserialize_fails: bool,
     pub(crate) default_value: Option<String>,
+    /// the functions of the field adapter (a `flat_message::FieldAdapter` value)
+    pub(crate) adapter: Option<proc_macro2::TokenStream>,
+    /// the `FlatField` type whose `FORMAT` gives the data format of the field (no `format` attribute)
+    pub(crate) format_from_type: Option<syn::Type>,
 }
 
 impl DataType {
     pub(crate) fn type_hash(&self) -> u32 {
-        match self.field_type {
-            FieldType::Object => self.data_format as u32,
-            FieldType::Slice | FieldType::Vector => (self.data_format as u32) | 0x80,
+        DataType::type_hash_of(self.field_type, self.data_format)
+    }
+    /// The type hash as an expression of the generated code. For a `FlatField` without a `format`
+    /// attribute it depends on `FlatField::FORMAT` (the `type_hash()` of such a field is the one of
+    /// the `bytes` format).
+    pub(crate) fn type_hash_tokens(&self) -> proc_macro2::TokenStream {
+        let Some(ty) = &self.format_from_type else {
+            let type_hash = self.type_hash();
+            return quote! { #type_hash };
+        };
+        let bytes = self.type_hash();
+        let string = match self.field_type {
+            FieldType::NestedList => DataType::type_hash_of(FieldType::Vector, DataFormat::String),
+            _ => DataType::type_hash_of(FieldType::Object, DataFormat::String),
+        };
+        quote! {
+            match <#ty as ::flat_message::FlatField>::FORMAT {
+                ::flat_message::FieldFormat::Bytes => #bytes,
+                ::flat_message::FieldFormat::String => #string,
+            }
+        }
+    }
+    fn type_hash_of(field_type: FieldType, data_format: DataFormat) -> u32 {
+        match field_type {
+            FieldType::Object => data_format as u32,
+            FieldType::Slice | FieldType::Vector | FieldType::WrappedSlice | FieldType::Iter => {
+                (data_format as u32) | 0x80
+            }
+            // lists with missing values use a different format than regular vectors
+            FieldType::Nullable => (data_format as u32) | 0xC0,
+            // lists of lists (Vec<Vec<T>> and Vec<&[T]>)
+            FieldType::NestedList => (data_format as u32) | 0x40,
         }
     }
     #[inline(always)]
     pub(crate) fn serde_trait(&self) -> syn::Ident {
-        syn::Ident::new(
-            self.field_type.serde_trait(),
-            proc_macro2::Span::call_site(),
-        )
+        // variable length integers and delta encoded vectors have their own traits
+        let name = match self.encoding {
+            // Box<Vec<T>>, Rc<Vec<T>> and Arc<Vec<T>>
+            Encoding::Fixed if self.pointer && self.field_type == FieldType::Vector => "SerDeVecPointer",
+            Encoding::Fixed => self.field_type.serde_trait(),
+            Encoding::Varint => "SerDeVarint",
+            Encoding::Delta => "SerDeDelta",
+            Encoding::StringTable => "SerDeStringRef",
+            Encoding::Adapter => "SerDeWith",
+        };
+        syn::Ident::new(name, proc_macro2::Span::call_site())
+    }
+    /// Extra argument for the methods of the serialization trait (the string table for fields
+    /// that are stored as indexes in that table and the functions of a field adapter).
+    pub(crate) fn extra_arg(&self, table: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        match (self.encoding, &self.adapter) {
+            (Encoding::StringTable, _) => quote! { , #table },
+            (Encoding::Adapter, Some(adapter)) => quote! { , #adapter },
+            _ => quote! {},
+        }
+    }
+    /// Stores the field as an index in the string table of the message (only `String`, `&str` and
+    /// vectors of them can be stored in this way). Returns `true` if the field was changed.
+    pub(crate) fn use_string_table(&mut self) -> bool {
+        if self.data_format != DataFormat::String
+            || self.encoding != Encoding::Fixed
+            || self.pointer
+            || !matches!(self.field_type, FieldType::Object | FieldType::Vector)
+        {
+            return false;
+        }
+        self.data_format = DataFormat::StringRef;
+        self.encoding = Encoding::StringTable;
+        true
     }
     pub(crate) fn new(
         ty: syn::Type,
@@ -65,7 +151,45 @@ impl DataType {
             def = def["Option<".len()..def.len() - 1].to_string();
             option = true;
         }
-        let field_type = if def.starts_with("Vec<") && def.ends_with(">") {
+        // Cow<'a, T>, Box<T>, Rc<T> and Arc<T> use the same wire format as the type they wrap
+        // (with str and [T] being serialized as &str and &[T]). Vectors of values are supported
+        // (Box<Vec<T>>), but not lists with missing values or lists of lists.
+        let mut pointer = None;
+        if let Some((name, inner)) = utils::pointer_inner_type(&def) {
+            if inner == "str" || (inner.starts_with('[') && inner.ends_with(']')) {
+                def = format!("&{inner}");
+                pointer = Some(name);
+            } else if name != "Cow"
+                && utils::nullable_element_type(&inner).is_none()
+                && utils::nested_list_element_type(&inner).is_none()
+            {
+                def = inner;
+                pointer = Some(name);
+            }
+        }
+        let mut encoding = Encoding::Fixed;
+        let mut field_type = if let Some(element) = utils::nullable_element_type(&def) {
+            // Vec<Option<T>> and NullableSlice<T>
+            def = element;
+            FieldType::Nullable
+        } else if let Some(element) = utils::nested_list_element_type(&def) {
+            // Vec<Vec<T>> and Vec<&[T]>
+            def = element;
+            FieldType::NestedList
+        } else if let Some(element) = utils::delta_element_type(&def) {
+            // DeltaSlice<T> uses the same format as a delta encoded Vec<T>
+            def = element;
+            encoding = Encoding::Delta;
+            FieldType::Vector
+        } else if let Some(element) = utils::iter_element_type(&def) {
+            // IterField<T> uses the same format as a Vec<T>
+            def = element;
+            FieldType::Iter
+        } else if cfg!(feature = "bytes") && matches!(def.as_str(), "Bytes" | "bytes :: Bytes") {
+            // bytes::Bytes uses the same format as &[u8] (only with the `bytes` feature)
+            def = "u8".to_string();
+            FieldType::WrappedSlice
+        } else if def.starts_with("Vec<") && def.ends_with(">") {
             def = def["Vec<".len()..def.len() - 1].to_string();
             FieldType::Vector
         } else if def.starts_with("&[") && def.ends_with("]") {
@@ -73,34 +197,97 @@ impl DataType {
                 // this wil be treated as an object (&[u8; N])
                 def = def[1..def.len()].to_string();
                 FieldType::Object
+            } else if let Some(element) = utils::array_element_type(&def[1..def.len()]) {
+                // &[T; N] uses the same format as &[T]
+                def = element;
+                FieldType::WrappedSlice
             } else {
                 def = def[2..def.len() - 1].to_string();
                 FieldType::Slice
             }
+        } else if let Some(element) = utils::array_element_type(&def).filter(|_| DataFormat::from(def.as_str()) != DataFormat::FixArray) {
+            // [T; N] uses the same format as &[T]
+            def = element;
+            FieldType::WrappedSlice
         } else {
             FieldType::Object
         };
+        if pointer.is_some() && field_type == FieldType::Slice {
+            field_type = FieldType::WrappedSlice;
+        }
         //println!(" -------- DataType: {def} 3");
-        let unique_id = matches!(def.as_str(), "UniqueID" | "flat_message :: UniqueID");
-        let timestamp = matches!(def.as_str(), "Timestamp" | "flat_message :: Timestamp");
+        let unique_id = pointer.is_none() && matches!(def.as_str(), "UniqueID" | "flat_message :: UniqueID");
+        let timestamp = pointer.is_none() && matches!(def.as_str(), "Timestamp" | "flat_message :: Timestamp");
         let zst = def.starts_with("PhantomData")
             || def.starts_with("std :: marker :: PhantomData")
             || def.starts_with("marker :: PhantomData");
+        let data_format = match utils::tuple_elements(&def) {
+            Some(elements) => DataType::tuple_data_format(&ty, &elements),
+            None if encoding == Encoding::Delta => DataFormat::Delta,
+            None => DataFormat::from(def.as_str()),
+        };
         DataType {
             field_type,
-            data_format: DataFormat::from(def.as_str()),
-            name: def,
+            data_format,
+            name: match pointer {
+                Some(pointer) if def == "&str" => format!("{pointer}<str>"),
+                _ => def,
+            },
             ty,
             unique_id,
             timestamp,
             ignore_field: zst,
+            pointer: pointer.is_some(),
             option,
+            encoding,
             mandatory: !option,
             default_value: None,
+            adapter: None,
+            format_from_type: None,
             use_default_if_deserialize_fails: use_default_if_deserialize_fails.unwrap_or(option),
         }
     }
 
+    /// A `Box`, `Rc` or `Arc` can only wrap a vector of values - returns the (formatted) type of a
+    /// pointer to a list with missing values or to a list of lists (e.g. `Box<Vec<Option<T>>>`).
+    pub(crate) fn unsupported_pointer(def: &str) -> Option<String> {
+        let mut def = def.to_string();
+        utils::type_name_formatter(&mut def);
+        if def.starts_with("Option<") && def.ends_with(">") {
+            def = def["Option<".len()..def.len() - 1].to_string();
+        }
+        let (name, inner) = utils::pointer_inner_type(&def)?;
+        if name != "Cow" && (utils::nullable_element_type(&inner).is_some() || utils::nested_list_element_type(&inner).is_some()) {
+            Some(def)
+        } else {
+            None
+        }
+    }
+
+    /// Tuples are serialized as nested structures. The alignment of the structure is the
+    /// largest alignment of its elements (but at least 4 bytes).
+    fn tuple_data_format(ty: &syn::Type, elements: &[String]) -> DataFormat {
+        if elements.len() > 8 {
+            return DataFormat::Unknwon;
+        }
+        let mut align = 4;
+        for element in elements {
+            let dt = DataType::new(ty.clone(), element.clone(), None);
+            if dt.option
+                || dt.data_format == DataFormat::Unknwon
+                || matches!(dt.field_type, FieldType::Slice | FieldType::Vector | FieldType::Nullable | FieldType::NestedList | FieldType::Iter)
+            {
+                return DataFormat::Unknwon;
+            }
+            align = align.max(dt.data_format.alignament());
+        }
+        match align {
+            8 => DataFormat::Struct8,
+            16 => DataFormat::Struct16,
+            _ => DataFormat::Struct4,
+        }
+    }
+
     pub(crate) fn parse_attr(&mut self, attr: &Attribute, field_name: &str) -> Result<(), String> {
         if attr.path().is_ident("flat_message_item") {
             let all_tokens = attr.meta.clone().into_token_stream();
@@ -158,7 +345,14 @@ impl DataType {
                 value.push_str("\"#)");
                 self.default_value = Some(value);
             }
-            // 3. for an Option if its not None or does not starts with Some(...) we need to enclose it in a Some(...)
+            // 3. for Cow<str>, Box<str>, Rc<str> or Arc<str> -> we need to convert it from a &str
+            if self.name.ends_with("<str>") {
+                let mut value = self.default_value.take().unwrap();
+                value.insert_str(0, "::std::convert::From::from(r#\"");
+                value.push_str("\"#)");
+                self.default_value = Some(value);
+            }
+            // 4. for an Option if its not None or does not starts with Some(...) we need to enclose it in a Some(...)
             if self.option {
                 let mut value = self.default_value.take().unwrap();
                 if (!value.starts_with("Some(")) && (value != "None") {
@@ -167,8 +361,8 @@ impl DataType {
                 }
                 self.default_value = Some(value);
             }
-            // 4. for slices we need to enclose it in a &[...]n
-            // 5. for vectors we need to enclose it in a Vec::new()
+            // 5. for slices we need to enclose it in a &[...]n
+            // 6. for vectors we need to enclose it in a Vec::new()
         }
 
         Ok(())
@@ -209,6 +403,32 @@ impl DataType {
             self.ignore_field = true;
             Ok(())
         } else {
+            if let Some(path) = attr.get("with") {
+                if has_kind || has_repr || has_align || attr.contains_key("encoding") {
+                    return Err(format!("The 'with' attribute can not be used together with 'kind', 'repr', 'align' or 'encoding' (for field: '{field_nane}')"));
+                }
+                let format = attr.get("format").map(|f| f.as_str()).unwrap_or("bytes");
+                return self.use_field_adapter(Some(path.as_str()), Some(format), field_nane);
+            }
+            if let Some(format) = attr.get("format") {
+                // the type of the field implements FlatField
+                if has_kind || has_repr || has_align || attr.contains_key("encoding") {
+                    return Err(format!("The 'format' attribute can not be used together with 'kind', 'repr', 'align' or 'encoding' (for field: '{field_nane}')"));
+                }
+                return self.use_field_adapter(None, Some(format.as_str()), field_nane);
+            }
+            if let Some(encoding) = attr.get("encoding") {
+                if has_kind || has_repr || has_align {
+                    return Err(format!("The 'encoding' attribute can not be used together with 'kind', 'repr' or 'align' (for field: '{field_nane}')"));
+                }
+                return match encoding.as_str() {
+                    _ if self.encoding == Encoding::Delta => Err(format!("A DeltaSlice is always delta encoded - the 'encoding' attribute can not be used (for field: '{field_nane}')")),
+                    "fixed" => Ok(()),
+                    "varint" => self.use_varint_encoding(field_nane),
+                    "delta" | "delta+varint" => self.use_delta_encoding(field_nane),
+                    other => Err(format!("Invalid encoding: '{other}' in field: '{field_nane}'. The possible encodings are: 'fixed', 'varint' or 'delta'.")),
+                };
+            }
             if has_kind {
                 let kind = attr.get("kind").unwrap().as_str();
                 if kind == "enum" {
@@ -305,6 +525,9 @@ impl DataType {
                 "mandatory",
                 "default",
                 "validate",
+                "encoding",
+                "with",
+                "format",
             ];
             for key in KEYS {
                 if attr.contains_key(*key) {
@@ -321,6 +544,114 @@ impl DataType {
         }
     }
 
+    fn use_varint_encoding(&mut self, field_name: &str) -> Result<(), String> {
+        let signed = match self.name.as_str() {
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize" => false,
+            "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => true,
+            _ => return Err(format!("The 'varint' encoding can only be used for integer types (u8 ... u128, i8 ... i128, usize, isize) - for field: '{field_name}'")),
+        };
+        if self.pointer || !matches!(self.field_type, FieldType::Object | FieldType::Vector) {
+            return Err(format!("The 'varint' encoding can only be used for integer values or vectors of integers (Vec<T>) - for field: '{field_name}'"));
+        }
+        self.data_format = if signed { DataFormat::VarInt } else { DataFormat::VarUInt };
+        self.encoding = Encoding::Varint;
+        Ok(())
+    }
+
+    /// Stores a field whose type is not known with the methods of the `FlatField` trait (in the
+    /// format given by `FlatField::FORMAT`).
+    pub(crate) fn use_flat_field(&mut self, field_name: &str) -> Result<(), String> {
+        self.use_field_adapter(None, None, field_name)
+    }
+
+    /// Stores the field with the functions of a field adapter (`with = "path"`) or, if `path` is `None`,
+    /// with the methods of the `FlatField` trait (in the given format, or in `FlatField::FORMAT` if
+    /// `format` is `None`). A `Vec<T>` field is stored as a list of values.
+    fn use_field_adapter(&mut self, path: Option<&str>, format: Option<&str>, field_name: &str) -> Result<(), String> {
+        let mut ty = self.ty.clone();
+        if self.option {
+            ty = utils::generic_argument(&ty, "Option").unwrap_or(ty);
+        }
+        let element = utils::generic_argument(&ty, "Vec");
+        let list = element.is_some();
+        let value_ty = element.unwrap_or(ty);
+        // values are stored in the same way as a buffer (&[u8]) or as a string (&str)
+        let (field_type, data_format, format_ident) = match (format.unwrap_or("bytes"), list) {
+            ("bytes", false) => (FieldType::WrappedSlice, DataFormat::U8, quote! { Bytes }),
+            ("bytes", true) => (FieldType::NestedList, DataFormat::U8, quote! { Bytes }),
+            ("string", false) => (FieldType::Object, DataFormat::String, quote! { String }),
+            ("string", true) => (FieldType::Vector, DataFormat::String, quote! { String }),
+            (other, _) => return Err(format!("Invalid format: '{other}' in field: '{field_name}'. The possible formats are: 'bytes' or 'string'.")),
+        };
+        let adapter = match path {
+            Some(path) => {
+                let Ok(path) = syn::parse_str::<syn::Path>(path) else {
+                    return Err(format!("Invalid module path: '{path}' for the 'with' attribute (for field: '{field_name}')"));
+                };
+                quote! {
+                    ::flat_message::FieldAdapter {
+                        format: ::flat_message::FieldFormat::#format_ident,
+                        size: #path::size,
+                        write: #path::write,
+                        read: #path::read,
+                    }
+                }
+            }
+            None if format.is_none() => {
+                self.format_from_type = Some(value_ty.clone());
+                quote! {
+                    ::flat_message::FieldAdapter {
+                        format: <#value_ty as ::flat_message::FlatField>::FORMAT,
+                        size: <#value_ty as ::flat_message::FlatField>::size,
+                        write: <#value_ty as ::flat_message::FlatField>::encode,
+                        read: <#value_ty as ::flat_message::FlatField>::decode,
+                    }
+                }
+            }
+            None => {
+                let error = format!("The format of field '{field_name}' in the #[flat_message_item(...)] attribute is not the same as the format of its type ! Make sure that #[flat_message_item(...)] contains the same format as `FlatField::FORMAT` !");
+                quote! {
+                    {
+                        const { assert!(matches!(<#value_ty as ::flat_message::FlatField>::FORMAT, ::flat_message::FieldFormat::#format_ident), #error) };
+                        ::flat_message::FieldAdapter {
+                            format: ::flat_message::FieldFormat::#format_ident,
+                            size: <#value_ty as ::flat_message::FlatField>::size,
+                            write: <#value_ty as ::flat_message::FlatField>::encode,
+                            read: <#value_ty as ::flat_message::FlatField>::decode,
+                        }
+                    }
+                }
+            }
+        };
+        self.field_type = field_type;
+        self.data_format = data_format;
+        self.encoding = Encoding::Adapter;
+        self.pointer = false;
+        self.unique_id = false;
+        self.timestamp = false;
+        self.adapter = Some(if list {
+            quote! { ::flat_message::FieldListAdapter(#adapter) }
+        } else {
+            adapter
+        });
+        Ok(())
+    }
+
+    fn use_delta_encoding(&mut self, field_name: &str) -> Result<(), String> {
+        if !matches!(
+            self.name.as_str(),
+            "u8" | "u16" | "u32" | "u64" | "usize" | "i8" | "i16" | "i32" | "i64" | "isize"
+        ) {
+            return Err(format!("The 'delta' encoding can only be used for vectors of integers of up to 64 bits (u8 ... u64, i8 ... i64, usize, isize) - for field: '{field_name}'"));
+        }
+        if self.pointer || self.field_type != FieldType::Vector {
+            return Err(format!("The 'delta' encoding can only be used for vectors of integers (Vec<T>) - for field: '{field_name}'"));
+        }
+        self.data_format = DataFormat::Delta;
+        self.encoding = Encoding::Delta;
+        Ok(())
+    }
+
     pub(crate) fn serialization_alignment(&self) -> usize {
         match self.field_type {
             FieldType::Object => {
@@ -330,7 +661,14 @@ impl DataType {
                     1
                 }
             }
-            FieldType::Slice | FieldType::Vector => self.data_format.alignament() as usize,
+            FieldType::Slice
+            | FieldType::Vector
+            | FieldType::WrappedSlice
+            | FieldType::Iter
+            | FieldType::Nullable
+            | FieldType::NestedList => {
+                self.data_format.alignament() as usize
+            }
         }
     }
 
@@ -347,7 +685,8 @@ impl DataType {
             quote! { ::std::default::Default::default() }
         } else {
             let ty = self.ty.clone();
-            quote! { #ty::default() }
+            quote! { <#ty as ::std::default::Default>::default() }
         }
     }
 }
+
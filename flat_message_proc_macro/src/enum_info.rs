@@ -173,7 +173,7 @@ impl EnumInfo {
                 }
                 #[inline(always)]
                 unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
-                    let len = obj.len() as u32;
+                    let len = obj.len();
                     unsafe {
                         std::ptr::write_unaligned(p.add(pos) as *mut u32, #name_hash);
                         let size_len =
@@ -183,12 +183,12 @@ impl EnumInfo {
                             p.add(pos + size_len + 4),
                             obj.len() #multiplier,
                         );
-                        pos + size_len + (len as usize) #multiplier  + 4usize
+                        pos + size_len + len #multiplier  + 4usize
                     }
                 }
                 #[inline(always)]
                 fn size(obj: &[Self]) -> usize {
-                    flat_message::size::len(obj.len() as u32, flat_message::size::Format::#size_format)
+                    flat_message::size::len(obj.len(), flat_message::size::Format::#size_format)
                     + obj.len() #multiplier + 4usize /* name hash */
                 }
             }
@@ -242,16 +242,63 @@ impl EnumInfo {
             }
         }
     }
+    fn generate_nullable_serde_implementation(&self) -> TokenStream {
+        let name = &self.name;
+        let data_format = self.repr.data_format();
+        let repr_type = self.repr.repr_type();
+        let name_hash = self.compute_hash();
+        let variant_validation = self.generate_variant_validation_match(true);
+
+        quote! {
+            unsafe impl<'a> SerDeNullable<'a> for #name {
+                const DATA_FORMAT: flat_message::DataFormat = #data_format;
+                const TYPE_HASH: u32 = #name_hash;
+                const ZERO_COPY: bool = true;
+                #[inline(always)]
+                unsafe fn from_buffer_unchecked(buf: &[u8], pos: usize) -> (Self, usize) {
+                    unsafe {
+                        let ptr = buf.as_ptr().add(pos) as *const Self;
+                        (std::ptr::read_unaligned(ptr), pos + std::mem::size_of::<#repr_type>())
+                    }
+                }
+                #[inline(always)]
+                fn from_buffer(buf: &[u8], pos: usize) -> Option<(Self, usize)> {
+                    let end = pos + std::mem::size_of::<#repr_type>();
+                    if end > buf.len() {
+                        None
+                    } else {
+                        let value = unsafe { ((buf.as_ptr().add(pos) as *const #repr_type)).read_unaligned() };
+                        let value: Option<Self> = #variant_validation;
+                        Some((value?, end))
+                    }
+                }
+                #[inline(always)]
+                unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
+                    unsafe {
+                        std::ptr::write_unaligned(p.add(pos) as *mut #repr_type, *obj as #repr_type);
+                        pos + std::mem::size_of::<#repr_type>()
+                    }
+                }
+                #[inline(always)]
+                fn size(_: &Self) -> usize {
+                    std::mem::size_of::<#repr_type>()
+                }
+            }
+        }
+    }
     pub fn generate_code(&self) -> TokenStream {
         let serde_code = self.generate_serde_implementation();
         let slice_code = self.generate_slice_serde_implementation();
         let vec_code = self.generate_vector_serde_implementation();
+        let nullable_code = self.generate_nullable_serde_implementation();
         quote! {
             #serde_code
             // for slices
             #slice_code
             // for vectors
             #vec_code
+            // for lists with missing values
+            #nullable_code
         }
     }
 }
@@ -9,6 +9,8 @@ pub(crate) struct FieldInfo {
     pub(crate) hash: u32,
     pub(crate) hash_table_order: u32,
     pub(crate) data_type: DataType,
+    /// the hidden field that stores the string table of the message
+    pub(crate) string_table: bool,
 }
 impl FieldInfo {
     pub(crate) fn new(field: &Field, use_default_if_deserialize_fails: Option<bool>) -> Result<Self, String> {
@@ -21,17 +23,20 @@ impl FieldInfo {
         let name = field.ident.as_ref().unwrap().to_string();
         let ty = &field.ty;
         let ty_str = quote! {#ty}.to_string();
+        if let Some(pointer) = DataType::unsupported_pointer(&ty_str) {
+            return Err(format!("The type '{pointer}' (for field '{name}') is not supported - a Box, Rc or Arc can only wrap a vector of values (use the list without the pointer instead) !"));
+        }
         let mut data_type = DataType::new(ty.clone(), ty_str, use_default_if_deserialize_fails);
         for attr in field.attrs.iter() {
             data_type.parse_attr(attr, &name)?;
         }
-        // if the data format is unknown, we need to check if the field is a unique id or a timestamp
+        // a type that is not known (and is not a unique id or a timestamp) must implement FlatField
         if data_type.data_format == common::data_format::DataFormat::Unknwon
             && !data_type.unique_id
             && !data_type.timestamp
             && !data_type.ignore_field
         {
-            return Err(format!("Please provide aditional specifications via #[flat_message_item(...)] for the field '{name}' !"));
+            data_type.use_flat_field(&name)?;
         }
         // compute the data format
         let hash = (hashes::fnv_32(&name) & 0xFFFFFF00) | data_type.type_hash();
@@ -40,10 +45,44 @@ impl FieldInfo {
             hash,
             hash_table_order: 0,
             data_type,
+            string_table: false,
         })
     }
+    /// The hidden field that stores the distinct strings of a message (a list of strings).
+    /// Its hash is smaller than the hash of any other field, so the field is read before the
+    /// fields that refer to it.
+    pub(crate) fn string_table() -> Self {
+        let ty: syn::Type = syn::parse_str("Vec<&str>").unwrap();
+        let mut data_type = DataType::new(ty, "Vec<&str>".to_string(), Some(false));
+        data_type.mandatory = false;
+        let hash = data_type.type_hash();
+        FieldInfo {
+            name: "string_table".to_string(),
+            hash,
+            hash_table_order: 0,
+            data_type,
+            string_table: true,
+        }
+    }
+    /// Stores the field as an index in the string table (see `DataType::use_string_table`).
+    pub(crate) fn use_string_table(&mut self) -> bool {
+        if !self.data_type.use_string_table() {
+            return false;
+        }
+        self.hash = (hashes::fnv_32(&self.name) & 0xFFFFFF00) | self.data_type.type_hash();
+        true
+    }
 
-
+    /// The hash of the field as an expression of the generated code (see `DataType::type_hash_tokens`).
+    pub(crate) fn hash_tokens(&self) -> proc_macro2::TokenStream {
+        if self.data_type.format_from_type.is_none() {
+            let hash = self.hash;
+            return quote! { #hash };
+        }
+        let name_hash = self.hash & 0xFFFFFF00;
+        let type_hash = self.data_type.type_hash_tokens();
+        quote! { const { #name_hash | #type_hash } }
+    }
 
     pub(crate) fn inner_var(&self) -> syn::Ident {
         syn::Ident::new(
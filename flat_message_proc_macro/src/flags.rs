@@ -280,7 +280,7 @@ impl Flags {
                 }
                 #[inline(always)]
                 unsafe fn write(obj: &[Self], p: *mut u8, pos: usize) -> usize {
-                    let len = obj.len() as u32;
+                    let len = obj.len();
                     unsafe {
                         std::ptr::write_unaligned(p.add(pos) as *mut u32, #name_hash);
                         let size_len =
@@ -290,12 +290,12 @@ impl Flags {
                             p.add(pos + size_len + 4),
                             obj.len() #multiplier,
                         );
-                        pos + size_len + (len as usize) #multiplier  + 4usize
+                        pos + size_len + len #multiplier  + 4usize
                     }
                 }
                 #[inline(always)]
                 fn size(obj: &[Self]) -> usize {
-                    flat_message::size::len(obj.len() as u32, flat_message::size::Format::#size_format)
+                    flat_message::size::len(obj.len(), flat_message::size::Format::#size_format)
                     + obj.len() #multiplier + 4usize /* name hash */
                 }
             }
@@ -1,5 +1,6 @@
 mod attribute_parser;
 mod attribute_value;
+mod batch;
 mod config;
 mod const_assetions;
 mod data_type;
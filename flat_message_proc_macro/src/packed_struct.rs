@@ -1,4 +1,4 @@
-use crate::data_type::FieldType;
+use crate::data_type::{Encoding, FieldType};
 use crate::field_info::FieldInfo;
 use crate::serde_definition::SerdeDefinition;
 use common::data_format::DataFormat;
@@ -122,12 +122,15 @@ impl<'a> PackedStruct<'a> {
         let structure_hash = self.hash;
         let mut v = Vec::new();
         let initial_field_padding = self.initial_field_padding();
+        let hash_padding = initial_field_padding.saturating_sub(4);
         let mut first_field = true;
         for field in &self.fields {
             if field.data_type.data_format.requires_padding() && !first_field {
                 let alignament = field.data_type.serialization_alignment();
                 v.push(quote! {
-                    pos = (pos + #alignament - 1) & !(#alignament - 1);
+                    let aligned_pos = (pos + #alignament - 1) & !(#alignament - 1);
+                    std::ptr::write_bytes(p.add(pos), 0, aligned_pos - pos);
+                    pos = aligned_pos;
                 });
             }
             let serde_trait = field.data_type.serde_trait();
@@ -140,6 +143,7 @@ impl<'a> PackedStruct<'a> {
         quote! {
             unsafe fn write(obj: &Self, p: *mut u8, pos: usize) -> usize {
                 std::ptr::write_unaligned(p.add(pos) as *mut u32, #structure_hash);
+                std::ptr::write_bytes(p.add(pos + 4), 0, #hash_padding);
                 let mut pos = pos + #initial_field_padding;
                 #(#v)*
                 pos
@@ -222,6 +226,9 @@ impl<'a> PackedStruct<'a> {
                         field.name
                     ));
                 }
+                if field.data_type.encoding == Encoding::Adapter {
+                    return Err(format!("Field adapters (#[flat_message_item(with = \"...\")]) and FlatField types are not supported for packed structures ! (for field {}) !", field.name));
+                }
                 if field.data_type.option {
                     return Err(format!("Option types (Option<T>)  are not supported for packed structures ! (for field {}) !", field.name));
                 }
@@ -1,7 +1,8 @@
 use crate::config::Config;
-use crate::data_type::FieldType;
+use crate::data_type::{Encoding, FieldType};
 use crate::field_info::FieldInfo;
 use common::constants;
+use common::data_format::DataFormat;
 use common::hashes;
 use quote::format_ident;
 use quote::quote;
@@ -14,14 +15,14 @@ use super::serde_definition::SerdeDefinition;
 mod gencode {
     use quote::quote;
     use crate::data_type::DataType;
-    pub(super) fn search_mandatory_field(field_name_hash: u32, field_is_missing: proc_macro2::TokenStream, create_field: proc_macro2::TokenStream)-> proc_macro2::TokenStream {
+    pub(super) fn search_mandatory_field(field_name_hash: &proc_macro2::TokenStream, field_is_missing: proc_macro2::TokenStream, create_field: proc_macro2::TokenStream)-> proc_macro2::TokenStream {
         quote! {
             unsafe { 
                 loop {
                     if ptr_it == p_end {
                         return #field_is_missing;
                     }
-                    if *ptr_it == #field_name_hash {
+                    if ptr::read_unaligned(ptr_it) == #field_name_hash {
                         ptr_it = ptr_it.add(1);  
                         break;
                     }
@@ -34,15 +35,16 @@ mod gencode {
             #create_field
         }        
     }
-    pub(super) fn search_non_mandatory_field(inner_var: &syn::Ident,field_name_hash: u32, default_value: proc_macro2::TokenStream, create_field: proc_macro2::TokenStream)-> proc_macro2::TokenStream {
+    pub(super) fn search_non_mandatory_field(inner_var: &syn::Ident,field_name_hash: &proc_macro2::TokenStream, default_value: proc_macro2::TokenStream, create_field: proc_macro2::TokenStream)-> proc_macro2::TokenStream {
         quote! {
             let create_field = loop { 
                 unsafe {
                     if ptr_it == p_end {
                         break false;
                     }
-                    if *ptr_it >= #field_name_hash {
-                        break *ptr_it == #field_name_hash;
+                    let hash = ptr::read_unaligned(ptr_it);
+                    if hash >= #field_name_hash {
+                        break hash == #field_name_hash;
                     }
                     p_ofs = p_ofs.add(1); 
                     ptr_it = ptr_it.add(1);   
@@ -63,6 +65,7 @@ mod gencode {
     }
     pub(super) fn safe_init_field_strict(dt: &DataType, inner_var: &syn::Ident, invalid_field_offset: proc_macro2::TokenStream, fail_to_deserialize: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
         let serde_trait = dt.serde_trait();
+        let table_arg = dt.extra_arg(quote! { string_table });
         let ty =  dt.ty.clone();
         if dt.option {
             // field is Option<T>
@@ -75,7 +78,7 @@ mod gencode {
                     }
                 } else {
                     // the type is alread an Option
-                    let tmp: #ty =  flat_message::#serde_trait::from_buffer(data_buffer, offset);
+                    let tmp: #ty =  flat_message::#serde_trait::from_buffer(data_buffer, offset #table_arg);
                     if tmp.is_none() {
                         return #fail_to_deserialize;
                     };
@@ -84,7 +87,7 @@ mod gencode {
             }            
         } else {
             // field is T
-            let from_buffer_call = quote! { flat_message::#serde_trait::from_buffer(data_buffer, offset) };
+            let from_buffer_call = quote! { flat_message::#serde_trait::from_buffer(data_buffer, offset #table_arg) };
             let init_code = if fail_to_deserialize.to_string() == "None" {
                 quote! {
                     let #inner_var: #ty = #from_buffer_call?;
@@ -107,6 +110,7 @@ mod gencode {
     }
     pub(super) fn unsafe_init_field_strict(dt: &DataType, inner_var: &syn::Ident, invalid_field_offset: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
         let serde_trait = dt.serde_trait();
+        let table_arg = dt.extra_arg(quote! { string_table });
         let ty =  dt.ty.clone();
         if dt.option {
             // field is Option<T>
@@ -118,7 +122,7 @@ mod gencode {
                         return #invalid_field_offset;
                     }
                 } else {
-                    Some ( unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset) })
+                    Some ( unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset #table_arg) })
                 };
             }            
         } else {
@@ -127,12 +131,13 @@ mod gencode {
                 if offset<8 || offset >= hash_table_offset {
                     return #invalid_field_offset;
                 }
-                let #inner_var: #ty = unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset) };
+                let #inner_var: #ty = unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset #table_arg) };
             }
         }
     }    
     pub(super) fn safe_init_field_fallback(dt: &DataType, inner_var: &syn::Ident, invalid_field_offset: proc_macro2::TokenStream, default_value: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
         let serde_trait = dt.serde_trait();
+        let table_arg = dt.extra_arg(quote! { string_table });
         let ty =  dt.ty.clone();
         if dt.option {
             // field is Option<T>
@@ -145,7 +150,7 @@ mod gencode {
                     }
                 } else {
                     // the type is alread an Option
-                    let tmp: #ty =  flat_message::#serde_trait::from_buffer(data_buffer, offset);
+                    let tmp: #ty =  flat_message::#serde_trait::from_buffer(data_buffer, offset #table_arg);
                     if tmp.is_none() {
                         #default_value
                     } else {
@@ -159,13 +164,14 @@ mod gencode {
                 if offset<8 || offset >= hash_table_offset {
                     return #invalid_field_offset;
                 }
-                let #inner_var: #ty = flat_message::#serde_trait::from_buffer(data_buffer, offset).unwrap_or_else(|| { #default_value });
+                let #inner_var: #ty = flat_message::#serde_trait::from_buffer(data_buffer, offset #table_arg).unwrap_or_else(|| { #default_value });
             }
         }
     }    
 
     pub(super) fn unsafe_init_field_fallback(dt: &DataType, inner_var: &syn::Ident, invalid_field_offset: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
         let serde_trait = dt.serde_trait();
+        let table_arg = dt.extra_arg(quote! { string_table });
         let ty =  dt.ty.clone();
         if dt.option {
             // field is Option<T>
@@ -177,7 +183,7 @@ mod gencode {
                         return #invalid_field_offset;
                     }
                 } else {
-                    Some ( unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset) })
+                    Some ( unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset #table_arg) })
                 };
             }            
         } else {
@@ -186,20 +192,21 @@ mod gencode {
                 if offset<8 || offset >= hash_table_offset {
                     return #invalid_field_offset;
                 }
-                let #inner_var: #ty = unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset) };
+                let #inner_var: #ty = unsafe { flat_message::#serde_trait::from_buffer_unchecked(data_buffer, offset #table_arg) };
             }
         }
     }   
 }
 
 pub(crate) struct StructInfo<'a> {
-    generics: &'a syn::Generics,
-    name: &'a syn::Ident,
-    fields: Vec<FieldInfo>,
-    unique_id: Option<FieldInfo>,
-    timestamp: Option<FieldInfo>,
-    ignored_fields: Vec<FieldInfo>,  
-    config: Config,
+    pub(crate) generics: &'a syn::Generics,
+    pub(crate) name: &'a syn::Ident,
+    pub(crate) visibility: &'a syn::Visibility,
+    pub(crate) fields: Vec<FieldInfo>,
+    pub(crate) unique_id: Option<FieldInfo>,
+    pub(crate) timestamp: Option<FieldInfo>,
+    pub(crate) ignored_fields: Vec<FieldInfo>,  
+    pub(crate) config: Config,
 }
 
 
@@ -210,6 +217,39 @@ impl<'a> StructInfo<'a> {
         let res = if use_self { format_ident!("self") } else { format_ident!("object") };
         quote! { #res }
     }
+    fn field_value(field: &FieldInfo, self_name: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+        if field.string_table {
+            // the hidden field is filled with the strings collected by `generate_string_table_code`
+            quote! { string_table.strings }
+        } else {
+            let field_name = field.name_ident();
+            quote! { #self_name.#field_name }
+        }
+    }
+    fn generate_string_table_code(&self, use_self: bool) -> proc_macro2::TokenStream {
+        if !self.fields.iter().any(|field| field.string_table) {
+            return quote! {};
+        }
+        let self_name = StructInfo::self_name(use_self);
+        let collect_code = self.fields.iter().filter(|field| field.data_type.encoding == Encoding::StringTable).map(|field| {
+            let field_name = field.name_ident();
+            if field.data_type.option {
+                quote! {
+                    if let Some(obj) = &#self_name.#field_name {
+                        ::flat_message::SerDeStringRef::collect(obj, &mut string_table);
+                    }
+                }
+            } else {
+                quote! {
+                    ::flat_message::SerDeStringRef::collect(&#self_name.#field_name, &mut string_table);
+                }
+            }
+        });
+        quote! {
+            let mut string_table = ::flat_message::StringTable::default();
+            #(#collect_code)*
+        }
+    }
     fn generate_metadata_serialization_code(&self) -> Vec<proc_macro2::TokenStream> {
         let mut lines = Vec::with_capacity(8);
         if let Some(timestamp) = &self.timestamp {
@@ -265,51 +305,55 @@ impl<'a> StructInfo<'a> {
     fn generate_compute_size_code(&self, use_self: bool, no_flags: bool) -> Vec<proc_macro2::TokenStream> {
         let self_name = StructInfo::self_name(use_self);
         let compute_size_code = self.fields.iter().map(|field| {
-            let field_name = field.name_ident();
+            let field_value = StructInfo::field_value(field, &self_name);
             let serialization_trait = field.data_type.serde_trait();
+            let table_arg = field.data_type.extra_arg(quote! { &string_table });
             let serialization_alignment = field.serialization_alignment();
             let size_increase = if serialization_alignment>1 {
                 quote! {
                     size = (size + #serialization_alignment - 1) & !(#serialization_alignment - 1);
-                    size += ::flat_message::#serialization_trait::size(&#self_name.#field_name);
+                    size += ::flat_message::#serialization_trait::size(&#field_value #table_arg);
                 }
             } else {
                 quote! {
-                    size += ::flat_message::#serialization_trait::size(&#self_name.#field_name);
-                }
-            };
-            let size_increase_option = if serialization_alignment>1 {
-                quote! {
-                    size = (size + #serialization_alignment - 1) & !(#serialization_alignment - 1);
-                    size += ::flat_message::#serialization_trait::size(obj);
-                }
-            } else {
-                quote! {
-                    size += ::flat_message::#serialization_trait::size(obj);
+                    size += ::flat_message::#serialization_trait::size(&#field_value #table_arg);
                 }
             };
             if field.data_type.option {
+                // the serialization code aligns the position even if the field is None
+                let alignament_code = if serialization_alignment>1 {
+                    quote! {
+                        size = (size + #serialization_alignment - 1) & !(#serialization_alignment - 1);
+                    }
+                } else {
+                    quote! {}
+                };
                 quote! {
-                    if let Some(obj) = &#self_name.#field_name {
-                        #size_increase_option
+                    #alignament_code
+                    if let Some(obj) = &#field_value {
+                        size += ::flat_message::#serialization_trait::size(obj #table_arg);
                     }
                 }
             } else {
                 quote! { #size_increase }
             }
         });
-        let mut v: Vec<_> = compute_size_code.collect();
+        let mut v = vec![self.generate_string_table_code(use_self)];
+        v.extend(compute_size_code);
         let ref_table_size_8 = self.fields.len();
         let ref_table_size_16 = self.fields.len() * 2;
         let ref_table_size_32 = self.fields.len() * 4;
+        let ref_table_size_64 = self.fields.len() * 8;
         if no_flags {
             v.push(quote! {
                 let ref_table_size: usize = if size < 0x100 {
                     #ref_table_size_8
                 } else if size < 0x10000 {
                     #ref_table_size_16
-                } else {
+                } else if size < 0x1_0000_0000 {
                     #ref_table_size_32
+                } else {
+                    #ref_table_size_64
                 };
             });
         } else {
@@ -327,11 +371,16 @@ impl<'a> StructInfo<'a> {
                     offset_size = RefOffsetSize::U16;
                     ref_table_size = #ref_table_size_16;
                     flags = 0b0000_0001;
-                } else {
+                } else if size < 0x1_0000_0000 {
                     // 32 bits
                     offset_size = RefOffsetSize::U32;
                     ref_table_size = #ref_table_size_32;
                     flags = 0b0000_0010;
+                } else {
+                    // 64 bits
+                    offset_size = RefOffsetSize::U64;
+                    ref_table_size = #ref_table_size_64;
+                    flags = 0b0000_0011;
                 }
             });
         }
@@ -339,8 +388,9 @@ impl<'a> StructInfo<'a> {
     }
     fn generate_hash_table_code(&self) -> Vec<proc_macro2::TokenStream> {
         // build a sorted hash vector
-        let mut hashes: Vec<u32> = self.fields.iter().map(|field| field.hash).collect();
-        hashes.sort();
+        let mut fields: Vec<&FieldInfo> = self.fields.iter().collect();
+        fields.sort_by_key(|field| field.hash);
+        let hashes: Vec<_> = fields.iter().map(|field| field.hash_tokens()).collect();
 
         let mut v: Vec<_> = Vec::with_capacity(16);
         v.push(quote! {
@@ -356,13 +406,17 @@ impl<'a> StructInfo<'a> {
     fn generate_fields_serialize_code(&self, ref_size: u8, use_self: bool) -> Vec<proc_macro2::TokenStream> {
         let self_name = StructInfo::self_name(use_self);
         let v: Vec<_> = self.fields.iter().map(|field| {
-            let field_name = syn::Ident::new(field.name.as_str(), proc_macro2::Span::call_site());
+            let field_value = StructInfo::field_value(field, &self_name);
             let hash_table_order = field.hash_table_order as usize;
             let serde_trait = field.data_type.serde_trait();
+            let table_arg = field.data_type.extra_arg(quote! { &string_table });
             let serialization_alignment = field.serialization_alignment();
             let alignament_code = if serialization_alignment>1 {
+                    // the buffer is not zeroed before writing (the padding bytes are)
                     quote! {
-                        buf_pos = (buf_pos + #serialization_alignment - 1) & !(#serialization_alignment - 1);
+                        let aligned_pos = (buf_pos + #serialization_alignment - 1) & !(#serialization_alignment - 1);
+                        ptr::write_bytes(buffer.add(buf_pos), 0, aligned_pos - buf_pos);
+                        buf_pos = aligned_pos;
                     }
                 } else {
                     quote! {}
@@ -386,20 +440,27 @@ impl<'a> StructInfo<'a> {
                         ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order*4) as *mut u32, offset);
                     }
                 }
+                8 => {
+                    quote! {
+                        let offset = buf_pos as u64;
+                        ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order*8) as *mut u64, offset);
+                    }
+                }
                 _ => quote! {}
             };
             let none_refcode = match ref_size {
                 1 => quote! { ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order) as *mut u8, 0u8); },
                 2 => quote! { ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order*2) as *mut u16, 0u16); },                
-                4 => quote! { ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order*4) as *mut u32, 032); },
+                4 => quote! { ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order*4) as *mut u32, 0u32); },
+                8 => quote! { ptr::write_unaligned(buffer.add(ref_offset + #hash_table_order*8) as *mut u64, 0u64); },
                 _ => quote! {}
             };
                 
             let serialize_code = if field.data_type.option {
                 quote! {
-                    if let Some(obj) = &#self_name.#field_name {
+                    if let Some(obj) = &#field_value {
                         #refcode
-                        buf_pos = ::flat_message::#serde_trait::write(obj, buffer, buf_pos);
+                        buf_pos = ::flat_message::#serde_trait::write(obj, buffer, buf_pos #table_arg);
                     } else {
                         #none_refcode
                     }
@@ -407,7 +468,7 @@ impl<'a> StructInfo<'a> {
             } else {
                 quote! {
                     #refcode
-                    buf_pos = ::flat_message::#serde_trait::write(&#self_name.#field_name, buffer, buf_pos);
+                    buf_pos = ::flat_message::#serde_trait::write(&#field_value, buffer, buf_pos #table_arg);
                 }
             };
             quote! {
@@ -519,6 +580,7 @@ impl<'a> StructInfo<'a> {
                     U8,
                     U16,
                     U32,
+                    U64,
                 }
                 let len = input.len();
                 if len < 8 {
@@ -547,17 +609,18 @@ impl<'a> StructInfo<'a> {
                     0 => RefOffsetSize::U8,
                     1 => RefOffsetSize::U16,
                     2 => RefOffsetSize::U32,
-                    _ => return Err(flat_message::Error::InvalidOffsetSize),
+                    _ => RefOffsetSize::U64,
                 };
                 let ref_table_size =  match ref_offset_size {
                     RefOffsetSize::U8 => header.fields_count as usize,
                     RefOffsetSize::U16 =>header.fields_count as usize * 2,
                     RefOffsetSize::U32 =>header.fields_count as usize * 4,
+                    RefOffsetSize::U64 =>header.fields_count as usize * 8,
                 };
                 let hash_table_size = header.fields_count as usize * 4;
                 let min_size = 8/* header */ + metadata_size + hash_table_size + ref_table_size;
                 if min_size > len {
-                    return Err(flat_message::Error::InvalidSizeToStoreFieldsTable((len as u32, min_size as u32)));
+                    return Err(flat_message::Error::InvalidSizeToStoreFieldsTable((len as u64, min_size as u64)));
                 }
                 // read metada if case
                 #metadata_code
@@ -574,8 +637,8 @@ impl<'a> StructInfo<'a> {
         }
     }
 
-    fn generate_mandatory_strict_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: u32, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
-        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u32, hash_table_offset as u32))) } } else { quote! { None } };
+    fn generate_mandatory_strict_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: &proc_macro2::TokenStream, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
+        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u64, hash_table_offset as u64))) } } else { quote! { None } };
         let fail_to_deserialize = if return_err { quote! { Err(flat_message::Error::FailToDeserialize(#field_name_hash)) }  } else { quote! { None } };
         let field_is_missing = if return_err { quote! { Err(flat_message::Error::FieldIsMissing(#field_name_hash)) }  } else { quote! { None } };
         let init_code = if unchecked_code { 
@@ -586,8 +649,8 @@ impl<'a> StructInfo<'a> {
         gencode::search_mandatory_field(field_name_hash, field_is_missing, init_code)
     }
 
-    fn generate_non_mandatory_strict_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: u32, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
-        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u32, hash_table_offset as u32))) } } else { quote! { None } };
+    fn generate_non_mandatory_strict_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: &proc_macro2::TokenStream, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
+        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u64, hash_table_offset as u64))) } } else { quote! { None } };
         let fail_to_deserialize = if return_err { quote! { Err(flat_message::Error::FailToDeserialize(#field_name_hash)) }  } else { quote! { None } };
         let default_value = dt.default_value(false);
         let init_code = if unchecked_code { 
@@ -598,8 +661,8 @@ impl<'a> StructInfo<'a> {
         gencode::search_non_mandatory_field(inner_var, field_name_hash, default_value, init_code)        
     }  
 
-    fn generate_mandatory_fallback_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: u32, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
-        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u32, hash_table_offset as u32))) } } else { quote! { None } };
+    fn generate_mandatory_fallback_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: &proc_macro2::TokenStream, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
+        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u64, hash_table_offset as u64))) } } else { quote! { None } };
         let field_is_missing = if return_err { quote! { Err(flat_message::Error::FieldIsMissing(#field_name_hash)) }  } else { quote! { None } };
         let default_value = dt.default_value(false);
         let init_code = if unchecked_code { 
@@ -610,8 +673,8 @@ impl<'a> StructInfo<'a> {
         gencode::search_mandatory_field(field_name_hash, field_is_missing, init_code)
     }    
 
-    fn generate_non_mandatory_fallback_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: u32, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
-        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u32, hash_table_offset as u32))) } } else { quote! { None } };
+    fn generate_non_mandatory_fallback_field_deserialize_code(&self, dt: &DataType, inner_var: &syn::Ident, field_name_hash: &proc_macro2::TokenStream, unchecked_code: bool, return_err: bool) -> proc_macro2::TokenStream {
+        let invalid_field_offset = if return_err { quote! { Err(flat_message::Error::InvalidFieldOffset((offset as u64, hash_table_offset as u64))) } } else { quote! { None } };
         let default_value = dt.default_value(false);
         let init_code = if unchecked_code { 
             gencode::unsafe_init_field_fallback(dt, inner_var, invalid_field_offset)
@@ -630,9 +693,11 @@ impl<'a> StructInfo<'a> {
     ) -> Vec<proc_macro2::TokenStream> {
         struct HashAndInnerVar<'a>   {
             hash: u32,
+            hash_tokens: proc_macro2::TokenStream,
             inner_var: syn::Ident,
             mandatory: bool,
             strict: bool,   
+            string_table: bool,
             dt: &'a DataType,
         }
         let mut v = Vec::with_capacity(4);
@@ -641,9 +706,11 @@ impl<'a> StructInfo<'a> {
             .iter()
             .map(|field| HashAndInnerVar {
                 hash: field.hash,
+                hash_tokens: field.hash_tokens(),
                 inner_var: field.inner_var(),
                 mandatory: field.data_type.mandatory,
                 strict: !field.data_type.use_default_if_deserialize_fails,
+                string_table: field.string_table,
                 dt: &field.data_type,
             })
             .collect();
@@ -658,6 +725,9 @@ impl<'a> StructInfo<'a> {
             4 => quote! {
                 let mut p_ofs = unsafe { buffer.add(ref_table_offset) as *const u32 };
             },
+            8 => quote! {
+                let mut p_ofs = unsafe { buffer.add(ref_table_offset) as *const u64 };
+            },
             _ => quote! {},
         });
         for obj in hashes {
@@ -666,7 +736,7 @@ impl<'a> StructInfo<'a> {
                     v.push(self.generate_mandatory_strict_field_deserialize_code(
                         obj.dt,
                         &obj.inner_var,
-                        obj.hash,
+                        &obj.hash_tokens,
                         unchecked_code,
                         return_err
                     ));
@@ -675,7 +745,7 @@ impl<'a> StructInfo<'a> {
                     v.push(self.generate_non_mandatory_strict_field_deserialize_code(
                         obj.dt,
                         &obj.inner_var,
-                        obj.hash,
+                        &obj.hash_tokens,
                         unchecked_code,
                         return_err
                     ));
@@ -684,7 +754,7 @@ impl<'a> StructInfo<'a> {
                     v.push(self.generate_mandatory_fallback_field_deserialize_code(
                         obj.dt,
                         &obj.inner_var,
-                        obj.hash,
+                        &obj.hash_tokens,
                         unchecked_code,
                         return_err
                     ));
@@ -693,12 +763,19 @@ impl<'a> StructInfo<'a> {
                     v.push(self.generate_non_mandatory_fallback_field_deserialize_code(
                         obj.dt,
                         &obj.inner_var,
-                        obj.hash,
+                        &obj.hash_tokens,
                         unchecked_code,
                         return_err
                     ));
                 }                             
             }
+            if obj.string_table {
+                // the string table has the smallest hash (it is read before the fields that use it)
+                let inner_var = &obj.inner_var;
+                v.push(quote! {
+                    let string_table: &[&str] = &#inner_var;
+                });
+            }
         }
         v
     }
@@ -712,7 +789,7 @@ impl<'a> StructInfo<'a> {
         }).collect()
     }
     fn generate_struct_construction_code(&self) -> proc_macro2::TokenStream {
-        let struct_fields = self.fields.iter().map(|field| {
+        let struct_fields = self.fields.iter().filter(|field| !field.string_table).map(|field| {
             let field_name = syn::Ident::new(field.name.as_str(), proc_macro2::Span::call_site());
             let iner_value = field.inner_var();
             Some(quote! {
@@ -773,6 +850,7 @@ impl<'a> StructInfo<'a> {
         let serialize_code_u8 = self.generate_fields_serialize_code(1, true);
         let serialize_code_u16 = self.generate_fields_serialize_code(2, true);
         let serialize_code_u32 = self.generate_fields_serialize_code(4, true);
+        let serialize_code_u64 = self.generate_fields_serialize_code(8, true);
         let metadata_serialization_code = self.generate_metadata_serialization_code();
         let hash_table_code = self.generate_hash_table_code();
         let compute_size_code = self.generate_compute_size_code(true, false);
@@ -795,7 +873,11 @@ impl<'a> StructInfo<'a> {
                     U8,
                     U16,
                     U32,
+                    U64,
                 }
+                // the sizes returned by field adapters are computed only once
+                let _adapter_sizes = flat_message::AdapterSizes::enter();
+                let nested_struct_sizes = flat_message::headers::NestedStructSizes::enter();
                 // basic header (magic + fields count + flags + version)
                 let mut buf_pos = 8usize;
                 let mut size = 8usize;
@@ -820,11 +902,15 @@ impl<'a> StructInfo<'a> {
                     flags,
                 };
                 // Step 7: allocate memory
-                if size > config.max_size() as usize {
-                    return Err(flat_message::Error::ExceedMaxSize((size as u32,config.max_size())));
+                if size as u64 > config.max_size() {
+                    return Err(flat_message::Error::ExceedMaxSize((size as u64,config.max_size())));
+                }
+                // the size of a nested structure is stored on 24 bits
+                if let Some(struct_size) = nested_struct_sizes.too_large() {
+                    return Err(flat_message::Error::ExceedMaxSize((struct_size as u64, flat_message::headers::MAX_NESTED_STRUCT_SIZE as u64)));
                 }
-                output.clear();
-                output.resize_zero(size);
+                // only the padding bytes are zeroed (every other byte is written below)
+                output.resize(size);
                 let output = output.as_mut_slice();
                 // Step 8: write data directly to a raw pointer
                 let buffer: *mut u8 = output.as_mut_ptr();
@@ -842,7 +928,13 @@ impl<'a> StructInfo<'a> {
                         RefOffsetSize::U32 => {
                             #(#serialize_code_u32)*
                         }
+                        RefOffsetSize::U64 => {
+                            #(#serialize_code_u64)*
+                        }
                     }
+                    // padding before the hash table
+                    debug_assert!(buf_pos <= hash_table_offset);
+                    ptr::write_bytes(buffer.add(buf_pos), 0, hash_table_offset - buf_pos);
                     // hash table
                     #(#hash_table_code)*
                     // metadata
@@ -859,6 +951,7 @@ impl<'a> StructInfo<'a> {
         let deserializaton_code_u8 = self.generate_fields_deserialize_code(1, false, true);
         let deserializaton_code_u16 = self.generate_fields_deserialize_code(2, false, true);
         let deserializaton_code_u32 = self.generate_fields_deserialize_code(4, false, true);
+        let deserializaton_code_u64 = self.generate_fields_deserialize_code(8, false, true);
         let checksum_check_code = self.generate_checksum_check_code();
         let ctor_code = self.generate_struct_construction_code();
         let lifetimes = &self.generics.params;
@@ -867,6 +960,7 @@ impl<'a> StructInfo<'a> {
             let deserializaton_code_u8_unchecked = self.generate_fields_deserialize_code(1, true, true);
             let deserializaton_code_u16_unchecked = self.generate_fields_deserialize_code(2, true, true);
             let deserializaton_code_u32_unchecked = self.generate_fields_deserialize_code(4, true, true);
+            let deserializaton_code_u64_unchecked = self.generate_fields_deserialize_code(8, true, true);
             quote! {
                 #header_deserialization_code
                 match ref_offset_size {
@@ -882,6 +976,10 @@ impl<'a> StructInfo<'a> {
                         #(#deserializaton_code_u32_unchecked)*
                         Ok(#ctor_code)
                     }
+                    RefOffsetSize::U64 => {
+                        #(#deserializaton_code_u64_unchecked)*
+                        Ok(#ctor_code)
+                    }
                 }
             }
         } else {
@@ -891,25 +989,45 @@ impl<'a> StructInfo<'a> {
         };
 
 
+        let deserialize_code = quote! {
+            #header_deserialization_code
+            #checksum_check_code
+            match ref_offset_size {
+                RefOffsetSize::U8 => {
+                    #(#deserializaton_code_u8)*
+                    Ok(#ctor_code)
+                }
+                RefOffsetSize::U16 => {
+                    #(#deserializaton_code_u16)*
+                    Ok(#ctor_code)
+                }
+                RefOffsetSize::U32 => {
+                    #(#deserializaton_code_u32)*
+                    Ok(#ctor_code)
+                }
+                RefOffsetSize::U64 => {
+                    #(#deserializaton_code_u64)*
+                    Ok(#ctor_code)
+                }
+            }
+        };
+        // Box / Rc / Arc fields (directly or from a nested struct / variant) can be recursive
+        // so the depth limit needs to be enforced for them
+        let may_recurse = self.fields.iter().any(|field| {
+            field.data_type.pointer || field.data_type.data_format.is_object_container()
+        });
+        let deserialize_code = if may_recurse {
+            quote! {
+                flat_message::limits::with_limits(None, || { #deserialize_code })
+            }
+        } else {
+            deserialize_code
+        };
+
         quote! {
             fn deserialize_from(input: & #lifetimes ::flat_message::Storage) -> core::result::Result<Self,flat_message::Error>
             {
-                #header_deserialization_code
-                #checksum_check_code
-                match ref_offset_size {
-                    RefOffsetSize::U8 => {
-                        #(#deserializaton_code_u8)*
-                        Ok(#ctor_code)
-                    }
-                    RefOffsetSize::U16 => {
-                        #(#deserializaton_code_u16)*
-                        Ok(#ctor_code)
-                    }
-                    RefOffsetSize::U32 => {
-                        #(#deserializaton_code_u32)*
-                        Ok(#ctor_code)
-                    }
-                }
+                #deserialize_code
             }
             unsafe fn deserialize_from_unchecked(input: & #lifetimes ::flat_message::Storage) -> core::result::Result<Self,flat_message::Error>
             {
@@ -917,6 +1035,34 @@ impl<'a> StructInfo<'a> {
             }
         }
     }
+    /// `MessageView` (used by `OwnedMessage`) for structures without type parameters and with at most
+    /// one lifetime. `shorten` only compiles if the structure is covariant in its lifetime (structures
+    /// with `IterField` fields are not, and they can not be deserialized anyway).
+    fn generate_message_view_code(&self) -> proc_macro2::TokenStream {
+        let name = self.name;
+        if self.generics.type_params().count() > 0
+            || self.generics.const_params().count() > 0
+            || self.generics.lifetimes().count() > 1
+            || self.generics.where_clause.is_some()
+            || self.fields.iter().any(|f| f.data_type.field_type == FieldType::Iter)
+        {
+            return quote! {};
+        }
+        let (static_type, view_type, short_type, long_type) = if self.generics.lifetimes().count() == 1 {
+            (quote! { #name<'static> }, quote! { #name<'__view> }, quote! { #name<'__short> }, quote! { #name<'__long> })
+        } else {
+            (quote! { #name }, quote! { #name }, quote! { #name }, quote! { #name })
+        };
+        quote! {
+            unsafe impl flat_message::MessageView for #static_type {
+                type View<'__view> = #view_type;
+                #[inline(always)]
+                fn shorten<'__short, '__long: '__short>(view: &'__short #long_type) -> &'__short #short_type {
+                    view
+                }
+            }
+        }
+    }
     pub(crate) fn generate_code(&self) -> proc_macro::TokenStream {
         let name = self.name;
         let generics = self.generics;
@@ -928,6 +1074,12 @@ impl<'a> StructInfo<'a> {
         let serialize_to_methods = self.generate_serialize_to_methods();
         let deserialize_from_methods = self.generate_deserialize_from_methods();
         let const_assertion_functions = self.generate_const_assertion_functions();
+        let message_view_code = self.generate_message_view_code();
+        let batch_code = if self.config.batch {
+            self.generate_batch_code().unwrap_or_else(|e| quote! { compile_error!(#e); })
+        } else {
+            quote! {}
+        };
 
         let new_code = quote! {
 
@@ -937,6 +1089,10 @@ impl<'a> StructInfo<'a> {
                 #serialize_to_methods
                 #deserialize_from_methods
             }
+
+            #message_view_code
+
+            #batch_code
         };
         new_code.into()
     }
@@ -948,6 +1104,7 @@ impl<'a> StructInfo<'a> {
         let serialize_code_u8 = self.generate_fields_serialize_code(1, false);
         let serialize_code_u16 = self.generate_fields_serialize_code(2, false);
         let serialize_code_u32 = self.generate_fields_serialize_code(4, false);
+        let serialize_code_u64 = self.generate_fields_serialize_code(8, false);
         let hash_table_code = self.generate_hash_table_code();
         let compute_size_code = self.generate_compute_size_code(false, false);        
         quote! {
@@ -957,6 +1114,7 @@ impl<'a> StructInfo<'a> {
                     U8,
                     U16,
                     U32,
+                    U64,
                 }
                 // basic header (magic + fields count + flags + version)
                 let mut buf_pos = 8usize;
@@ -970,7 +1128,6 @@ impl<'a> StructInfo<'a> {
                 size = ref_offset + ref_table_size;
                 // Step 4: compute aditional size of metainformation
                 let sz_flags_pack: u32 = ((size as u32) << 8) | (flags as u32) | ((#fields_count << 2) & 0xFF) as u32;
-                // fill with 0 --> not really needed as the storage is already zeroed before writing
                 let buffer: *mut u8 = unsafe { p.add(pos) };
                 unsafe {
                     // header
@@ -987,7 +1144,13 @@ impl<'a> StructInfo<'a> {
                         RefOffsetSize::U32 => {
                             #(#serialize_code_u32)*
                         }
+                        RefOffsetSize::U64 => {
+                            #(#serialize_code_u64)*
+                        }
                     }
+                    // padding before the hash table
+                    debug_assert!(buf_pos <= hash_table_offset);
+                    ptr::write_bytes(buffer.add(buf_pos), 0, hash_table_offset - buf_pos);
                     // hash table
                     #(#hash_table_code)*
                 }
@@ -1006,7 +1169,7 @@ impl<'a> StructInfo<'a> {
                 size = (size + 3) & !3;
                 let ref_offset = size + 4 * #fields_count as usize;
                 size = ref_offset + ref_table_size;
-                size
+                ::flat_message::headers::check_nested_struct_size(size)
             }
         }
     }    
@@ -1030,6 +1193,7 @@ impl<'a> StructInfo<'a> {
                     U8,
                     U16,
                     U32,
+                    U64,
                 }
                 let buffer_len = input.len();
                 if buffer_len < 8 {
@@ -1046,12 +1210,13 @@ impl<'a> StructInfo<'a> {
                     0 => RefOffsetSize::U8,
                     1 => RefOffsetSize::U16,
                     2 => RefOffsetSize::U32,
-                    _ => return None,
+                    _ => RefOffsetSize::U64,
                 };
                 let ref_table_size =  match ref_offset_size {
                     RefOffsetSize::U8 => fields_count as usize,
                     RefOffsetSize::U16 =>fields_count as usize * 2,
                     RefOffsetSize::U32 =>fields_count as usize * 4,
+                    RefOffsetSize::U64 =>fields_count as usize * 8,
                 };
                 let hash_table_size = fields_count as usize * 4;
                 let struct_len = (size_and_flags >> 8) as usize;
@@ -1093,6 +1258,7 @@ impl<'a> StructInfo<'a> {
         let deserializaton_code_u8 = self.generate_fields_deserialize_code(1, false, false);
         let deserializaton_code_u16 = self.generate_fields_deserialize_code(2, false, false);
         let deserializaton_code_u32 = self.generate_fields_deserialize_code(4, false, false);
+        let deserializaton_code_u64 = self.generate_fields_deserialize_code(8, false, false);
         let ctor_code = self.generate_struct_construction_code();
 
         let serde_code = quote! {
@@ -1105,6 +1271,7 @@ impl<'a> StructInfo<'a> {
                     flat_message::SerDe::from_buffer(buf, pos).unwrap()
                 }
                 fn from_buffer(buf: &#implicit_lifetime [u8], pos: usize) -> Option<Self> {
+                    let _guard = flat_message::limits::DepthGuard::enter()?;
                     #header_read
                     match ref_offset_size {
                         RefOffsetSize::U8 => {
@@ -1119,6 +1286,10 @@ impl<'a> StructInfo<'a> {
                             #(#deserializaton_code_u32)*
                             Some(#ctor_code)
                         }
+                        RefOffsetSize::U64 => {
+                            #(#deserializaton_code_u64)*
+                            Some(#ctor_code)
+                        }
                     }                    
                 }
                 #serde_write
@@ -1171,9 +1342,34 @@ impl<'a> StructInfo<'a> {
                 }
                 
             }
+            if config.string_table {
+                if let Some(field) = data_members.iter().find(|field| {
+                    field.data_type.field_type == FieldType::Iter && field.data_type.data_format == DataFormat::String
+                }) {
+                    return Err(format!("IterField of strings can not be used in a message with a string table (field '{}' from structure {}) - use a Vec instead !", field.name, input.ident));
+                }
+                let mut uses_string_table = false;
+                for field in data_members.iter_mut() {
+                    uses_string_table |= field.use_string_table();
+                }
+                if uses_string_table {
+                    let string_table = FieldInfo::string_table();
+                    if let Some(field) = data_members.iter().find(|field| field.hash <= string_table.hash) {
+                        return Err(format!("The hash of field '{}' from structure {} is smaller than the hash of the string table - please rename the field or disable the string table !", field.name, input.ident));
+                    }
+                    data_members.push(string_table);
+                }
+            }
             if data_members.len() > 0xFFFF {
                 return Err(format!("Structs with more than 65535 fields are not supported ! (Current structure has {} fields)", data_members.len()));
             }
+            // the order of a FlatField without a format attribute only depends on the name hash (the
+            // type hash is computed by the generated code)
+            for field in data_members.iter().filter(|field| field.data_type.format_from_type.is_some()) {
+                if let Some(other) = data_members.iter().find(|other| other.name != field.name && (other.hash & 0xFFFFFF00) == (field.hash & 0xFFFFFF00)) {
+                    return Err(format!("The fields '{}' and '{}' from structure {} have the same name hash - please add #[flat_message_item(format = \"...\")] to field '{}' !", field.name, other.name, input.ident, field.name));
+                }
+            }
             // sort the fields again (based on hash)
             data_members.sort_by_key(|field_info| field_info.hash);
             // compute the order
@@ -1189,7 +1385,7 @@ impl<'a> StructInfo<'a> {
                 //fields_name: fields,
                 fields: data_members,
                 config,
-                //visibility: &input.vis,
+                visibility: &input.vis,
                 generics: &input.generics,
                 name: &input.ident,
                 unique_id,
@@ -157,6 +157,138 @@ pub(crate) fn type_name_formatter(name: &mut String) {
     }
 }
 
+/// Wrappers that are serialized exactly like the type they wrap (name, module from `std`)
+static POINTER_TYPES: &[(&str, &str)] = &[
+    ("Cow", "borrow"),
+    ("Box", "boxed"),
+    ("Rc", "rc"),
+    ("Arc", "sync"),
+];
+
+/// For a formatted `Cow<'a, T>`, `Box<T>`, `Rc<T>` or `Arc<T>` type name returns the
+/// name of the wrapper and the wrapped type (`T`). Returns `None` for any other type.
+pub(crate) fn pointer_inner_type(name: &str) -> Option<(&'static str, String)> {
+    for (pointer, module) in POINTER_TYPES {
+        let without_path = name
+            .strip_prefix("std :: ")
+            .unwrap_or(name)
+            .strip_prefix(module)
+            .and_then(|n| n.strip_prefix(" :: "))
+            .unwrap_or(name);
+        let Some(inner) = without_path
+            .strip_prefix(pointer)
+            .and_then(|n| n.strip_prefix('<'))
+            .and_then(|n| n.strip_suffix('>'))
+        else {
+            continue;
+        };
+        let inner = if *pointer == "Cow" {
+            let inner = inner.strip_prefix("'static").unwrap_or(inner).trim_start();
+            inner.strip_prefix(',').unwrap_or(inner).trim()
+        } else {
+            inner.trim()
+        };
+        return Some((pointer, inner.to_string()));
+    }
+    None
+}
+
+/// For a formatted `Vec<Option<T>>` or `NullableSlice<'a, T>` type name returns the element type (`T`).
+pub(crate) fn nullable_element_type(name: &str) -> Option<String> {
+    if let Some(inner) = name.strip_prefix("Vec<Option<").and_then(|n| n.strip_suffix(">>")) {
+        return Some(inner.to_string());
+    }
+    let inner = name
+        .strip_prefix("flat_message :: ")
+        .unwrap_or(name)
+        .strip_prefix("NullableSlice<")?
+        .strip_suffix('>')?
+        .trim_start();
+    Some(inner.strip_prefix(',').unwrap_or(inner).trim().to_string())
+}
+
+/// For a formatted `DeltaSlice<'a, T>` type name returns the element type (`T`).
+pub(crate) fn delta_element_type(name: &str) -> Option<String> {
+    let inner = name
+        .strip_prefix("flat_message :: ")
+        .unwrap_or(name)
+        .strip_prefix("DeltaSlice<")?
+        .strip_suffix('>')?
+        .trim_start();
+    Some(inner.strip_prefix(',').unwrap_or(inner).trim().to_string())
+}
+
+/// For a formatted `IterField<'a, T>` type name returns the element type (`T`).
+pub(crate) fn iter_element_type(name: &str) -> Option<String> {
+    let inner = name
+        .strip_prefix("flat_message :: ")
+        .unwrap_or(name)
+        .strip_prefix("IterField<")?
+        .strip_suffix('>')?
+        .trim_start();
+    Some(inner.strip_prefix(',').unwrap_or(inner).trim().to_string())
+}
+
+/// For a formatted `Vec<Vec<T>>` or `Vec<&[T]>` type name returns the element type (`T`).
+pub(crate) fn nested_list_element_type(name: &str) -> Option<String> {
+    let inner = name.strip_prefix("Vec<")?.strip_suffix('>')?;
+    let element = if let Some(element) = inner.strip_prefix("Vec<").and_then(|n| n.strip_suffix('>')) {
+        element
+    } else {
+        let slice = inner.strip_prefix('&')?;
+        let element = slice.strip_prefix('[')?.strip_suffix(']')?;
+        // &[T; N] is an array, not a slice
+        if array_element_type(slice).is_some() {
+            return None;
+        }
+        element
+    };
+    if element.is_empty() {
+        None
+    } else {
+        Some(element.to_string())
+    }
+}
+
+/// For a formatted `[T; N]` type name returns the element type (`T`).
+pub(crate) fn array_element_type(name: &str) -> Option<String> {
+    let inner = name.strip_prefix('[')?.strip_suffix(']')?;
+    let (element, count) = inner.rsplit_once(';')?;
+    // the size of the array must be the last item (e.g. not `[[u8; 3]]`)
+    if element.trim().is_empty() || count.trim().is_empty() || count.contains(']') {
+        return None;
+    }
+    Some(element.trim().to_string())
+}
+
+/// For a formatted tuple type name (e.g. `(u32, String)`) returns the types of its elements.
+pub(crate) fn tuple_elements(name: &str) -> Option<Vec<String>> {
+    let inner = name.strip_prefix('(')?.strip_suffix(')')?;
+    let mut elements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (idx, c) in inner.char_indices() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                elements.push(inner[start..idx].trim().to_string());
+                start = idx + 1;
+            }
+            _ => {}
+        }
+    }
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        elements.push(last.to_string());
+    }
+    // a parenthesized type without any comma (e.g. `(u32)`) is not a tuple
+    if elements.is_empty() || !inner.contains(',') {
+        return None;
+    }
+    Some(elements)
+}
+
 pub(crate) fn validate_one_string_parameter(input: TokenStream, name: &str) -> String {
     let mut tokens = input.into_iter().peekable();
 
@@ -186,3 +318,21 @@ pub(crate) fn validate_one_string_parameter(input: TokenStream, name: &str) -> S
 
     string_param
 }
+
+/// Returns `T` if `ty` is `name<T>` (for example the type of the elements of a `Vec<T>`).
+pub(crate) fn generic_argument(ty: &syn::Type, name: &str) -> Option<syn::Type> {
+    let syn::Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != name {
+        return None;
+    }
+    let syn::PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first() {
+        Some(syn::GenericArgument::Type(ty)) if args.args.len() == 1 => Some(ty.clone()),
+        _ => None,
+    }
+}
@@ -1,5 +1,5 @@
 use super::ConstAssertions;
-use crate::data_type::DataType;
+use crate::data_type::{DataType, Encoding, FieldType};
 use crate::serde_definition::SerdeDefinition;
 use common::data_format::DataFormat;
 use proc_macro2::TokenStream;
@@ -110,11 +110,18 @@ impl Variant {
             let serde_trait = variant.serde_trait.clone();
             let extra_size = variant.extra_size;
             let hash = variant.hash;
+            // 16 bytes aligned values are stored after 8 bytes of padding
+            let padding = if extra_size > 8 {
+                quote! { std::ptr::write_bytes(p.add(pos+8), 0, #extra_size - 8); }
+            } else {
+                quote! {}
+            };
             if let Some(dt) = &variant.data_type {
                 if dt.option {
                     let hash_none = Self::option_hash_from_hash(hash);
                     v.push(quote! {
                         #struct_name::#name(obj) => {
+                            #padding
                             if let Some(obj) = obj {
                                 std::ptr::write_unaligned(p.add(pos+4) as *mut u32, #hash);
                                 ::flat_message::#serde_trait::write(obj,p,pos+#extra_size)
@@ -127,6 +134,7 @@ impl Variant {
                 } else {
                     v.push(quote! {
                         #struct_name::#name(obj) => {
+                            #padding
                             std::ptr::write_unaligned(p.add(pos+4) as *mut u32, #hash);
                             ::flat_message::#serde_trait::write(obj,p,pos+#extra_size)
                         }
@@ -188,6 +196,7 @@ impl Variant {
 
         quote! {
             fn from_buffer(buf: &#implicit_lifetime [u8], pos: usize) -> Option<Self> {
+                let _guard = flat_message::limits::DepthGuard::enter()?;
                 if pos + 8 >= buf.len() {
                     return None;
                 }
@@ -326,6 +335,9 @@ impl TryFrom<syn::DeriveInput> for Variant {
                     }
                     let ty = fields.unnamed[0].ty.clone();
                     let ty_str = quote! {#ty}.to_string();
+                    if let Some(pointer) = DataType::unsupported_pointer(&ty_str) {
+                        return Err(format!("The type '{pointer}' (for variant '{name}') is not supported - a Box, Rc or Arc can only wrap a vector of values (use the list without the pointer instead) !"));
+                    }
                     let mut dt = DataType::new(ty, ty_str, None);
                     for attr in v.attrs.iter() {
                         dt.parse_attr(attr, &name_str)?;
@@ -350,6 +362,15 @@ impl TryFrom<syn::DeriveInput> for Variant {
                     if dt.ignore_field {
                         return Err(format!("Ignore fields are not allowed in a variant enum - for field {} in structure {} !", name, input.ident));
                     }
+                    if dt.encoding == Encoding::Adapter {
+                        return Err(format!("Field adapters (#[flat_message_item(with = \"...\")]) can not be used inside a variant enum - for field {} in structure {} !", name, input.ident));
+                    }
+                    if dt.field_type == FieldType::Nullable {
+                        return Err(format!("Lists with missing values (Vec<Option<T>>) can not be used inside a variant enum - for field {} in structure {} !", name, input.ident));
+                    }
+                    if dt.field_type == FieldType::NestedList {
+                        return Err(format!("Lists of lists (Vec<Vec<T>>) can not be used inside a variant enum - for field {} in structure {} !", name, input.ident));
+                    }
                     // if dt.option {
                     //     println!("Found option -> DataType: {} -> String Type: {} -> Type: {}",dt.data_format, dt.name, dt.ty.to_token_stream());
                     // }
@@ -0,0 +1,33 @@
+#![no_main]
+
+use flat_message::*;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct BasicTypes {
+    a: u8,
+    b: i16,
+    c: u32,
+    d: i64,
+    e: u128,
+    f: f32,
+    g: f64,
+    h: bool,
+    i: Option<u64>,
+    j: [u8; 7],
+    timestamp: Timestamp,
+    id: UniqueID,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let storage = Storage::from_buffer(data);
+    // deserialize_from is only called for buffers that passed the validation
+    if flat_message::validate(storage.as_slice()).is_err() {
+        return;
+    }
+    if let Ok(obj) = BasicTypes::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+});
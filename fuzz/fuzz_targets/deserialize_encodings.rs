@@ -0,0 +1,121 @@
+#![no_main]
+
+use flat_message::*;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+mod decimal {
+    use super::Decimal;
+    use flat_message::{Error, FieldReader, FieldWriter};
+
+    pub fn size(_: &Decimal) -> usize {
+        20
+    }
+    pub fn write(value: &Decimal, w: &mut FieldWriter) {
+        w.write_i128(value.mantissa);
+        w.write_u32(value.scale);
+    }
+    pub fn read(r: &mut FieldReader) -> Result<Decimal, Error> {
+        let mantissa = r.read_i128()?;
+        let scale = r.read_u32()?;
+        if scale <= 28 && r.is_empty() {
+            Ok(Decimal { mantissa, scale })
+        } else {
+            Err(Error::InvalidFieldValue)
+        }
+    }
+}
+
+mod label {
+    use flat_message::{Error, FieldReader, FieldWriter};
+
+    pub fn size(value: &String) -> usize {
+        value.len()
+    }
+    pub fn write(value: &String, w: &mut FieldWriter) {
+        w.write_str(value);
+    }
+    pub fn read(r: &mut FieldReader) -> Result<String, Error> {
+        r.read_rest_str().map(str::to_string)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tag<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a> FlatField<'a> for Tag<'a> {
+    const FORMAT: FieldFormat = FieldFormat::String;
+    fn size(&self) -> usize {
+        self.key.len() + 1 + self.value.len()
+    }
+    fn encode(&self, writer: &mut FieldWriter) {
+        writer.write_str(self.key);
+        writer.write_u8(b'=');
+        writer.write_str(self.value);
+    }
+    fn decode(reader: &mut FieldReader<'a>) -> Result<Self, Error> {
+        let (key, value) = reader.read_rest_str()?.split_once('=').ok_or(Error::InvalidFieldValue)?;
+        Ok(Tag { key, value })
+    }
+}
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct Encodings<'a> {
+    #[flat_message_item(encoding = "varint")]
+    id: u64,
+    #[flat_message_item(encoding = "varint")]
+    delta: i32,
+    #[flat_message_item(encoding = "varint")]
+    counters: Vec<u64>,
+    #[flat_message_item(encoding = "delta")]
+    timestamps: Vec<i64>,
+    #[flat_message_item(encoding = "delta+varint")]
+    ids: Vec<u32>,
+    samples: DeltaSlice<'a, u32>,
+    #[flat_message_item(with = "decimal", format = "bytes")]
+    price: Decimal,
+    #[flat_message_item(with = "decimal")]
+    discount: Option<Decimal>,
+    #[flat_message_item(with = "decimal")]
+    history: Vec<Decimal>,
+    #[flat_message_item(with = "label", format = "string")]
+    label: String,
+    category: Tag<'a>,
+    #[flat_message_item(format = "string")]
+    tags: Option<Vec<Tag<'a>>>,
+}
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false, string_table = true)]
+struct StringTable<'a> {
+    currency: String,
+    venue: &'a str,
+    tags: Vec<&'a str>,
+    names: Vec<String>,
+    note: Option<String>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let storage = Storage::from_buffer(data);
+    // deserialize_from is only called for buffers that passed the validation
+    if flat_message::validate(storage.as_slice()).is_err() {
+        return;
+    }
+    if let Ok(obj) = Encodings::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+    if let Ok(obj) = StringTable::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+});
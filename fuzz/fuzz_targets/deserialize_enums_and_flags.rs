@@ -0,0 +1,62 @@
+#![no_main]
+
+use flat_message::*;
+use libfuzzer_sys::fuzz_target;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+#[repr(u16)]
+enum Color {
+    Red = 1,
+    Green = 2,
+    Blue = 300,
+}
+
+#[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+#[repr(i8)]
+#[sealed]
+enum Direction {
+    Left = -1,
+    Right = 1,
+}
+
+#[derive(Copy, Clone, FlatMessageFlags, Eq, PartialEq, Debug)]
+#[repr(transparent)]
+#[flags(A, B, C)]
+struct Permissions(u32);
+
+impl Permissions {
+    add_flag!(A = 1);
+    add_flag!(B = 2);
+    add_flag!(C = 0x10000);
+}
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct EnumsAndFlags {
+    #[flat_message_item(repr = u16, kind = enum)]
+    color: Color,
+    #[flat_message_item(repr = u16, kind = enum)]
+    colors: Vec<Color>,
+    #[flat_message_item(repr = i8, kind = enum)]
+    direction: Option<Direction>,
+    #[flat_message_item(repr = u32, kind = flags)]
+    permissions: Permissions,
+    #[flat_message_item(repr = u32, kind = flags)]
+    all_permissions: Vec<Permissions>,
+    ip: IpAddr,
+    ip4: Ipv4Addr,
+    ip6: Option<Ipv6Addr>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let storage = Storage::from_buffer(data);
+    // deserialize_from is only called for buffers that passed the validation
+    if flat_message::validate(storage.as_slice()).is_err() {
+        return;
+    }
+    if let Ok(obj) = EnumsAndFlags::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+});
@@ -0,0 +1,40 @@
+#![no_main]
+
+use flat_message::*;
+use libfuzzer_sys::fuzz_target;
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct Lists<'a> {
+    sizes: Vec<usize>,
+    offsets: Vec<isize>,
+    counters: &'a [u64],
+    big: Vec<u128>,
+    nullable: Vec<Option<u32>>,
+    nullable_names: Vec<Option<String>>,
+    optional_nullable: Option<Vec<Option<i16>>>,
+    blobs: Vec<Vec<u8>>,
+    rows: Vec<&'a [u32]>,
+    groups: Vec<Vec<String>>,
+    bits: flat_message::BitVec,
+    bit_view: flat_message::BitSlice<'a>,
+    name: Cow<'a, str>,
+    values: Cow<'a, [u32]>,
+    wide: Cow<'a, [u64]>,
+    root: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let storage = Storage::from_buffer(data);
+    // deserialize_from is only called for buffers that passed the validation
+    if flat_message::validate(storage.as_slice()).is_err() {
+        return;
+    }
+    if let Ok(obj) = Lists::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+});
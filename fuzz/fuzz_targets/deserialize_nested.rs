@@ -0,0 +1,54 @@
+#![no_main]
+
+use flat_message::*;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(FlatMessageStruct)]
+struct Inner {
+    a: u8,
+    b: u64,
+    name: String,
+    values: Vec<u32>,
+}
+
+#[derive(FlatMessagePacked)]
+struct Point {
+    x: i32,
+    y: u32,
+    label: String,
+}
+
+#[derive(FlatMessageVariant)]
+enum Value {
+    Byte(u8),
+    Text(String),
+    List(Vec<u32>),
+    Empty,
+}
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct Nested {
+    #[flat_message_item(kind = struct, align = 4)]
+    inner: Inner,
+    #[flat_message_item(kind = struct, align = 4)]
+    optional_inner: Option<Inner>,
+    #[flat_message_item(kind = packed, align = 1)]
+    point: Point,
+    #[flat_message_item(kind = variant, align = 4)]
+    value: Value,
+    #[flat_message_item(kind = variant, align = 4)]
+    optional_value: Option<Value>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let storage = Storage::from_buffer(data);
+    // deserialize_from is only called for buffers that passed the validation
+    if flat_message::validate(storage.as_slice()).is_err() {
+        return;
+    }
+    if let Ok(obj) = Nested::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+});
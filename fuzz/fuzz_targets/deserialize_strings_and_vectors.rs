@@ -0,0 +1,32 @@
+#![no_main]
+
+use flat_message::*;
+use libfuzzer_sys::fuzz_target;
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct StringsAndVectors<'a> {
+    name: String,
+    slice: &'a str,
+    tags: Vec<String>,
+    small: Vec<u8>,
+    values: &'a [u32],
+    big: Vec<i128>,
+    floats: Vec<f64>,
+    flags: Vec<bool>,
+    buffers: Vec<[u8; 3]>,
+    text: Option<String>,
+    numbers: Option<Vec<u16>>,
+}
+
+fuzz_target!(|data: &[u8]| {
+    let storage = Storage::from_buffer(data);
+    // deserialize_from is only called for buffers that passed the validation
+    if flat_message::validate(storage.as_slice()).is_err() {
+        return;
+    }
+    if let Ok(obj) = StringsAndVectors::deserialize_from(&storage) {
+        let mut output = Storage::default();
+        let _ = obj.serialize_to(&mut output, Config::default());
+    }
+});
@@ -0,0 +1,164 @@
+use flat_message::*;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+struct Event {
+    code: u32,
+    source: String,
+    id: UniqueID,
+    timestamp: Timestamp,
+}
+
+fn event(code: u32) -> Event {
+    Event {
+        code,
+        source: format!("sensor-{}", code % 3),
+        id: UniqueID::with_value(1000 + code as u64),
+        // timestamps are not in the same order as the events
+        timestamp: Timestamp::with_value(50_000 - (code as u64 % 5) * 1000 + code as u64),
+    }
+}
+
+fn archive_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("flat_message_{}_{}.flma", name, std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+fn append_events(writer: &mut ArchiveWriter, codes: std::ops::Range<u32>) {
+    let mut storage = Storage::default();
+    for code in codes {
+        event(code).serialize_to(&mut storage, Config::default()).unwrap();
+        assert_eq!(writer.append(storage.as_slice()).unwrap(), code as usize);
+    }
+}
+
+fn codes(messages: impl Iterator<Item = impl AsRef<[u8]>>) -> Vec<u32> {
+    messages
+        .map(|m| Event::deserialize_from(&Storage::from_buffer(m.as_ref())).unwrap().code)
+        .collect()
+}
+
+#[test]
+fn check_write_and_read() {
+    let path = archive_path("write_and_read");
+    let mut writer = ArchiveWriter::create(&path).unwrap();
+    append_events(&mut writer, 0..20);
+    assert_eq!(writer.len(), 20);
+    writer.finish().unwrap();
+
+    let archive = unsafe { ArchiveReader::open(&path).unwrap() };
+    assert_eq!(archive.len(), 20);
+    assert_eq!(codes(archive.iter()), (0..20).collect::<Vec<_>>());
+    for (pos, message) in archive.iter().enumerate() {
+        // messages are aligned like a `Storage`
+        assert_eq!(message.as_ptr() as usize % 16, 0);
+        let entry = archive.entries()[pos];
+        assert_eq!(entry.size(), message.len() as u64);
+        assert_eq!(entry.unique_id(), Some(UniqueID::with_value(1000 + pos as u64)));
+        assert_eq!(entry.name(), Some(name!("Event")));
+    }
+    let e = Event::deserialize_from(&Storage::from_buffer(archive.get(7).unwrap())).unwrap();
+    assert_eq!(e, event(7));
+    assert!(archive.get(20).is_none());
+
+    let m = archive.find_by_unique_id(UniqueID::with_value(1013)).unwrap();
+    assert_eq!(StructureInformation::try_from(m).unwrap().unique_id(), Some(1013));
+    assert!(archive.find_by_unique_id(UniqueID::with_value(999)).is_none());
+
+    // timestamps: 50000 + code (codes 0, 5, 10, 15), 49000 + code (codes 1, 6, 11, 16), ...
+    let range = archive.range_by_timestamp(Timestamp::with_value(49_000)..Timestamp::with_value(50_000));
+    assert_eq!(codes(range), vec![1, 6, 11, 16]);
+    let range = archive.range_by_timestamp(Timestamp::with_value(50_000)..=Timestamp::with_value(50_010));
+    assert_eq!(codes(range), vec![0, 5, 10]);
+    let range = archive.range_by_timestamp(..Timestamp::with_value(46_005));
+    assert_eq!(codes(range), vec![4]);
+    assert_eq!(archive.range_by_timestamp(..).count(), 20);
+    assert_eq!(archive.range_by_timestamp(Timestamp::with_value(60_000)..).count(), 0);
+    drop(archive);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn check_append_to_existing_archive() {
+    let path = archive_path("append");
+    let mut writer = ArchiveWriter::create(&path).unwrap();
+    append_events(&mut writer, 0..5);
+    writer.finish().unwrap();
+
+    let mut writer = ArchiveWriter::open(&path).unwrap();
+    assert_eq!(writer.len(), 5);
+    append_events(&mut writer, 5..8);
+    writer.finish().unwrap();
+
+    let archive = unsafe { ArchiveReader::open(&path).unwrap() };
+    assert_eq!(codes(archive.iter()), (0..8).collect::<Vec<_>>());
+    drop(archive);
+
+    // `open` creates the archive if it does not exist
+    std::fs::remove_file(&path).unwrap();
+    let writer = ArchiveWriter::open(&path).unwrap();
+    assert!(writer.is_empty());
+    writer.finish().unwrap();
+    let archive = unsafe { ArchiveReader::open(&path).unwrap() };
+    assert!(archive.is_empty());
+    drop(archive);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn check_rebuild_index() {
+    let path = archive_path("rebuild");
+    let mut writer = ArchiveWriter::create(&path).unwrap();
+    append_events(&mut writer, 0..6);
+    writer.sync().unwrap();
+    // the writer is not finished (no index)
+    drop(writer);
+    // simulate a crash while the next record was written
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&[100, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4, b'F', b'L', b'M', b'R', 1, 2]).unwrap();
+    drop(file);
+
+    let archive = unsafe { ArchiveReader::open(&path).unwrap() };
+    assert_eq!(codes(archive.iter()), (0..6).collect::<Vec<_>>());
+    assert!(archive.find_by_unique_id(UniqueID::with_value(1005)).is_some());
+    drop(archive);
+
+    // the writer discards the incomplete record and continues after the last valid one
+    let mut writer = ArchiveWriter::open(&path).unwrap();
+    assert_eq!(writer.len(), 6);
+    append_events(&mut writer, 6..9);
+    writer.finish().unwrap();
+    let archive = unsafe { ArchiveReader::open(&path).unwrap() };
+    assert_eq!(codes(archive.iter()), (0..9).collect::<Vec<_>>());
+    drop(archive);
+
+    // a corrupted index is rebuilt from the records
+    let len = std::fs::metadata(&path).unwrap().len();
+    let file = OpenOptions::new().write(true).open(&path).unwrap();
+    file.set_len(len - 1).unwrap();
+    drop(file);
+    let archive = unsafe { ArchiveReader::open(&path).unwrap() };
+    assert_eq!(archive.len(), 9);
+    drop(archive);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn check_invalid_input() {
+    let path = archive_path("invalid");
+    let mut writer = ArchiveWriter::create(&path).unwrap();
+    let err = writer.append(&[1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(writer.is_empty());
+    writer.finish().unwrap();
+
+    std::fs::write(&path, b"this is not an archive").unwrap();
+    let err = unsafe { ArchiveReader::open(&path) }.err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    let err = ArchiveWriter::open(&path).err().unwrap();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    std::fs::remove_file(&path).unwrap();
+}
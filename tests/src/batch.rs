@@ -0,0 +1,177 @@
+use flat_message::*;
+use std::borrow::Cow;
+
+#[derive(Debug, PartialEq, FlatMessage)]
+#[flat_message_options(batch: true)]
+struct Trade {
+    price: f64,
+    quantity: u32,
+    buy: bool,
+    symbol: String,
+    discount: Option<u16>,
+    note: Option<String>,
+    id: UniqueID,
+    timestamp: Timestamp,
+}
+
+fn trades(count: u32) -> Vec<Trade> {
+    (0..count)
+        .map(|i| Trade {
+            price: i as f64 * 0.5,
+            quantity: i * 10,
+            buy: i % 2 == 0,
+            symbol: format!("SYM{}", i % 7),
+            discount: if i % 3 == 0 { Some(i as u16) } else { None },
+            note: if i % 5 == 0 { Some(format!("note {}", i)) } else { None },
+            id: UniqueID::with_value(100 + i as u64),
+            timestamp: Timestamp::with_value(1_000_000 + i as u64),
+        })
+        .collect()
+}
+
+#[test]
+fn check_batch_round_trip() {
+    let rows = trades(1000);
+    let mut storage = Storage::default();
+    Batch::serialize_to(&rows, &mut storage, Config::default()).unwrap();
+    assert_eq!(validate(storage.as_slice()), Ok(()));
+
+    let batch = Batch::<Trade>::deserialize_from(&storage).unwrap();
+    assert_eq!(batch.len(), 1000);
+    assert!(!batch.is_empty());
+    assert_eq!(batch.iter().collect::<Vec<_>>(), rows);
+    assert_eq!(batch.get(999).as_ref(), rows.last());
+    assert_eq!(batch.get(300).as_ref(), Some(&rows[300]));
+    assert!(batch.get(1000).is_none());
+
+    // one header for all the rows
+    let mut single = Storage::default();
+    let mut total = 0;
+    for row in rows.iter() {
+        row.serialize_to(&mut single, Config::default()).unwrap();
+        total += single.len();
+    }
+    assert!(storage.len() < total / 2);
+}
+
+#[test]
+fn check_batch_columns() {
+    let rows = trades(10);
+    let mut storage = Storage::default();
+    Batch::serialize_to(&rows, &mut storage, Config::default()).unwrap();
+    let batch = Batch::<Trade>::deserialize_from(&storage).unwrap();
+    let columns = batch.columns();
+
+    // zero-copy columns
+    let quantity: &[u32] = columns.quantity;
+    assert_eq!(quantity, &[0, 10, 20, 30, 40, 50, 60, 70, 80, 90]);
+    let price: &[f64] = columns.price;
+    assert_eq!(price[3], 1.5);
+    assert_eq!(columns.buy.iter().filter(|b| **b).count(), 5);
+    assert_eq!(columns.id[9], 109);
+    assert_eq!(columns.timestamp[0], 1_000_000);
+    // strings
+    assert_eq!(columns.symbol[8], "SYM1");
+    // validity bitmap for Option fields
+    assert_eq!(columns.discount.len(), 10);
+    assert_eq!(columns.discount.null_count(), 6);
+    assert_eq!(columns.discount.values(), &[0, 3, 6, 9]);
+    assert!(columns.discount.is_present(6));
+    assert!(!columns.discount.is_present(7));
+    assert_eq!(columns.note, vec![Some("note 0"), None, None, None, None, Some("note 5"), None, None, None, None]);
+}
+
+#[test]
+fn check_empty_batch() {
+    let mut storage = Storage::default();
+    Batch::<Trade>::serialize_to(&[], &mut storage, Config::default()).unwrap();
+    let batch = Batch::<Trade>::deserialize_from(&storage).unwrap();
+    assert!(batch.is_empty());
+    assert_eq!(batch.iter().count(), 0);
+    assert!(batch.get(0).is_none());
+}
+
+#[test]
+fn check_batch_with_borrowed_fields() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(batch: true, store_name: false)]
+    struct Sample<'a> {
+        name: &'a str,
+        label: Cow<'a, str>,
+        value: Box<i64>,
+        alias: Option<&'a str>,
+        scale: Option<Box<f32>>,
+        #[flat_message_item(ignore = true)]
+        cache: Option<u32>,
+    }
+    let rows = vec![
+        Sample {
+            name: "a",
+            label: Cow::Owned("first".to_string()),
+            value: Box::new(-1),
+            alias: Some("x"),
+            scale: None,
+            cache: None,
+        },
+        Sample {
+            name: "b",
+            label: Cow::Borrowed("second"),
+            value: Box::new(i64::MAX),
+            alias: None,
+            scale: Some(Box::new(2.5)),
+            cache: None,
+        },
+    ];
+    let mut storage = Storage::default();
+    Batch::serialize_to(&rows, &mut storage, Config::default()).unwrap();
+    let batch = Batch::<Sample>::deserialize_from(&storage).unwrap();
+    assert_eq!(batch.iter().collect::<Vec<_>>(), rows);
+    assert_eq!(batch.get(1).as_ref(), Some(&rows[1]));
+    assert_eq!(batch.columns().value, &[-1, i64::MAX]);
+}
+
+#[test]
+fn check_batch_validation() {
+    let rows = trades(4);
+    let mut storage = Storage::default();
+    Batch::serialize_to(&rows, &mut storage, Config::default()).unwrap();
+
+    // a batch is not a single message (and the other way around)
+    assert!(Trade::deserialize_from(&storage).is_err());
+    let mut single = Storage::default();
+    rows[0].serialize_to(&mut single, Config::default()).unwrap();
+    assert!(Batch::<Trade>::deserialize_from(&single).is_err());
+
+    // limits apply to the columns
+    let config = ConfigBuilder::new().max_list_len(3).build();
+    assert_eq!(
+        Batch::<Trade>::deserialize_from_with_config(&storage, config).err(),
+        Some(Error::MaxListLengthExceeded(3))
+    );
+
+    // all columns must have the same number of values
+    #[derive(FlatMessage)]
+    #[flat_message_options(batch: true)]
+    struct Point {
+        x: u32,
+        y: u32,
+    }
+    mod broken {
+        use flat_message::*;
+        // same name and fields as the columns of `Point`
+        #[derive(FlatMessage)]
+        pub struct PointColumns<'a> {
+            pub __rows: u64,
+            pub x: &'a [u32],
+            pub y: &'a [u32],
+        }
+    }
+    let mut storage = Storage::default();
+    let broken = broken::PointColumns { __rows: 2, x: &[1, 2], y: &[3] };
+    broken.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(Batch::<Point>::deserialize_from(&storage).err(), Some(Error::InvalidColumnLength((1, 2))));
+    let broken = broken::PointColumns { __rows: 2, x: &[1, 2], y: &[3, 4] };
+    broken.serialize_to(&mut storage, Config::default()).unwrap();
+    let batch = Batch::<Point>::deserialize_from(&storage).unwrap();
+    assert_eq!(batch.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>(), vec![(1, 3), (2, 4)]);
+}
@@ -0,0 +1,125 @@
+use flat_message::*;
+
+#[test]
+fn check_bit_vec() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        flags: flat_message::BitVec,
+        empty: flat_message::BitVec,
+        full_bytes: flat_message::BitVec,
+        optional: Option<flat_message::BitVec>,
+        missing: Option<flat_message::BitVec>,
+    }
+    let flags: BitVec = (0..20_000).map(|i| i % 3 == 0).collect();
+    let t = Test {
+        flags,
+        empty: BitVec::new(),
+        full_bytes: BitVec::repeat(true, 16),
+        optional: Some(BitVec::from(vec![false, true])),
+        missing: None,
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    // 20000 bits need 2500 bytes
+    assert!(s.len() < 2600);
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    let d = unsafe { Test::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_bit_slice_zero_copy() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Owned {
+        mask: flat_message::BitVec,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct View<'a> {
+        mask: flat_message::BitSlice<'a>,
+    }
+    let values = [true, false, false, true, true, false, true, false, true, true];
+    let mut s = Storage::default();
+    Owned { mask: BitVec::from(&values[..]) }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    let v = View::deserialize_from(&s).unwrap();
+    assert!(s.as_slice().as_ptr_range().contains(&v.mask.as_bytes().as_ptr()));
+    assert_eq!(v.mask.len(), 10);
+    assert_eq!(v.mask.as_bytes(), &[0b0101_1001, 0b11]);
+    assert_eq!(v.mask.get(0), Some(true));
+    assert_eq!(v.mask.get(2), Some(false));
+    assert_eq!(v.mask.get(10), None);
+    assert_eq!(v.mask.count_ones(), 6);
+    assert_eq!(v.mask.count_zeros(), 4);
+    assert_eq!(v.mask.to_vec(), values);
+    assert!(v.mask.iter().eq(values.iter().copied()));
+    // a view can be serialized as well
+    let mut s2 = Storage::default();
+    v.serialize_to(&mut s2, Config::default()).unwrap();
+    assert_eq!(s.as_slice(), s2.as_slice());
+}
+
+#[test]
+fn check_bit_vec_api() {
+    let mut b = BitVec::with_capacity(10);
+    assert!(b.is_empty());
+    b.extend([true, false, true]);
+    b.push(true);
+    b.set(1, true);
+    b.set(0, false);
+    assert_eq!(b.to_vec(), vec![false, true, true, true]);
+    assert_eq!(b.as_bytes(), &[0b1110]);
+    assert_eq!(BitVec::repeat(true, 10).as_bytes(), &[0xFF, 0b11]);
+    assert_eq!(BitVec::repeat(false, 9).count_zeros(), 9);
+    assert!(BitSlice::new(9, &[0, 1]).is_some());
+    // wrong size or bits set after the last element
+    assert!(BitSlice::new(9, &[0]).is_none());
+    assert!(BitSlice::new(9, &[0, 2]).is_none());
+}
+
+#[test]
+fn check_bits_not_compatible_with_bool_lists() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Packed {
+        value: flat_message::BitVec,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Bytes {
+        value: Vec<bool>,
+    }
+    let mut s = Storage::default();
+    Packed { value: BitVec::from(vec![true, true]) }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    assert!(Bytes::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Bytes { value: vec![true, true] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Packed::deserialize_from(&s).is_err());
+}
+
+#[test]
+fn check_bits_invalid_buffer() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        value: flat_message::BitVec,
+    }
+    let mut s = Storage::default();
+    Test { value: BitVec::from(vec![true, false, true]) }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    let mut buf = s.as_slice().to_vec();
+    // header (8 bytes) + number of bits (1 byte) + bits - set a bit after the last element
+    assert_eq!(buf[9], 0b101);
+    buf[9] |= 0b1000;
+    let s = Storage::from_buffer(&buf);
+    assert!(validate(s.as_slice()).is_err());
+    assert!(Test::deserialize_from(&s).is_err());
+}
@@ -0,0 +1,164 @@
+use flat_message::*;
+use std::borrow::Cow;
+
+#[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+#[repr(u8)]
+enum Level {
+    Low = 1,
+    High = 2,
+}
+
+#[derive(Debug, PartialEq, FlatMessage)]
+struct CowFields<'a> {
+    name: Cow<'a, str>,
+    data: Cow<'a, [u8]>,
+    values: Cow<'a, [u32]>,
+    big: std::borrow::Cow<'a, [u64]>,
+    floats: Cow<'a, [f32]>,
+    flags: Cow<'a, [bool]>,
+    #[flat_message_item(repr = u8, kind = enum)]
+    levels: Cow<'a, [Level]>,
+    comment: Option<Cow<'a, str>>,
+    missing: Option<Cow<'a, [i16]>>,
+}
+
+#[test]
+fn check_cow_borrowed_and_owned() {
+    let values = [1u32, 2, 3];
+    let borrowed = CowFields {
+        name: Cow::Borrowed("borrowed"),
+        data: Cow::Borrowed(&[1, 2, 3]),
+        values: Cow::Borrowed(&values),
+        big: Cow::Borrowed(&[u64::MAX]),
+        floats: Cow::Borrowed(&[1.5, -2.0]),
+        flags: Cow::Borrowed(&[true, false]),
+        levels: Cow::Borrowed(&[Level::High, Level::Low]),
+        comment: Some(Cow::Borrowed("text")),
+        missing: None,
+    };
+    let owned = CowFields {
+        name: Cow::Owned("owned".to_string()),
+        data: Cow::Owned(vec![4, 5]),
+        values: Cow::Owned(vec![10, 20, 30, 40]),
+        big: Cow::Owned(vec![]),
+        floats: Cow::Owned(vec![0.25]),
+        flags: Cow::Owned(vec![true]),
+        levels: Cow::Owned(vec![Level::Low]),
+        comment: None,
+        missing: Some(Cow::Owned(vec![-1, 1])),
+    };
+    for obj in [borrowed, owned] {
+        let mut storage = Storage::default();
+        obj.serialize_to(&mut storage, Config::default()).unwrap();
+        let ds = CowFields::deserialize_from(&storage).unwrap();
+        assert_eq!(ds, obj);
+        // deserialization never allocates for an aligned buffer
+        assert!(matches!(ds.name, Cow::Borrowed(_)));
+        assert!(matches!(ds.data, Cow::Borrowed(_)));
+        assert!(matches!(ds.values, Cow::Borrowed(_)));
+        assert!(matches!(ds.big, Cow::Borrowed(_)));
+        assert!(matches!(ds.levels, Cow::Borrowed(_)));
+        let range = storage.as_slice().as_ptr_range();
+        assert!(range.contains(&ds.name.as_ptr()));
+    }
+}
+
+#[test]
+fn check_cow_compatible_with_owned_and_borrowed_types() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct Owned {
+        name: String,
+        values: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        values: &'a [u32],
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct WithCow<'a> {
+        name: Cow<'a, str>,
+        values: Cow<'a, [u32]>,
+    }
+    let mut storage = Storage::default();
+    Owned {
+        name: "abc".to_string(),
+        values: vec![1, 2],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    let c = WithCow::deserialize_from(&storage).unwrap();
+    assert_eq!(c.name, "abc");
+    assert_eq!(c.values.as_ref(), &[1, 2]);
+
+    let mut cow_storage = Storage::default();
+    c.serialize_to(&mut cow_storage, Config::default()).unwrap();
+    assert_eq!(cow_storage.as_slice(), storage.as_slice());
+    let b = Borrowed::deserialize_from(&cow_storage).unwrap();
+    assert_eq!(b.name, "abc");
+    assert_eq!(b.values, &[1, 2]);
+}
+
+#[test]
+fn check_cow_default_value() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct Empty {
+        value: u8,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct WithDefault<'a> {
+        value: u8,
+        #[flat_message_item(mandatory = false, default = "unknown")]
+        name: Cow<'a, str>,
+        #[flat_message_item(mandatory = false)]
+        values: Cow<'a, [u16]>,
+    }
+    let mut storage = Storage::default();
+    Empty { value: 1 }.serialize_to(&mut storage, Config::default()).unwrap();
+    let d = WithDefault::deserialize_from(&storage).unwrap();
+    assert_eq!(d.name, "unknown");
+    assert!(d.values.is_empty());
+}
+
+#[test]
+fn check_cow_unaligned_buffer() {
+    let values: Cow<[u32]> = Cow::Owned(vec![7, 8, 9]);
+    let mut storage = Storage::default();
+    storage.resize_zero(SerDe::size(&values) + 1);
+    // write the field at position 1 (storage is 16 bytes aligned, so the values are not aligned)
+    let end = unsafe { SerDe::write(&values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    assert_eq!(end, storage.len());
+    let ds: Cow<[u32]> = SerDe::from_buffer(storage.as_slice(), 1).unwrap();
+    assert!(matches!(ds, Cow::Owned(_)));
+    assert_eq!(ds, values);
+    let ds: Option<Cow<[u32]>> = SerDe::from_buffer(&storage.as_slice()[..end - 1], 1);
+    assert!(ds.is_none());
+}
+
+#[test]
+fn check_cow_unaligned_copy_and_limits() {
+    let values: Cow<[u64]> = Cow::Owned(vec![1, 2, 3, 4]);
+    let mut storage = Storage::default();
+    // the field is followed by other data (that is not copied)
+    storage.resize_zero(SerDe::size(&values) + 1 + 4096);
+    unsafe { SerDe::write(&values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    let read = |config: Config| {
+        limits::with_limits(Some(config), || {
+            let ds: Cow<[u64]> = SerDe::from_buffer(storage.as_slice(), 1).ok_or(Error::FailToDeserialize(0))?;
+            Ok(ds)
+        })
+    };
+    let ds = read(Config::default()).unwrap();
+    assert!(matches!(ds, Cow::Owned(_)));
+    assert_eq!(ds, values);
+    // the copy of the field (8 + 32 bytes) and the vector (32 bytes) are counted
+    let config = ConfigBuilder::new().max_allocation(72).build();
+    assert_eq!(read(config).unwrap(), values);
+    let config = ConfigBuilder::new().max_allocation(71).build();
+    assert_eq!(read(config), Err(Error::MaxAllocationExceeded(71)));
+}
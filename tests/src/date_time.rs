@@ -0,0 +1,149 @@
+use crate::*;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, Utc};
+use flat_message::*;
+
+fn utc(secs: i64, nanos: u32) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, nanos).unwrap()
+}
+
+#[test]
+fn check_chrono_types() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        created: DateTime<Utc>,
+        updated: Option<chrono::DateTime<chrono::Utc>>,
+        local: NaiveDateTime,
+        zoned: DateTime<FixedOffset>,
+        birthday: NaiveDate,
+        history: Vec<DateTime<Utc>>,
+        days: Vec<NaiveDate>,
+        value: u8,
+    }
+    let offset = FixedOffset::east_opt(3 * 3600 + 1800).unwrap();
+    validate_correct_serde(Test {
+        created: utc(1_700_000_000, 123_456_789),
+        updated: Some(utc(-1_000_000_000, 999_999_999)),
+        local: NaiveDate::from_ymd_opt(2024, 2, 29).unwrap().and_hms_nano_opt(23, 59, 59, 1).unwrap(),
+        zoned: utc(1_600_000_000, 5).with_timezone(&offset),
+        birthday: NaiveDate::from_ymd_opt(1969, 12, 31).unwrap(),
+        history: vec![utc(0, 0), utc(1, 1), utc(253_402_300_799, 0)],
+        days: vec![NaiveDate::MIN, NaiveDate::from_ymd_opt(1970, 1, 1).unwrap(), NaiveDate::MAX],
+        value: 5,
+    });
+}
+
+#[test]
+fn check_chrono_leap_second() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        value: DateTime<Utc>,
+    }
+    validate_correct_serde(Test {
+        value: utc(1_483_228_799, 1_500_000_000),
+    });
+}
+
+#[test]
+fn check_time_types() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        zoned: time::OffsetDateTime,
+        local: Option<time::PrimitiveDateTime>,
+        day: time::Date,
+        days: Vec<time::Date>,
+        history: Vec<time::OffsetDateTime>,
+    }
+    let day = time::Date::from_calendar_date(2023, time::Month::March, 12).unwrap();
+    let offset = time::UtcOffset::from_hms(-5, -30, 0).unwrap();
+    let zoned = time::OffsetDateTime::from_unix_timestamp_nanos(1_678_600_000_123_456_789)
+        .unwrap()
+        .to_offset(offset);
+    validate_correct_serde(Test {
+        zoned,
+        local: Some(time::PrimitiveDateTime::new(
+            day,
+            time::Time::from_hms_nano(1, 2, 3, 4).unwrap(),
+        )),
+        day,
+        days: vec![time::Date::MIN, day, time::Date::MAX],
+        history: vec![time::OffsetDateTime::UNIX_EPOCH, zoned],
+    });
+}
+
+#[test]
+fn check_chrono_time_interoperability() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithChrono {
+        zoned: DateTime<FixedOffset>,
+        local: NaiveDateTime,
+        day: NaiveDate,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithTime {
+        zoned: time::OffsetDateTime,
+        local: time::PrimitiveDateTime,
+        day: time::Date,
+    }
+    let offset = FixedOffset::west_opt(7200).unwrap();
+    let chrono_value = WithChrono {
+        zoned: utc(1_234_567_890, 987_654_321).with_timezone(&offset),
+        local: NaiveDate::from_ymd_opt(2001, 9, 9).unwrap().and_hms_nano_opt(1, 46, 40, 500).unwrap(),
+        day: NaiveDate::from_ymd_opt(1900, 3, 1).unwrap(),
+    };
+    let mut storage = Storage::default();
+    chrono_value.serialize_to(&mut storage, Config::default()).unwrap();
+    let time_value = WithTime::deserialize_from(&storage).unwrap();
+    assert_eq!(time_value.zoned.unix_timestamp(), 1_234_567_890);
+    assert_eq!(time_value.zoned.nanosecond(), 987_654_321);
+    assert_eq!(time_value.zoned.offset().whole_seconds(), -7200);
+    assert_eq!(
+        time_value.local,
+        time::PrimitiveDateTime::new(
+            time::Date::from_calendar_date(2001, time::Month::September, 9).unwrap(),
+            time::Time::from_hms_nano(1, 46, 40, 500).unwrap()
+        )
+    );
+    assert_eq!(
+        time_value.day,
+        time::Date::from_calendar_date(1900, time::Month::March, 1).unwrap()
+    );
+    // and back
+    time_value.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(WithChrono::deserialize_from(&storage).unwrap(), chrono_value);
+}
+
+#[test]
+fn check_out_of_range_values() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Raw {
+        day: i32,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithDate {
+        day: NaiveDate,
+    }
+    let mut storage = Storage::default();
+    Raw { day: i32::MAX }
+        .serialize_to(&mut storage, Config::default())
+        .unwrap();
+    // different data formats (I32 vs Date)
+    assert!(WithDate::deserialize_from(&storage).is_err());
+
+    WithDate {
+        day: NaiveDate::from_ymd_opt(2000, 1, 1).unwrap(),
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    // the date is stored right after the header
+    let mut buffer = storage.as_slice().to_vec();
+    buffer[8..12].copy_from_slice(&i32::MAX.to_le_bytes());
+    let storage = Storage::from_buffer(&buffer);
+    assert!(WithDate::deserialize_from(&storage).is_err());
+}
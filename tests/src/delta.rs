@@ -0,0 +1,154 @@
+use flat_message::*;
+
+#[test]
+fn check_delta_vectors() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        #[flat_message_item(encoding = "delta")]
+        timestamps: Vec<u64>,
+        #[flat_message_item(encoding = "delta+varint")]
+        ids: Vec<u32>,
+        #[flat_message_item(encoding = "delta")]
+        unsorted: Vec<i64>,
+        #[flat_message_item(encoding = "delta")]
+        extremes: Vec<u64>,
+        #[flat_message_item(encoding = "delta")]
+        small: Vec<i8>,
+        #[flat_message_item(encoding = "delta")]
+        empty: Vec<u16>,
+        #[flat_message_item(encoding = "delta")]
+        optional: Option<Vec<usize>>,
+        #[flat_message_item(encoding = "delta")]
+        missing: Option<Vec<isize>>,
+    }
+    let t = Test {
+        timestamps: (0..1000).map(|i| 1_700_000_000_000 + i * 250).collect(),
+        ids: vec![10, 11, 12, 15, 100],
+        unsorted: vec![5, -3, 1_000_000, i64::MIN, i64::MAX, 0],
+        extremes: vec![u64::MAX, 0, u64::MAX],
+        small: vec![i8::MIN, i8::MAX, -1],
+        empty: Vec::new(),
+        optional: Some(vec![3, 2, 1]),
+        missing: None,
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    let d = unsafe { Test::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_delta_size() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Compact {
+        #[flat_message_item(encoding = "delta")]
+        timestamps: Vec<u64>,
+    }
+    let timestamps: Vec<u64> = (0..1000).map(|i| 1_700_000_000_000 + i * 100).collect();
+    let mut s = Storage::default();
+    Compact { timestamps }.serialize_to(&mut s, Config::default()).unwrap();
+    // the first value needs 6 bytes, every difference (100) needs 2 bytes (zigzag encoded)
+    assert!(s.len() < 2100);
+}
+
+#[test]
+fn check_delta_slice() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Owned {
+        #[flat_message_item(encoding = "delta")]
+        values: Vec<i32>,
+        id: u32,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct View<'a> {
+        values: DeltaSlice<'a, i32>,
+        id: u32,
+    }
+    let values = vec![100, 90, 95, -20, i32::MAX, i32::MIN];
+    let mut s = Storage::default();
+    Owned { values: values.clone(), id: 1 }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    let v = View::deserialize_from(&s).unwrap();
+    assert_eq!(v.values.len(), 6);
+    let mut it = v.values.iter();
+    assert_eq!(it.len(), 6);
+    assert_eq!(it.next(), Some(100));
+    assert_eq!(it.next(), Some(90));
+    assert_eq!(it.len(), 4);
+    assert_eq!(v.values.to_vec(), values);
+    assert_eq!(v.values.into_iter().max(), Some(i32::MAX));
+    let v = unsafe { View::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(v.values.to_vec(), values);
+    // a view can be serialized as well
+    let mut s2 = Storage::default();
+    v.serialize_to(&mut s2, Config::default()).unwrap();
+    assert_eq!(s.as_slice(), s2.as_slice());
+    assert_eq!(Owned::deserialize_from(&s2).unwrap().values, values);
+}
+
+#[test]
+fn check_delta_different_types() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Wide {
+        #[flat_message_item(encoding = "delta")]
+        values: Vec<u64>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Narrow {
+        #[flat_message_item(encoding = "delta")]
+        values: Vec<i16>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct NarrowView<'a> {
+        values: DeltaSlice<'a, u8>,
+    }
+    let mut s = Storage::default();
+    Wide { values: vec![10, 200, 30] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(Narrow::deserialize_from(&s).unwrap().values, vec![10, 200, 30]);
+    assert_eq!(NarrowView::deserialize_from(&s).unwrap().values.to_vec(), vec![10, 200, 30]);
+    let mut s = Storage::default();
+    Wide { values: vec![10, 300] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(NarrowView::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Narrow { values: vec![1, -1] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Wide::deserialize_from(&s).is_err());
+}
+
+#[test]
+fn check_delta_not_compatible_with_other_encodings() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Delta {
+        #[flat_message_item(encoding = "delta")]
+        values: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Varint {
+        #[flat_message_item(encoding = "varint")]
+        values: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Fixed {
+        values: Vec<u32>,
+    }
+    let mut s = Storage::default();
+    Delta { values: vec![1, 2] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Varint::deserialize_from(&s).is_err());
+    assert!(Fixed::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Varint { values: vec![1, 2] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Delta::deserialize_from(&s).is_err());
+}
@@ -0,0 +1,322 @@
+use crate::*;
+use flat_message::*;
+
+// types from "another crate" (they do not implement the serialization traits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Decimal {
+    mantissa: i128,
+    scale: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+}
+
+mod decimal {
+    use super::Decimal;
+    use flat_message::{Error, FieldReader, FieldWriter};
+
+    pub fn size(_: &Decimal) -> usize {
+        20
+    }
+    pub fn write(value: &Decimal, w: &mut FieldWriter) {
+        w.write_i128(value.mantissa);
+        w.write_u32(value.scale);
+    }
+    pub fn read(r: &mut FieldReader) -> Result<Decimal, Error> {
+        let mantissa = r.read_i128()?;
+        let scale = r.read_u32()?;
+        if scale <= 28 && r.is_empty() {
+            Ok(Decimal { mantissa, scale })
+        } else {
+            Err(Error::InvalidFieldValue)
+        }
+    }
+}
+
+pub mod adapters {
+    pub mod version {
+        use crate::field_adapters::Version;
+        use flat_message::{Error, FieldReader, FieldWriter};
+
+        fn text(value: &Version) -> String {
+            format!("{}.{}.{}", value.major, value.minor, value.patch)
+        }
+        pub fn size(value: &Version) -> usize {
+            text(value).len()
+        }
+        pub fn write(value: &Version, w: &mut FieldWriter) {
+            w.write_str(&text(value));
+        }
+        pub fn read(r: &mut FieldReader) -> Result<Version, Error> {
+            let mut parts = r.read_rest_str()?.split('.').map(|p| p.parse::<u64>().map_err(|_| Error::InvalidFieldValue));
+            let mut next = || parts.next().unwrap_or(Err(Error::InvalidFieldValue));
+            let version = Version {
+                major: next()?,
+                minor: next()?,
+                patch: next()?,
+            };
+            match parts.next() {
+                None => Ok(version),
+                Some(_) => Err(Error::InvalidFieldValue),
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Package {
+    #[flat_message_item(with = "decimal", format = "bytes")]
+    price: Decimal,
+    #[flat_message_item(with = "decimal")]
+    discount: Option<Decimal>,
+    #[flat_message_item(with = "crate::field_adapters::adapters::version", format = "string")]
+    version: Version,
+    #[flat_message_item(with = "adapters::version", format = "string", validate = fallback, default = "Version::default()")]
+    min_version: Version,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct RawPackage<'a> {
+    price: &'a [u8],
+    discount: Option<Vec<u8>>,
+    version: String,
+    min_version: &'a str,
+}
+
+fn package() -> Package {
+    Package {
+        price: Decimal { mantissa: 12345, scale: 2 },
+        discount: Some(Decimal { mantissa: -5, scale: 1 }),
+        version: Version { major: 1, minor: 22, patch: 333 },
+        min_version: Version { major: 0, minor: 9, patch: 0 },
+    }
+}
+
+#[test]
+fn check_field_adapters() {
+    validate_correct_serde(package());
+    validate_correct_serde(Package {
+        discount: None,
+        ..package()
+    });
+}
+
+#[test]
+fn check_field_adapters_format() {
+    let mut storage = Storage::default();
+    package().serialize_to(&mut storage, Config::default()).unwrap();
+    // the values are stored as buffers and strings
+    let raw = RawPackage::deserialize_from(&storage).unwrap();
+    assert_eq!(raw.price.len(), 20);
+    assert_eq!(&raw.price[..16], &12345i128.to_le_bytes());
+    assert_eq!(raw.discount.as_ref().map(|d| d.len()), Some(20));
+    assert_eq!(raw.version, "1.22.333");
+    assert_eq!(raw.min_version, "0.9.0");
+
+    let raw = RawPackage {
+        price: &[0; 20],
+        discount: None,
+        version: "2.0.1".to_string(),
+        min_version: "not a version",
+    };
+    raw.serialize_to(&mut storage, Config::default()).unwrap();
+    let p = Package::deserialize_from(&storage).unwrap();
+    assert_eq!(p.price, Decimal::default());
+    assert_eq!(p.discount, None);
+    assert_eq!(p.version, Version { major: 2, minor: 0, patch: 1 });
+    // the field uses `validate = fallback`
+    assert_eq!(p.min_version, Version::default());
+
+    // `read` returns None => the field is invalid
+    let raw = RawPackage {
+        price: &[0; 21],
+        ..raw
+    };
+    raw.serialize_to(&mut storage, Config::default()).unwrap();
+    assert!(Package::deserialize_from(&storage).is_err());
+}
+
+#[test]
+fn check_field_adapter_in_nested_struct() {
+    #[derive(Debug, PartialEq, Eq, FlatMessageStruct)]
+    struct Price {
+        #[flat_message_item(with = "decimal")]
+        value: Decimal,
+        currency: String,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    struct Order {
+        #[flat_message_item(kind = struct, align = 4)]
+        price: Price,
+        quantity: u32,
+    }
+    validate_correct_serde(Order {
+        price: Price {
+            value: Decimal { mantissa: 999, scale: 3 },
+            currency: "EUR".to_string(),
+        },
+        quantity: 10,
+    });
+}
+
+mod wrong_size {
+    use super::Decimal;
+    use flat_message::{Error, FieldReader, FieldWriter};
+
+    pub fn size(_: &Decimal) -> usize {
+        4
+    }
+    pub fn write(value: &Decimal, w: &mut FieldWriter) {
+        w.write_i128(value.mantissa);
+    }
+    pub fn read(_: &mut FieldReader) -> Result<Decimal, Error> {
+        Err(Error::InvalidFieldValue)
+    }
+}
+
+#[test]
+#[should_panic(expected = "wrote more than the 4 bytes")]
+fn check_field_adapter_writes_only_its_size() {
+    #[derive(FlatMessage)]
+    struct Invalid {
+        #[flat_message_item(with = "wrong_size")]
+        value: Decimal,
+    }
+    let mut storage = Storage::default();
+    let _ = Invalid { value: Decimal::default() }.serialize_to(&mut storage, Config::default());
+}
+
+// `size` returns a different value every time it is called (1, 100_000, 1, ...)
+mod unstable {
+    use flat_message::{Error, FieldReader, FieldWriter};
+    use std::cell::Cell;
+
+    thread_local! {
+        static CALLS: Cell<usize> = const { Cell::new(0) };
+    }
+    pub fn size(_: &u8) -> usize {
+        let calls = CALLS.with(|c| c.replace(c.get() + 1));
+        if calls % 2 == 0 { 1 } else { 100_000 }
+    }
+    pub fn write(value: &u8, w: &mut FieldWriter) {
+        while w.remaining() > 0 {
+            w.write_u8(*value);
+        }
+    }
+    pub fn read(r: &mut FieldReader) -> Result<u8, Error> {
+        r.read_rest().first().copied().ok_or(Error::InvalidFieldValue)
+    }
+}
+
+#[test]
+fn check_field_adapter_with_unstable_size() {
+    #[derive(Debug, PartialEq, Eq, FlatMessageStruct)]
+    struct Inner {
+        #[flat_message_item(with = "unstable")]
+        value: u8,
+        name: String,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Unstable {
+        #[flat_message_item(with = "unstable")]
+        value: u8,
+        #[flat_message_item(with = "unstable")]
+        values: Vec<u8>,
+        #[flat_message_item(kind = struct, align = 4)]
+        inner: Inner,
+        data: Vec<u8>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct RawUnstable<'a> {
+        value: &'a [u8],
+        values: Vec<&'a [u8]>,
+    }
+    // only the first result of `size` is used for a value (the writer never goes past the reserved space)
+    for _ in 0..2 {
+        let u = Unstable {
+            value: 1,
+            values: vec![2, 3, 4],
+            inner: Inner { value: 5, name: "inner".to_string() },
+            data: vec![6; 100],
+        };
+        let mut storage = Storage::default();
+        u.serialize_to(&mut storage, Config::default()).unwrap();
+        assert_eq!(Unstable::deserialize_from(&storage).unwrap(), u);
+        let raw = RawUnstable::deserialize_from(&storage).unwrap();
+        assert!(raw.values.iter().chain([&raw.value]).all(|v| v.len() == 1 || v.len() == 100_000));
+    }
+}
+
+#[test]
+fn check_field_reader_and_writer() {
+    mod numbers {
+        use flat_message::{Error, FieldReader, FieldWriter};
+        pub fn size(_: &(u8, i16, f64, [u8; 3])) -> usize {
+            1 + 2 + 8 + 3
+        }
+        pub fn write(value: &(u8, i16, f64, [u8; 3]), w: &mut FieldWriter) {
+            assert_eq!(w.remaining(), 14);
+            w.write_u8(value.0);
+            w.write_i16(value.1);
+            w.write_f64(value.2);
+            w.write_bytes(&value.3);
+            assert_eq!(w.position(), 14);
+        }
+        pub fn read(r: &mut FieldReader) -> Result<(u8, i16, f64, [u8; 3]), Error> {
+            let value = (r.read_u8()?, r.read_i16()?, r.read_f64()?, r.read_array()?);
+            assert_eq!(r.read_u8(), Err(Error::InvalidFieldValue));
+            assert_eq!(r.read_bytes(1), Err(Error::InvalidFieldValue));
+            assert_eq!(r.read_rest(), &[] as &[u8]);
+            Ok(value)
+        }
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    struct Numbers {
+        #[flat_message_item(with = "numbers")]
+        value: (u8, i16, f64, [u8; 3]),
+    }
+    let n = Numbers { value: (1, -2, 3.5, [4, 5, 6]) };
+    let mut storage = Storage::default();
+    n.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(Numbers::deserialize_from(&storage).unwrap(), n);
+}
+
+#[test]
+fn check_field_adapter_list() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Prices {
+        #[flat_message_item(with = "decimal")]
+        values: Vec<Decimal>,
+        #[flat_message_item(with = "adapters::version", format = "string")]
+        versions: Option<Vec<Version>>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct RawPrices<'a> {
+        values: Vec<Vec<u8>>,
+        versions: Option<Vec<&'a str>>,
+    }
+    let prices = Prices {
+        values: vec![Decimal { mantissa: 1, scale: 0 }, Decimal { mantissa: -25, scale: 2 }],
+        versions: Some(vec![Version { major: 1, minor: 2, patch: 3 }]),
+    };
+    validate_correct_serde(prices);
+    let prices = Prices {
+        values: vec![Decimal::default()],
+        versions: Some(vec![Version { major: 1, minor: 2, patch: 3 }]),
+    };
+    let mut storage = Storage::default();
+    prices.serialize_to(&mut storage, Config::default()).unwrap();
+    let raw = RawPrices::deserialize_from(&storage).unwrap();
+    assert_eq!(raw.values, vec![vec![0; 20]]);
+    assert_eq!(raw.versions, Some(vec!["1.2.3"]));
+}
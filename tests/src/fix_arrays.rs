@@ -241,4 +241,146 @@ fn check_simple_array_ref_repr() {
         26, 70, 74, 148, // hash for v1 (26 = type for FixArray)
         8 // offset of v1
     ]);
-}
\ No newline at end of file
+}
+#[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+#[repr(u16)]
+enum Axis {
+    X = 1,
+    Y = 2,
+    Z = 3,
+}
+
+#[test]
+fn check_generic_arrays() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        position: [f32; 3],
+        ids: [u64; 4],
+        small: [i16; 2],
+        flags: [bool; 3],
+        letters: [char; 2],
+        empty: [u32; 0],
+        #[flat_message_item(repr = u16, kind = enum)]
+        axes: [Axis; 3],
+        matrix: Option<[f64; 4]>,
+        missing: Option<[u128; 2]>,
+        boxed: Box<[u32; 2]>,
+    }
+    let t = Test {
+        position: [1.0, -2.5, 3.25],
+        ids: [1, 2, u64::MAX, 4],
+        small: [-1, 1],
+        flags: [true, false, true],
+        letters: ['a', 'ț'],
+        empty: [],
+        axes: [Axis::Z, Axis::X, Axis::Y],
+        matrix: Some([1.0, 0.0, 0.0, 1.0]),
+        missing: None,
+        boxed: Box::new([5, 6]),
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_generic_array_refs() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test<'a> {
+        position: &'a [f32; 3],
+        ids: &'a [u64; 2],
+    }
+    let t = Test {
+        position: &[1.0, 2.0, 3.0],
+        ids: &[10, 20],
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    // zero-copy
+    assert!(s.as_slice().as_ptr_range().contains(&(d.ids.as_ptr() as *const u8)));
+}
+
+#[test]
+fn check_generic_arrays_compatible_with_vec() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithArray {
+        values: [u32; 3],
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithVec {
+        values: Vec<u32>,
+    }
+    let mut s = Storage::default();
+    WithArray { values: [1, 2, 3] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(WithVec::deserialize_from(&s).unwrap().values, vec![1, 2, 3]);
+
+    // a different number of elements can not be deserialized into the array
+    WithVec { values: vec![1, 2] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(WithArray::deserialize_from(&s).is_err());
+    WithVec { values: vec![4, 5, 6] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(WithArray::deserialize_from(&s).unwrap().values, [4, 5, 6]);
+}
+
+#[test]
+fn check_generic_array_unaligned() {
+    let values: [u64; 2] = [7, 8];
+    let mut storage = Storage::default();
+    storage.resize_zero(SerDe::size(&values) + 1);
+    let end = unsafe { SerDe::write(&values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    assert_eq!(end, storage.len());
+    let ds: [u64; 2] = SerDe::from_buffer(storage.as_slice(), 1).unwrap();
+    assert_eq!(ds, values);
+    // a reference can not be created for unaligned data
+    let ds: Option<&[u64; 2]> = SerDe::from_buffer(storage.as_slice(), 1);
+    assert!(ds.is_none());
+}
+
+#[test]
+fn check_generic_array_unaligned_copy_and_limits() {
+    let values: [u64; 2] = [7, 8];
+    let mut storage = Storage::default();
+    // the field is followed by other data (that is not copied)
+    storage.resize_zero(SerDe::size(&values) + 1 + 4096);
+    unsafe { SerDe::write(&values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    let read = |config: Config| {
+        flat_message::limits::with_limits(Some(config), || {
+            let ds: [u64; 2] = SerDe::from_buffer(storage.as_slice(), 1).ok_or(Error::FailToDeserialize(0))?;
+            Ok(ds)
+        })
+    };
+    // only the copy of the field (8 + 16 bytes) is counted
+    let config = ConfigBuilder::new().max_allocation(24).build();
+    assert_eq!(read(config).unwrap(), values);
+    let config = ConfigBuilder::new().max_allocation(23).build();
+    assert_eq!(read(config), Err(Error::MaxAllocationExceeded(23)));
+    // a different number of elements is rejected
+    let ds: Option<[u64; 3]> = SerDe::from_buffer(storage.as_slice(), 1);
+    assert!(ds.is_none());
+}
+
+#[test]
+fn check_generic_array_unaligned_unchecked() {
+    let values: [u32; 3] = [1, u32::MAX, 3];
+    let mut storage = Storage::default();
+    storage.resize_zero(SerDe::size(&values) + 1);
+    unsafe { SerDe::write(&values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    // like the aligned case, the elements are read without checking the limits (this can not fail)
+    let config = ConfigBuilder::new().max_list_len(1).build();
+    let ds = flat_message::limits::with_limits(Some(config), || {
+        let ds: [u32; 3] = unsafe { SerDe::from_buffer_unchecked(storage.as_slice(), 1) };
+        Ok(ds)
+    });
+    assert_eq!(ds, Ok(values));
+    let values: [i16; 2] = [-1, 2];
+    storage.resize_zero(SerDe::size(&values) + 1);
+    unsafe { SerDe::write(&values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    let ds: [i16; 2] = unsafe { SerDe::from_buffer_unchecked(storage.as_slice(), 1) };
+    assert_eq!(ds, values);
+}
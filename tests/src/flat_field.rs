@@ -0,0 +1,300 @@
+use flat_message::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct Money {
+    cents: i64,
+    currency: [u8; 3],
+}
+
+impl FlatField<'_> for Money {
+    fn size(&self) -> usize {
+        11
+    }
+    fn encode(&self, writer: &mut FieldWriter) {
+        writer.write_i64(self.cents);
+        writer.write_bytes(&self.currency);
+    }
+    fn decode(reader: &mut FieldReader) -> Result<Self, Error> {
+        let money = Money {
+            cents: reader.read_i64()?,
+            currency: reader.read_array()?,
+        };
+        if reader.is_empty() && money.currency.iter().all(u8::is_ascii_uppercase) {
+            Ok(money)
+        } else {
+            Err(Error::InvalidFieldValue)
+        }
+    }
+}
+
+/// A tag ("key=value") that borrows from the buffer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Tag<'a> {
+    key: &'a str,
+    value: &'a str,
+}
+
+impl<'a> FlatField<'a> for Tag<'a> {
+    const FORMAT: FieldFormat = FieldFormat::String;
+    fn size(&self) -> usize {
+        self.key.len() + 1 + self.value.len()
+    }
+    fn encode(&self, writer: &mut FieldWriter) {
+        writer.write_str(self.key);
+        writer.write_u8(b'=');
+        writer.write_str(self.value);
+    }
+    fn decode(reader: &mut FieldReader<'a>) -> Result<Self, Error> {
+        let (key, value) = reader.read_rest_str()?.split_once('=').ok_or(Error::InvalidFieldValue)?;
+        Ok(Tag { key, value })
+    }
+}
+
+fn eur(cents: i64) -> Money {
+    Money { cents, currency: *b"EUR" }
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Invoice<'a> {
+    total: Money,
+    discount: Option<Money>,
+    items: Vec<Money>,
+    category: Tag<'a>,
+    tags: Option<Vec<Tag<'a>>>,
+}
+
+/// The same fields as `Invoice`, with the format given by the attribute.
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct InvoiceWithFormat<'a> {
+    #[flat_message_item(format = "bytes")]
+    total: Money,
+    #[flat_message_item(format = "bytes")]
+    discount: Option<Money>,
+    #[flat_message_item(format = "bytes")]
+    items: Vec<Money>,
+    #[flat_message_item(format = "string")]
+    category: Tag<'a>,
+    #[flat_message_item(format = "string")]
+    tags: Option<Vec<Tag<'a>>>,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct RawInvoice<'a> {
+    total: &'a [u8],
+    discount: Option<Vec<u8>>,
+    items: Vec<&'a [u8]>,
+    category: String,
+    tags: Option<Vec<&'a str>>,
+}
+
+fn invoice<'a>() -> Invoice<'a> {
+    Invoice {
+        total: eur(1500),
+        discount: Some(eur(-100)),
+        items: vec![eur(1000), eur(600)],
+        category: Tag { key: "type", value: "service" },
+        tags: Some(vec![Tag { key: "a", value: "1" }, Tag { key: "b", value: "" }]),
+    }
+}
+
+fn round_trip(invoice: Invoice) {
+    let mut storage = Storage::default();
+    invoice.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(Invoice::deserialize_from(&storage).unwrap(), invoice);
+    assert_eq!(unsafe { Invoice::deserialize_from_unchecked(&storage).unwrap() }, invoice);
+}
+
+#[test]
+fn check_flat_field() {
+    round_trip(invoice());
+    round_trip(Invoice {
+        discount: None,
+        items: Vec::new(),
+        tags: None,
+        ..invoice()
+    });
+    round_trip(Invoice {
+        tags: Some(Vec::new()),
+        ..invoice()
+    });
+}
+
+#[test]
+fn check_flat_field_format_from_type() {
+    // the format attribute only repeats FlatField::FORMAT (the messages are identical)
+    let mut storage = Storage::default();
+    invoice().serialize_to(&mut storage, Config::default()).unwrap();
+    let i = InvoiceWithFormat::deserialize_from(&storage).unwrap();
+    assert_eq!(i.category, Tag { key: "type", value: "service" });
+    assert_eq!(i.items, vec![eur(1000), eur(600)]);
+    let mut with_format = Storage::default();
+    i.serialize_to(&mut with_format, Config::default()).unwrap();
+    assert_eq!(with_format.as_slice(), storage.as_slice());
+}
+
+#[test]
+fn check_flat_field_zero_copy() {
+    let mut storage = Storage::default();
+    invoice().serialize_to(&mut storage, Config::default()).unwrap();
+    let i = Invoice::deserialize_from(&storage).unwrap();
+    assert_eq!(i, invoice());
+    let range = storage.as_slice().as_ptr_range();
+    assert!(range.contains(&i.category.key.as_ptr()));
+    assert!(i.tags.unwrap().iter().all(|t| range.contains(&t.key.as_ptr())));
+}
+
+#[test]
+fn check_flat_field_format() {
+    let mut storage = Storage::default();
+    invoice().serialize_to(&mut storage, Config::default()).unwrap();
+    // values are stored as buffers / strings and lists as Vec<&[u8]> / Vec<&str>
+    let raw = RawInvoice::deserialize_from(&storage).unwrap();
+    assert_eq!(raw.total.len(), 11);
+    assert_eq!(&raw.total[8..], b"EUR");
+    assert_eq!(raw.discount.as_ref().map(|d| d.len()), Some(11));
+    assert_eq!(raw.items.len(), 2);
+    assert!(raw.items.iter().all(|item| item.len() == 11));
+    assert_eq!(raw.category, "type=service");
+    assert_eq!(raw.tags, Some(vec!["a=1", "b="]));
+
+    let mut item = [0u8; 11];
+    item[..8].copy_from_slice(&250i64.to_le_bytes());
+    item[8..].copy_from_slice(b"USD");
+    let raw = RawInvoice {
+        total: &item,
+        discount: None,
+        items: vec![&item, &item],
+        category: "k=v".to_string(),
+        tags: Some(vec!["x=y"]),
+    };
+    raw.serialize_to(&mut storage, Config::default()).unwrap();
+    let i = Invoice::deserialize_from(&storage).unwrap();
+    let usd = Money { cents: 250, currency: *b"USD" };
+    assert_eq!(i.total, usd);
+    assert_eq!(i.items, vec![usd, usd]);
+    assert_eq!(i.category, Tag { key: "k", value: "v" });
+    assert_eq!(i.tags, Some(vec![Tag { key: "x", value: "y" }]));
+
+    // `decode` returns an error for one of the elements
+    let raw = RawInvoice {
+        items: vec![&item, b"lowercase.."],
+        ..raw
+    };
+    raw.serialize_to(&mut storage, Config::default()).unwrap();
+    assert!(Invoice::deserialize_from(&storage).is_err());
+    let raw = RawInvoice {
+        items: Vec::new(),
+        tags: Some(vec!["x=y", "no separator"]),
+        ..raw
+    };
+    raw.serialize_to(&mut storage, Config::default()).unwrap();
+    // Option fields use `validate = fallback` by default
+    assert_eq!(Invoice::deserialize_from(&storage).unwrap().tags, None);
+}
+
+#[test]
+fn check_flat_field_fallback() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Price {
+        #[flat_message_item(validate = fallback, default = "Money::default()")]
+        value: Money,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct RawPrice {
+        value: Vec<u8>,
+    }
+    let mut storage = Storage::default();
+    RawPrice { value: vec![1, 2, 3] }.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(Price::deserialize_from(&storage).unwrap().value, Money::default());
+}
+
+#[test]
+fn check_flat_field_list_limits() {
+    let mut storage = Storage::default();
+    invoice().serialize_to(&mut storage, Config::default()).unwrap();
+    let config = ConfigBuilder::new().max_list_len(1).build();
+    assert!(Invoice::deserialize_from_with_config(&storage, config).is_err());
+}
+
+/// A value whose `encode` writes `written` bytes while `size` returns `size` (and, if `unstable`
+/// is set, 100_000 for every call after the first one).
+struct Mismatch {
+    size: usize,
+    written: usize,
+    unstable: bool,
+    calls: std::cell::Cell<usize>,
+}
+
+impl Mismatch {
+    fn new(size: usize, written: usize, unstable: bool) -> Self {
+        Mismatch { size, written, unstable, calls: Default::default() }
+    }
+}
+
+impl FlatField<'_> for Mismatch {
+    fn size(&self) -> usize {
+        let calls = self.calls.replace(self.calls.get() + 1);
+        if self.unstable && calls > 0 { 100_000 } else { self.size }
+    }
+    fn encode(&self, writer: &mut FieldWriter) {
+        writer.write_bytes(&vec![0; self.written]);
+    }
+    fn decode(reader: &mut FieldReader) -> Result<Self, Error> {
+        let len = reader.read_rest().len();
+        Ok(Mismatch::new(len, len, false))
+    }
+}
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Mismatched {
+    #[flat_message_item(format = "bytes")]
+    value: Mismatch,
+    #[flat_message_item(format = "bytes")]
+    values: Vec<Mismatch>,
+    data: Vec<u8>,
+}
+
+fn serialize_mismatched(size: usize, written: usize, unstable: bool) -> Storage {
+    let m = Mismatched {
+        value: Mismatch::new(size, written, unstable),
+        values: vec![Mismatch::new(size, written, unstable), Mismatch::new(size, written, unstable)],
+        data: vec![1; 64],
+    };
+    let mut storage = Storage::default();
+    m.serialize_to(&mut storage, Config::default()).unwrap();
+    storage
+}
+
+#[test]
+fn check_flat_field_with_unstable_size() {
+    // only the first result of `size` is used (the value can not grow after the buffer was allocated)
+    let storage = serialize_mismatched(3, 3, true);
+    let m = Mismatched::deserialize_from(&storage).unwrap();
+    assert_eq!(m.value.size, 3);
+    assert!(m.values.iter().all(|v| v.size == 3));
+    assert_eq!(m.data, vec![1; 64]);
+}
+
+#[test]
+#[should_panic(expected = "wrote more than the 4 bytes")]
+fn check_flat_field_encode_writes_more_than_size() {
+    serialize_mismatched(4, 8, false);
+}
+
+#[test]
+#[should_panic(expected = "wrote 4 bytes instead of the 8 bytes")]
+fn check_flat_field_encode_writes_less_than_size() {
+    serialize_mismatched(8, 4, false);
+}
+
+#[test]
+#[should_panic(expected = "wrote more than the 3 bytes")]
+fn check_flat_field_encode_writes_more_with_unstable_size() {
+    serialize_mismatched(3, 100_000, true);
+}
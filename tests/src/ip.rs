@@ -150,3 +150,156 @@ fn check_ip_repr() {
         ]
     );
 }
+
+#[test]
+fn check_ip_lists() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        v4: Vec<Ipv4Addr>,
+        v6: Vec<std::net::Ipv6Addr>,
+        mixed: Vec<IpAddr>,
+        empty: Vec<Ipv4Addr>,
+        optional: Option<Vec<IpAddr>>,
+    }
+    let t = Test {
+        v4: vec![Ipv4Addr::new(10, 0, 0, 1), Ipv4Addr::BROADCAST],
+        v6: vec![Ipv6Addr::LOCALHOST, Ipv6Addr::new(0xfe80, 0, 0, 0, 1, 2, 3, 4)],
+        mixed: vec![
+            IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+            IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)),
+        ],
+        empty: Vec::new(),
+        optional: Some(vec![IpAddr::V4(Ipv4Addr::LOCALHOST)]),
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    assert_eq!(Test::deserialize_from(&s).unwrap(), t);
+    assert_eq!(unsafe { Test::deserialize_from_unchecked(&s).unwrap() }, t);
+}
+
+#[test]
+fn check_ip_slices_zero_copy() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Owned {
+        allow: Vec<Ipv4Addr>,
+        deny: Vec<Ipv6Addr>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Borrowed<'a> {
+        allow: &'a [Ipv4Addr],
+        deny: &'a [Ipv6Addr],
+    }
+    let allow: Vec<Ipv4Addr> = (0..1000u32).map(|i| Ipv4Addr::from(0x0A00_0000 + i)).collect();
+    let deny = vec![Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1)];
+    let mut s = Storage::default();
+    Owned {
+        allow: allow.clone(),
+        deny: deny.clone(),
+    }
+    .serialize_to(&mut s, Config::default())
+    .unwrap();
+    let b = Borrowed::deserialize_from(&s).unwrap();
+    assert_eq!(b.allow, allow.as_slice());
+    assert_eq!(b.deny, deny.as_slice());
+    assert!(s.as_slice().as_ptr_range().contains(&(b.allow.as_ptr() as *const u8)));
+    // the octets are stored in network order
+    let mut s2 = Storage::default();
+    Borrowed {
+        allow: &[Ipv4Addr::new(1, 2, 3, 4)],
+        deny: &[],
+    }
+    .serialize_to(&mut s2, Config::default())
+    .unwrap();
+    assert!(s2.as_slice().windows(5).any(|w| w == [1, 1, 2, 3, 4]));
+}
+
+#[test]
+fn check_ip_arrays() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        gateways: [Ipv4Addr; 2],
+        dns: Option<[Ipv6Addr; 1]>,
+    }
+    validate_correct_serde(Test {
+        gateways: [Ipv4Addr::LOCALHOST, Ipv4Addr::new(8, 8, 8, 8)],
+        dns: Some([Ipv6Addr::LOCALHOST]),
+    });
+}
+
+#[test]
+fn check_ip_networks() {
+    use ipnet::{IpNet, Ipv4Net, Ipv6Net};
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        local: Ipv4Net,
+        site: ipnet::Ipv6Net,
+        any: IpNet,
+        routes: Vec<IpNet>,
+        v4_routes: Vec<Ipv4Net>,
+        v6_routes: Vec<Ipv6Net>,
+        missing: Option<Ipv4Net>,
+    }
+    let t = Test {
+        local: "192.168.0.0/16".parse().unwrap(),
+        site: "2001:db8::/32".parse().unwrap(),
+        any: "10.1.2.3/8".parse().unwrap(),
+        routes: vec!["0.0.0.0/0".parse().unwrap(), "fe80::/10".parse().unwrap()],
+        v4_routes: vec!["172.16.0.0/12".parse().unwrap()],
+        v6_routes: vec!["::1/128".parse().unwrap(), "fd00::/8".parse().unwrap()],
+        missing: None,
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    // the address is kept as it is (not truncated to the network address)
+    assert_eq!(d.any.addr(), "10.1.2.3".parse::<IpAddr>().unwrap());
+    assert_eq!(unsafe { Test::deserialize_from_unchecked(&s).unwrap() }, t);
+}
+
+#[test]
+fn check_ip_network_invalid_prefix() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        net: ipnet::Ipv4Net,
+    }
+    let mut s = Storage::default();
+    Test {
+        net: "10.0.0.0/8".parse().unwrap(),
+    }
+    .serialize_to(&mut s, Config::default())
+    .unwrap();
+    let mut buf = s.as_slice().to_vec();
+    assert_eq!(&buf[8..13], &[10, 0, 0, 0, 8]);
+    buf[12] = 33;
+    assert!(Test::deserialize_from(&Storage::from_buffer(&buf)).is_err());
+}
+
+#[test]
+fn check_ip_network_not_compatible_with_address() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Net {
+        value: ipnet::Ipv4Net,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Addr {
+        value: Ipv4Addr,
+    }
+    let mut s = Storage::default();
+    Net {
+        value: "10.0.0.0/8".parse().unwrap(),
+    }
+    .serialize_to(&mut s, Config::default())
+    .unwrap();
+    assert!(Addr::deserialize_from(&s).is_err());
+}
@@ -0,0 +1,153 @@
+use flat_message::*;
+
+#[derive(Debug, PartialEq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Streamed<'a> {
+    id: u32,
+    bytes: IterField<'a, u8>,
+    flags: IterField<'a, bool>,
+    values: IterField<'a, i64>,
+    ratios: IterField<'a, f32>,
+    large: IterField<'a, u128>,
+    names: IterField<'a, &'a str>,
+    labels: IterField<'a, String>,
+    missing: Option<IterField<'a, u16>>,
+}
+
+#[derive(Debug, PartialEq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Materialized<'a> {
+    id: u32,
+    bytes: Vec<u8>,
+    flags: Vec<bool>,
+    values: Vec<i64>,
+    ratios: Vec<f32>,
+    large: Vec<u128>,
+    names: Vec<&'a str>,
+    labels: Vec<String>,
+    missing: Option<Vec<u16>>,
+}
+
+struct Record {
+    id: u32,
+    name: &'static str,
+}
+
+const RECORDS: [Record; 3] = [
+    Record { id: 1, name: "one" },
+    Record { id: 20, name: "twenty" },
+    Record { id: 300, name: "three hundred" },
+];
+
+fn streamed<'a>() -> Streamed<'a> {
+    Streamed {
+        id: 7,
+        bytes: IterField::new(RECORDS.iter().map(|r| r.id as u8)),
+        flags: IterField::new(RECORDS.iter().map(|r| r.id > 10)),
+        values: IterField::new((0..300i32).map(|v| v as i64 * -3)),
+        ratios: IterField::new(RECORDS.iter().map(|r| r.id as f32 / 2.0)),
+        large: IterField::new(std::iter::once(u128::MAX)),
+        names: IterField::new(RECORDS.iter().map(|r| r.name)),
+        labels: IterField::new(RECORDS.iter().map(|r| format!("#{}", r.id))),
+        missing: None,
+    }
+}
+
+fn materialized<'a>() -> Materialized<'a> {
+    Materialized {
+        id: 7,
+        bytes: RECORDS.iter().map(|r| r.id as u8).collect(),
+        flags: RECORDS.iter().map(|r| r.id > 10).collect(),
+        values: (0..300).map(|v| v * -3).collect(),
+        ratios: RECORDS.iter().map(|r| r.id as f32 / 2.0).collect(),
+        large: vec![u128::MAX],
+        names: RECORDS.iter().map(|r| r.name).collect(),
+        labels: RECORDS.iter().map(|r| format!("#{}", r.id)).collect(),
+        missing: None,
+    }
+}
+
+#[test]
+fn check_same_format_as_vec() {
+    let mut s1 = Storage::default();
+    streamed().serialize_to(&mut s1, Config::default()).unwrap();
+    let mut s2 = Storage::default();
+    materialized().serialize_to(&mut s2, Config::default()).unwrap();
+    assert_eq!(s1.as_slice(), s2.as_slice());
+    assert_eq!(validate(s1.as_slice()), Ok(()));
+
+    // messages can be read in both ways
+    assert_eq!(Materialized::deserialize_from(&s1).unwrap(), materialized());
+    assert_eq!(Streamed::deserialize_from(&s2).unwrap(), streamed());
+    assert_eq!(unsafe { Streamed::deserialize_from_unchecked(&s2).unwrap() }, streamed());
+}
+
+#[test]
+fn check_deserialized_iter_field() {
+    let mut s = Storage::default();
+    let mut m = materialized();
+    m.missing = Some(vec![1, 2]);
+    m.serialize_to(&mut s, Config::default()).unwrap();
+    let d = Streamed::deserialize_from(&s).unwrap();
+    assert_eq!(d.values.len(), 300);
+    assert_eq!(d.values.iter().nth(2), Some(-6));
+    assert_eq!(d.names.iter().collect::<Vec<_>>(), vec!["one", "twenty", "three hundred"]);
+    assert_eq!(d.missing.unwrap().iter().collect::<Vec<_>>(), vec![1, 2]);
+    // every call of `iter` starts from the first element
+    assert_eq!(d.bytes.iter().count(), 3);
+    assert_eq!(d.bytes.iter().count(), 3);
+    assert_eq!(format!("{:?}", d.labels), r##"["#1", "#20", "#300"]"##);
+}
+
+#[test]
+fn check_empty_iter_field() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Empty<'a> {
+        values: IterField<'a, u64>,
+        names: IterField<'a, String>,
+    }
+    let mut s = Storage::default();
+    let e = Empty {
+        values: IterField::default(),
+        names: Vec::new().into(),
+    };
+    assert!(e.values.is_empty());
+    e.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(Empty::deserialize_from(&s).unwrap(), e);
+}
+
+/// An iterator that reports a wrong number of elements.
+#[derive(Clone)]
+struct WrongLen(u32);
+impl Iterator for WrongLen {
+    type Item = u32;
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            None
+        } else {
+            self.0 -= 1;
+            Some(self.0)
+        }
+    }
+}
+impl ExactSizeIterator for WrongLen {
+    fn len(&self) -> usize {
+        self.0 as usize + 1
+    }
+}
+
+#[test]
+#[should_panic(expected = "fewer elements than its length")]
+fn check_iterator_with_wrong_len() {
+    #[derive(FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Values<'a> {
+        values: IterField<'a, u32>,
+    }
+    let v = Values {
+        values: IterField::new(WrongLen(3)),
+    };
+    let mut s = Storage::default();
+    let _ = v.serialize_to(&mut s, Config::default());
+}
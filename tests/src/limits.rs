@@ -0,0 +1,182 @@
+use flat_message::*;
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Record {
+    name: String,
+    values: Vec<u64>,
+    tags: Vec<String>,
+}
+
+fn record() -> Record {
+    Record {
+        name: "sensor".to_string(),
+        values: (0..100).collect(),
+        tags: vec!["a".to_string(), "b".to_string(), "c".to_string()],
+    }
+}
+
+fn serialize<'a, T: FlatMessage<'a>>(obj: &T) -> Storage {
+    let mut s = Storage::default();
+    obj.serialize_to(&mut s, Config::default()).unwrap();
+    s
+}
+
+#[test]
+fn check_default_limits() {
+    let r = record();
+    let s = serialize(&r);
+    assert_eq!(Record::deserialize_from_with_config(&s, Config::default()).unwrap(), r);
+    let config = ConfigBuilder::new().max_list_len(100).max_string_len(6).max_allocation(1024).build();
+    assert_eq!(Record::deserialize_from_with_config(&s, config).unwrap(), r);
+}
+
+#[test]
+fn check_max_list_len() {
+    let r = record();
+    let s = serialize(&r);
+    let config = ConfigBuilder::new().max_list_len(99).build();
+    assert_eq!(Record::deserialize_from_with_config(&s, config), Err(Error::MaxListLengthExceeded(99)));
+    // the limits are only used when a config is provided
+    assert_eq!(Record::deserialize_from(&s).unwrap(), r);
+
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct View<'a> {
+        values: &'a [u64],
+        flags: Option<Vec<Option<u8>>>,
+    }
+    let v = View {
+        values: &[1, 2, 3],
+        flags: Some(vec![Some(1), None]),
+    };
+    let s = serialize(&v);
+    let config = ConfigBuilder::new().max_list_len(2).build();
+    assert_eq!(View::deserialize_from_with_config(&s, config), Err(Error::MaxListLengthExceeded(2)));
+    let config = ConfigBuilder::new().max_list_len(3).build();
+    assert_eq!(View::deserialize_from_with_config(&s, config).unwrap(), v);
+}
+
+#[test]
+fn check_max_string_len() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Borrowed<'a> {
+        name: &'a str,
+        aliases: Vec<&'a str>,
+    }
+    let b = Borrowed {
+        name: "short",
+        aliases: vec!["a much longer alias"],
+    };
+    let s = serialize(&b);
+    let config = ConfigBuilder::new().max_string_len(10).build();
+    assert_eq!(Borrowed::deserialize_from_with_config(&s, config), Err(Error::MaxStringLengthExceeded(10)));
+    let config = ConfigBuilder::new().max_string_len(19).build();
+    assert_eq!(Borrowed::deserialize_from_with_config(&s, config).unwrap(), b);
+
+    let s = serialize(&record());
+    let config = ConfigBuilder::new().max_string_len(5).build();
+    assert_eq!(Record::deserialize_from_with_config(&s, config), Err(Error::MaxStringLengthExceeded(5)));
+}
+
+#[test]
+fn check_max_allocation() {
+    let r = record();
+    let s = serialize(&r);
+    // 100 u64 values (800 bytes) + the name + 3 strings
+    let config = ConfigBuilder::new().max_allocation(800).build();
+    assert_eq!(Record::deserialize_from_with_config(&s, config), Err(Error::MaxAllocationExceeded(800)));
+
+    // zero-copy values do not allocate
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct View<'a> {
+        name: &'a str,
+        values: &'a [u64],
+    }
+    let config = ConfigBuilder::new().max_allocation(0).build();
+    let v = View::deserialize_from_with_config(&s, config).unwrap();
+    assert_eq!(v.name, "sensor");
+    assert_eq!(v.values, r.values.as_slice());
+}
+
+#[test]
+fn check_max_allocation_with_string_table() {
+    // a small message can reference the same (large) string many times
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false, string_table: true)]
+    struct Shared {
+        hosts: Vec<String>,
+    }
+    let shared = Shared {
+        hosts: vec!["x".repeat(1000); 1000],
+    };
+    let s = serialize(&shared);
+    assert!(s.len() < 3000);
+    let config = ConfigBuilder::new().max_allocation(64 * 1024).build();
+    assert_eq!(Shared::deserialize_from_with_config(&s, config), Err(Error::MaxAllocationExceeded(64 * 1024)));
+    let config = ConfigBuilder::new().max_allocation(2 * 1024 * 1024).build();
+    assert_eq!(Shared::deserialize_from_with_config(&s, config).unwrap(), shared);
+}
+
+#[test]
+fn check_max_depth_for_structs() {
+    #[derive(Debug, PartialEq, Eq, FlatMessageStruct)]
+    struct Inner {
+        value: u32,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessageStruct)]
+    struct Middle {
+        #[flat_message_item(align = 4, kind = struct)]
+        inner: Inner,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Outer {
+        #[flat_message_item(align = 4, kind = struct)]
+        middle: Middle,
+    }
+    let o = Outer {
+        middle: Middle {
+            inner: Inner { value: 5 },
+        },
+    };
+    let s = serialize(&o);
+    let config = ConfigBuilder::new().max_depth(1).build();
+    assert_eq!(Outer::deserialize_from_with_config(&s, config), Err(Error::MaxDepthExceeded(1)));
+    let config = ConfigBuilder::new().max_depth(2).build();
+    assert_eq!(Outer::deserialize_from_with_config(&s, config).unwrap(), o);
+}
+
+#[test]
+fn check_limits_are_restored_after_a_panic() {
+    mod panics {
+        use flat_message::{Error, FieldReader, FieldWriter};
+        pub fn size(_: &u8) -> usize {
+            1
+        }
+        pub fn write(value: &u8, w: &mut FieldWriter) {
+            w.write_u8(*value);
+        }
+        pub fn read(r: &mut FieldReader) -> Result<u8, Error> {
+            match r.read_u8()? {
+                0 => panic!("invalid value"),
+                value => Ok(value),
+            }
+        }
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Panics {
+        #[flat_message_item(with = "panics")]
+        value: u8,
+        values: Vec<u64>,
+    }
+    let config = ConfigBuilder::new().max_list_len(1).build();
+    let s = serialize(&Panics { value: 0, values: vec![] });
+    assert!(std::panic::catch_unwind(|| Panics::deserialize_from_with_config(&s, config)).is_err());
+    // the limits from `config` are no longer used
+    let p = Panics { value: 1, values: vec![1, 2, 3] };
+    assert_eq!(Panics::deserialize_from(&serialize(&p)).unwrap(), p);
+}
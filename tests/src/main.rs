@@ -34,6 +34,54 @@ mod checksum_validation;
 mod name_validation;
 #[cfg(test)]
 mod default_values;
+#[cfg(test)]
+mod validate;
+#[cfg(test)]
+mod std_types;
+#[cfg(test)]
+mod date_time;
+#[cfg(test)]
+mod uuids;
+#[cfg(test)]
+mod path;
+#[cfg(test)]
+mod cow;
+#[cfg(test)]
+mod smart_pointers;
+#[cfg(test)]
+mod tuples;
+#[cfg(test)]
+mod nullable;
+#[cfg(test)]
+mod nested_lists;
+#[cfg(test)]
+mod bits;
+#[cfg(test)]
+mod varint;
+#[cfg(test)]
+mod delta;
+#[cfg(test)]
+mod string_table;
+#[cfg(test)]
+mod offsets64;
+#[cfg(test)]
+mod limits;
+#[cfg(test)]
+mod iter_field;
+#[cfg(test)]
+mod archive;
+#[cfg(test)]
+mod batch;
+#[cfg(test)]
+mod storage_pool;
+#[cfg(test)]
+mod shared_bytes;
+#[cfg(test)]
+mod owned_message;
+#[cfg(test)]
+mod field_adapters;
+#[cfg(test)]
+mod flat_field;
 
 #[cfg(test)]
 pub(crate) use flat_message::{Config, FlatMessage, Storage};
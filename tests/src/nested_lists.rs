@@ -0,0 +1,127 @@
+use flat_message::*;
+
+#[test]
+fn check_nested_lists() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        rows: Vec<Vec<u32>>,
+        bytes: Vec<Vec<u8>>,
+        small: Vec<Vec<i16>>,
+        values: Vec<Vec<f64>>,
+        big: Vec<Vec<u128>>,
+        signed: Vec<Vec<i8>>,
+        names: Vec<Vec<String>>,
+        empty: Vec<Vec<u64>>,
+        optional: Option<Vec<Vec<i32>>>,
+        missing: Option<Vec<Vec<i32>>>,
+    }
+    let t = Test {
+        rows: vec![vec![1, 2, 3], vec![], vec![u32::MAX]],
+        bytes: vec![vec![1; 300], vec![2, 3]],
+        small: vec![vec![-1], vec![2, 3, 4]],
+        values: vec![vec![1.5, -2.5], vec![f64::MAX]],
+        big: vec![vec![], vec![u128::MAX, 1]],
+        signed: vec![vec![-128, 127]],
+        names: vec![vec!["a".to_string(), "bb".to_string()], vec![]],
+        empty: Vec::new(),
+        optional: Some(vec![vec![-5], vec![6, 7]]),
+        missing: None,
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    let d = unsafe { Test::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_nested_lists_zero_copy() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Borrowed<'a> {
+        attachments: Vec<&'a [u8]>,
+        rows: Vec<&'a [u64]>,
+        id: u8,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Owned {
+        attachments: Vec<Vec<u8>>,
+        rows: Vec<Vec<u64>>,
+        id: u8,
+    }
+    let first = [1u8, 2, 3];
+    let second = [0xFFu8; 1000];
+    let t = Borrowed {
+        attachments: vec![&first, &[], &second],
+        rows: vec![&[1, 2], &[u64::MAX]],
+        id: 7,
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Borrowed::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    // the lists point inside the buffer
+    let range = s.as_slice().as_ptr_range();
+    assert!(range.contains(&d.attachments[2].as_ptr()));
+    assert!(range.contains(&(d.rows[0].as_ptr() as *const u8)));
+    assert!((d.rows[1].as_ptr() as usize).is_multiple_of(8));
+    // the borrowed and the owned forms are interchangeable
+    let o = Owned::deserialize_from(&s).unwrap();
+    assert_eq!(o.attachments, vec![first.to_vec(), vec![], second.to_vec()]);
+    assert_eq!(o.rows, vec![vec![1, 2], vec![u64::MAX]]);
+    let mut s2 = Storage::default();
+    o.serialize_to(&mut s2, Config::default()).unwrap();
+    assert_eq!(s.as_slice(), s2.as_slice());
+}
+
+#[test]
+fn check_nested_lists_type_mismatch() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Nested {
+        value: Vec<Vec<u32>>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Flat {
+        value: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Other {
+        value: Vec<Vec<u16>>,
+    }
+    let mut s = Storage::default();
+    Nested { value: vec![vec![1, 2], vec![3]] }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    assert!(Flat::deserialize_from(&s).is_err());
+    assert!(Other::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Flat { value: vec![1, 2] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Nested::deserialize_from(&s).is_err());
+}
+
+#[test]
+fn check_nested_lists_invalid_buffer() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        value: Vec<Vec<u32>>,
+    }
+    let mut s = Storage::default();
+    Test { value: vec![vec![1, 2], vec![3]] }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    let mut buf = s.as_slice().to_vec();
+    // the data starts right after the header (8 bytes) - set the number of lists to a huge value
+    buf[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    let s = Storage::from_buffer(&buf);
+    assert!(validate(s.as_slice()).is_err());
+    assert!(Test::deserialize_from(&s).is_err());
+}
@@ -0,0 +1,188 @@
+use crate::*;
+use flat_message::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+#[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+#[repr(u16)]
+enum Level {
+    Low = 1,
+    Medium = 2,
+    High = 3,
+}
+
+#[test]
+fn check_nullable_lists() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        bytes: Vec<Option<u8>>,
+        temperatures: Vec<Option<f32>>,
+        counters: Vec<Option<u64>>,
+        big: Vec<Option<i128>>,
+        names: Vec<Option<String>>,
+        #[flat_message_item(repr = u16, kind = enum)]
+        levels: Vec<Option<Level>>,
+        ips: Vec<Option<IpAddr>>,
+        v4: Vec<Option<Ipv4Addr>>,
+        v6: Vec<Option<Ipv6Addr>>,
+        empty: Vec<Option<u32>>,
+        all_missing: Vec<Option<u16>>,
+        optional: Option<Vec<Option<i32>>>,
+    }
+    let t = Test {
+        bytes: vec![Some(1), None, Some(3)],
+        temperatures: vec![Some(21.5), None, None, Some(-3.0), None, None, None, None, Some(10.0)],
+        counters: vec![None, Some(u64::MAX)],
+        big: vec![Some(-1), None, Some(i128::MAX)],
+        names: vec![Some("first".to_string()), None, Some(String::new())],
+        levels: vec![Some(Level::High), None, Some(Level::Low), Some(Level::Medium)],
+        ips: vec![Some(IpAddr::V4(Ipv4Addr::LOCALHOST)), None, Some(IpAddr::V6(Ipv6Addr::LOCALHOST))],
+        v4: vec![None, Some(Ipv4Addr::new(192, 168, 0, 1))],
+        v6: vec![Some(Ipv6Addr::UNSPECIFIED), None],
+        empty: Vec::new(),
+        all_missing: vec![None; 10],
+        optional: Some(vec![None, Some(-5)]),
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    let d = unsafe { Test::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_nullable_str_references() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test<'a> {
+        tags: Vec<Option<&'a str>>,
+    }
+    let t = Test {
+        tags: vec![Some("a"), None, Some("bc")],
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    assert!(s.as_slice().as_ptr_range().contains(&d.tags[2].unwrap().as_ptr()));
+}
+
+#[test]
+fn check_nullable_slice() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Owned {
+        samples: Vec<Option<f64>>,
+        #[flat_message_item(repr = u16, kind = enum)]
+        levels: Vec<Option<Level>>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Borrowed<'a> {
+        samples: NullableSlice<'a, f64>,
+        #[flat_message_item(repr = u16, kind = enum)]
+        levels: NullableSlice<'a, Level>,
+    }
+    let owned = Owned {
+        samples: vec![Some(1.0), None, None, Some(2.0), None, None, None, None, None, Some(3.0)],
+        levels: vec![None, Some(Level::Medium)],
+    };
+    let mut s = Storage::default();
+    owned.serialize_to(&mut s, Config::default()).unwrap();
+    let b = Borrowed::deserialize_from(&s).unwrap();
+    assert_eq!(b.samples.len(), 10);
+    assert_eq!(b.samples.null_count(), 7);
+    assert_eq!(b.samples.values(), &[1.0, 2.0, 3.0]);
+    assert_eq!(b.samples.validity(), &[0b0000_1001, 0b10]);
+    assert!(s.as_slice().as_ptr_range().contains(&(b.samples.values().as_ptr() as *const u8)));
+    assert_eq!(b.samples.get(0), Some(&1.0));
+    assert_eq!(b.samples.get(1), None);
+    assert_eq!(b.samples.get(3), Some(&2.0));
+    assert_eq!(b.samples.get(9), Some(&3.0));
+    assert_eq!(b.samples.get(10), None);
+    assert_eq!(b.samples.to_vec(), owned.samples);
+    assert_eq!(b.levels.iter().collect::<Vec<_>>(), vec![None, Some(&Level::Medium)]);
+
+    // a NullableSlice can be serialized and read back as a vector
+    let mut s2 = Storage::default();
+    b.serialize_to(&mut s2, Config::default()).unwrap();
+    assert_eq!(s.as_slice(), s2.as_slice());
+    assert_eq!(Owned::deserialize_from(&s2).unwrap(), owned);
+}
+
+#[test]
+fn check_nullable_slice_new() {
+    assert!(NullableSlice::new(3, &[0b101], &[1u32, 2]).is_some());
+    // wrong number of values
+    assert!(NullableSlice::new(3, &[0b101], &[1u32]).is_none());
+    // bits set after the last element
+    assert!(NullableSlice::new(3, &[0b1101], &[1u32, 2, 3]).is_none());
+    // bitmap too small / too large
+    assert!(NullableSlice::<u32>::new(9, &[0], &[]).is_none());
+    assert!(NullableSlice::<u32>::new(1, &[0, 0], &[]).is_none());
+    let empty = NullableSlice::<u8>::default();
+    assert!(empty.is_empty());
+    assert_eq!(empty.iter().count(), 0);
+}
+
+#[test]
+fn check_nullable_type_mismatch() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Nullable {
+        values: Vec<Option<u32>>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Regular {
+        values: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct OtherType {
+        values: Vec<Option<i32>>,
+    }
+    let mut s = Storage::default();
+    Nullable { values: vec![Some(1), None] }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    assert!(Regular::deserialize_from(&s).is_err());
+    assert!(OtherType::deserialize_from(&s).is_err());
+    Regular { values: vec![1, 2] }
+        .serialize_to(&mut s, Config::default())
+        .unwrap();
+    assert!(Nullable::deserialize_from(&s).is_err());
+}
+
+#[test]
+fn check_nullable_invalid_buffer() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        #[flat_message_item(repr = u16, kind = enum)]
+        levels: Vec<Option<Level>>,
+    }
+    let mut s = Storage::default();
+    Test {
+        levels: vec![Some(Level::Low), None],
+    }
+    .serialize_to(&mut s, Config::default())
+    .unwrap();
+    let mut buf = s.as_slice().to_vec();
+    // header (8) + count (4) + type hash (4) + bitmap (1) + padding (1) + value (2)
+    assert_eq!(&buf[8..12], &[2, 0, 0, 0]);
+    assert_eq!(buf[16], 0b01);
+    assert_eq!(&buf[18..20], &[1, 0]);
+    // invalid enum value
+    buf[18] = 100;
+    assert!(Test::deserialize_from(&Storage::from_buffer(&buf)).is_err());
+    buf[18] = 1;
+    // bit set for an element that does not exist
+    buf[16] = 0b101;
+    assert!(Test::deserialize_from(&Storage::from_buffer(&buf)).is_err());
+    assert!(validate(&buf).is_err());
+    buf[16] = 0b01;
+    assert!(Test::deserialize_from(&Storage::from_buffer(&buf)).is_ok());
+}
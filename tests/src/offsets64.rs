@@ -0,0 +1,222 @@
+use flat_message::*;
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Record {
+    id: u32,
+    name: String,
+    values: Vec<u64>,
+    missing: Option<u16>,
+}
+
+/// Rewrites a message that uses 8 bit offsets (and has no metadata) to use 64 bit offsets.
+fn to_64bit_offsets(buf: &[u8]) -> Vec<u8> {
+    assert_eq!(buf[7] & 0b11, 0);
+    let fields_count = u16::from_le_bytes([buf[4], buf[5]]) as usize;
+    let ref_table_offset = buf.len() - fields_count;
+    let mut result = buf[..ref_table_offset].to_vec();
+    for offset in &buf[ref_table_offset..] {
+        result.extend_from_slice(&(*offset as u64).to_le_bytes());
+    }
+    result[7] |= 0b11;
+    result
+}
+
+#[test]
+fn check_64bit_offsets() {
+    let r = Record {
+        id: 7,
+        name: "large".to_string(),
+        values: vec![1, 2, 3],
+        missing: None,
+    };
+    let mut s = Storage::default();
+    r.serialize_to(&mut s, Config::default()).unwrap();
+    let s = Storage::from_buffer(&to_64bit_offsets(s.as_slice()));
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    assert_eq!(Record::deserialize_from(&s).unwrap(), r);
+    assert_eq!(unsafe { Record::deserialize_from_unchecked(&s).unwrap() }, r);
+}
+
+#[test]
+fn check_64bit_offset_out_of_bounds() {
+    let r = Record {
+        id: 7,
+        name: String::new(),
+        values: Vec::new(),
+        missing: None,
+    };
+    let mut s = Storage::default();
+    r.serialize_to(&mut s, Config::default()).unwrap();
+    let mut buf = to_64bit_offsets(s.as_slice());
+    // the last byte of the first offset (an offset larger than 4 GiB)
+    let first_offset = buf.len() - 4 * 8;
+    buf[first_offset + 7] = 1;
+    let s = Storage::from_buffer(&buf);
+    assert!(matches!(validate(s.as_slice()), Err(Error::InvalidFieldOffset((0x0100_0000_0000_0000.., _)))));
+    assert!(matches!(Record::deserialize_from(&s), Err(Error::InvalidFieldOffset(_))));
+}
+
+#[test]
+fn check_64bit_sizes() {
+    let formats = [
+        (size::Format::U8withExtension, 13),
+        (size::Format::U16withExtension, 14),
+        (size::Format::U32, 12),
+        (size::Format::U32on64bits, 8),
+        (size::Format::U32on96bits, 12),
+        (size::Format::U32on128bits, 16),
+    ];
+    for (format, expected_len) in formats {
+        for (value, large) in [(0xFFFF_FFFEusize, false), (0xFFFF_FFFF, true), (0x1_2345_6789, true)] {
+            let mut buf = [0u8; 16];
+            let len = unsafe { size::write(buf.as_mut_ptr(), 0, value, format) };
+            assert_eq!(len, size::len(value, format));
+            if large {
+                assert_eq!(len, expected_len);
+            }
+            assert_eq!(size::read(buf.as_ptr(), 0, buf.len(), format), Some((value, len)));
+            assert_eq!(unsafe { size::read_unchecked(buf.as_ptr(), 0, format) }, (value, len));
+            // the size does not fit in the buffer
+            assert_eq!(size::read(buf.as_ptr(), 0, len - 1, format), None);
+        }
+    }
+}
+
+#[test]
+fn check_max_size_above_4gb() {
+    let config = ConfigBuilder::new().max_size(8 << 30).build();
+    assert_eq!(config.max_size(), 8 << 30);
+    let mut s = Storage::default();
+    let r = Record {
+        id: 1,
+        name: "a".to_string(),
+        values: vec![0; 4],
+        missing: Some(1),
+    };
+    assert_eq!(
+        r.serialize_to(&mut s, ConfigBuilder::new().max_size(10).build()),
+        Err(Error::ExceedMaxSize((s_len(&r), 10)))
+    );
+    r.serialize_to(&mut s, config).unwrap();
+    assert_eq!(Record::deserialize_from(&s).unwrap(), r);
+}
+
+fn s_len(r: &Record) -> u64 {
+    let mut s = Storage::default();
+    r.serialize_to(&mut s, Config::default()).unwrap();
+    s.len() as u64
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessageStruct)]
+struct Inner {
+    a: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Outer {
+    #[flat_message_item(kind = struct, align = 4)]
+    inner: Inner,
+}
+
+#[test]
+fn check_64bit_offset_overflow_in_nested_struct() {
+    let mut s = Storage::default();
+    Outer { inner: Inner { a: 5 } }.serialize_to(&mut s, Config::default()).unwrap();
+    let buf = s.as_slice();
+    assert_eq!(buf.len(), 33);
+    // the nested structure (at offset 8) is rewritten to use 64 bit offsets, with an offset equal to u64::MAX
+    let mut crafted = buf[..12].to_vec();
+    crafted.extend_from_slice(&((20u32 << 8) | (1 << 2) | 0b11).to_le_bytes());
+    crafted.extend_from_slice(&buf[20..24]);
+    crafted.extend_from_slice(&u64::MAX.to_le_bytes());
+    crafted.extend_from_slice(&buf[28..]);
+    assert_eq!(crafted.len(), 33);
+    let s = Storage::from_buffer(&crafted);
+    assert!(matches!(validate(s.as_slice()), Err(Error::InvalidFieldOffset(_))));
+    assert!(Outer::deserialize_from(&s).is_err());
+}
+
+#[test]
+fn check_64bit_list_count_overflow() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Lists<'a> {
+        values: &'a [u64],
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Owned {
+        values: Vec<u64>,
+    }
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Sizes {
+        values: Vec<usize>,
+    }
+    for count in [u64::MAX / 8 + 1, u64::MAX / 4, u64::MAX] {
+        let mut s = Storage::default();
+        Owned { values: vec![1, 2, 3] }.serialize_to(&mut s, Config::default()).unwrap();
+        let mut buf = s.as_slice().to_vec();
+        // the values are stored after an 8 bytes count (the offset of the field is the last byte)
+        let pos = buf[buf.len() - 1] as usize;
+        buf[pos..pos + 8].copy_from_slice(&count.to_le_bytes());
+        let s = Storage::from_buffer(&buf);
+        assert!(validate(s.as_slice()).is_err());
+        assert!(Lists::deserialize_from(&s).is_err());
+        assert!(Owned::deserialize_from(&s).is_err());
+        assert!(Sizes::deserialize_from(&s).is_err());
+    }
+}
+
+#[test]
+fn check_64bit_string_len_overflow() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Text<'a> {
+        value: &'a str,
+        values: Vec<&'a str>,
+    }
+    let mut s = Storage::default();
+    let text = "a".repeat(20);
+    Text { value: &text, values: vec![&text] }.serialize_to(&mut s, Config::default()).unwrap();
+    let buf = s.as_slice().to_vec();
+    for field in 0..2 {
+        let mut buf = buf.clone();
+        let pos = buf[buf.len() - 2 + field] as usize;
+        // a 64 bit length (0xFF, the u32 marker and u64::MAX) instead of the 1 byte length (or count)
+        buf[pos] = 0xFF;
+        buf[pos + 1..pos + 5].copy_from_slice(&u32::MAX.to_le_bytes());
+        buf[pos + 5..pos + 13].copy_from_slice(&u64::MAX.to_le_bytes());
+        let s = Storage::from_buffer(&buf);
+        assert!(validate(s.as_slice()).is_err());
+        assert!(Text::deserialize_from(&s).is_err());
+    }
+}
+
+#[test]
+fn check_nested_struct_larger_than_16mb() {
+    #[derive(Debug, PartialEq, Eq, FlatMessageStruct)]
+    struct Blob {
+        data: Vec<u8>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Message {
+        #[flat_message_item(kind = struct, align = 4)]
+        blob: Blob,
+    }
+    let config = ConfigBuilder::new().max_size(1 << 30).build();
+    let mut s = Storage::default();
+    // the size of a nested structure is stored on 24 bits
+    let m = Message { blob: Blob { data: vec![1; 17 << 20] } };
+    assert!(matches!(
+        m.serialize_to(&mut s, config),
+        Err(Error::ExceedMaxSize((size, 0xFF_FFFF))) if size > 17 << 20
+    ));
+    let m = Message { blob: Blob { data: vec![1; (16 << 20) - 64] } };
+    m.serialize_to(&mut s, config).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    assert_eq!(Message::deserialize_from(&s).unwrap(), m);
+}
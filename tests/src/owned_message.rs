@@ -0,0 +1,128 @@
+use flat_message::*;
+use std::collections::VecDeque;
+use std::io::Cursor;
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+struct Msg<'a> {
+    id: u32,
+    name: &'a str,
+    values: &'a [u32],
+    tags: Vec<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn serialize(id: u32) -> Storage {
+    let tags = [format!("tag-{id}")];
+    let msg = Msg {
+        id,
+        name: "sensor",
+        values: &[id, id + 1],
+        tags: tags.iter().map(|t| t.as_str()).collect(),
+    };
+    let mut storage = Storage::default();
+    msg.serialize_to(&mut storage, Config::default()).unwrap();
+    storage
+}
+
+fn load(id: u32) -> OwnedMessage<Msg<'static>> {
+    OwnedMessage::new(serialize(id)).unwrap()
+}
+
+fn check(message: &OwnedMessage<Msg<'static>>, id: u32) {
+    let msg = message.get();
+    assert_eq!(msg.id, id);
+    assert_eq!(msg.name, "sensor");
+    assert_eq!(msg.values, &[id, id + 1]);
+    assert_eq!(msg.tags, vec![format!("tag-{id}").as_str()]);
+    // zero-copy: the fields point inside the buffer of the message
+    assert!(message.storage().as_slice().as_ptr_range().contains(&msg.name.as_ptr()));
+}
+
+#[test]
+fn check_owned_message() {
+    let mut queue: VecDeque<OwnedMessage<Msg<'static>>> = (0..10).map(load).collect();
+    // moving the messages does not move their buffers
+    queue.rotate_left(3);
+    let moved: Vec<_> = queue.drain(..).collect();
+    for (pos, message) in moved.iter().enumerate() {
+        check(message, ((pos + 3) % 10) as u32);
+    }
+    let handle = std::thread::spawn(move || {
+        for message in moved.iter() {
+            assert_eq!(message.get().name, "sensor");
+        }
+        moved.len()
+    });
+    assert_eq!(handle.join().unwrap(), 10);
+
+    let message = load(5);
+    assert_eq!(format!("{:?}", message), format!("{:?}", message.get()));
+    assert_eq!(message.into_storage(), serialize(5));
+}
+
+#[test]
+fn check_owned_message_constructors() {
+    check(&OwnedMessage::from_vec(serialize(1).as_slice().to_vec()).unwrap(), 1);
+    check(&OwnedMessage::with_config(serialize(2), Config::default()).unwrap(), 2);
+    let point = OwnedMessage::<Point>::new({
+        let mut storage = Storage::default();
+        Point { x: 1, y: -1 }.serialize_to(&mut storage, Config::default()).unwrap();
+        storage
+    })
+    .unwrap();
+    assert_eq!(point.get(), &Point { x: 1, y: -1 });
+
+    // errors from the deserialization are returned
+    assert!(OwnedMessage::<Point>::new(serialize(1)).is_err());
+    assert!(OwnedMessage::<Msg>::from_vec(vec![1, 2, 3]).is_err());
+    let config = ConfigBuilder::new().max_list_len(1).build();
+    assert!(matches!(
+        OwnedMessage::<Msg>::with_config(serialize(1), config),
+        Err(Error::MaxListLengthExceeded(_))
+    ));
+}
+
+#[test]
+fn check_frames() {
+    let mut stream = Vec::new();
+    for id in 0..5 {
+        serialize(id).write_frame(&mut stream).unwrap();
+    }
+    let mut reader = Cursor::new(stream.clone());
+    let mut id = 0;
+    while let Some(message) = OwnedMessage::<Msg>::read_frame(&mut reader, Config::default()).unwrap() {
+        check(&message, id);
+        id += 1;
+    }
+    assert_eq!(id, 5);
+
+    // a truncated frame
+    let mut reader = Cursor::new(&stream[..stream.len() - 1]);
+    let error = std::iter::from_fn(|| OwnedMessage::<Msg>::read_frame(&mut reader, Config::default()).transpose())
+        .find_map(|r| r.err())
+        .unwrap();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+    let mut reader = Cursor::new(&stream[..3]);
+    let error = OwnedMessage::<Msg>::read_frame(&mut reader, Config::default()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof);
+
+    // a frame larger than the maximum size
+    let config = ConfigBuilder::new().max_size(16).build();
+    let error = OwnedMessage::<Msg>::read_frame(&mut Cursor::new(&stream), config).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // a frame that is not a valid message
+    let error = OwnedMessage::<Point>::read_frame(&mut Cursor::new(&stream), Config::default()).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+
+    // frames can be read into a reused storage as well
+    let mut storage = Storage::default();
+    let mut reader = Cursor::new(&stream);
+    assert!(storage.read_frame(&mut reader, u64::MAX).unwrap());
+    assert_eq!(storage, serialize(0));
+}
@@ -0,0 +1,102 @@
+use crate::*;
+use flat_message::*;
+use std::ffi::{OsStr, OsString};
+use std::path::{Path, PathBuf};
+
+#[test]
+fn check_owned_paths() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        root: PathBuf,
+        name: OsString,
+        parent: Option<std::path::PathBuf>,
+        files: Vec<PathBuf>,
+        names: Vec<OsString>,
+        empty: PathBuf,
+    }
+    validate_correct_serde(Test {
+        root: PathBuf::from("/var/lib/build"),
+        name: OsString::from("artifact.tar.gz"),
+        parent: Some(PathBuf::from("/var/lib")),
+        files: vec![PathBuf::from("a.txt"), PathBuf::from("dir/b.txt"), PathBuf::from("ț.bin")],
+        names: vec![OsString::from("x"), OsString::new()],
+        empty: PathBuf::new(),
+    });
+}
+
+#[test]
+fn check_borrowed_paths() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    struct Test<'a> {
+        root: &'a Path,
+        name: &'a OsStr,
+        files: Vec<&'a Path>,
+        names: Vec<&'a OsStr>,
+        value: u32,
+    }
+    let t = Test {
+        root: Path::new("/home/user"),
+        name: OsStr::new("report.pdf"),
+        files: vec![Path::new("1"), Path::new("2/3")],
+        names: vec![OsStr::new("a"), OsStr::new("bcd")],
+        value: 123,
+    };
+    let mut storage = Storage::default();
+    t.serialize_to(&mut storage, Config::default()).unwrap();
+    let ds = Test::deserialize_from(&storage).unwrap();
+    assert_eq!(ds, t);
+    // borrowed values point inside the buffer
+    let range = storage.as_slice().as_ptr_range();
+    assert!(range.contains(&ds.root.as_os_str().as_encoded_bytes().as_ptr()));
+}
+
+#[test]
+fn check_path_and_string_are_different() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithPath {
+        value: PathBuf,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithString {
+        value: String,
+    }
+    let mut storage = Storage::default();
+    WithString {
+        value: "/tmp".to_string(),
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert!(WithPath::deserialize_from(&storage).is_err());
+}
+
+#[cfg(unix)]
+#[test]
+fn check_non_utf8_paths() {
+    use std::os::unix::ffi::OsStrExt;
+
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        path: PathBuf,
+        names: Vec<OsString>,
+    }
+    let raw: &[u8] = &[b'/', b't', 0xFF, 0xFE, b'x', 0x80];
+    validate_correct_serde(Test {
+        path: PathBuf::from(OsStr::from_bytes(raw)),
+        names: vec![OsStr::from_bytes(&[0xC0]).to_os_string(), OsString::from("ok")],
+    });
+
+    let mut storage = Storage::default();
+    Test {
+        path: PathBuf::from(OsStr::from_bytes(raw)),
+        names: vec![],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    // the path (length + bytes) is stored right after the header
+    assert_eq!(storage.as_slice()[8] as usize, raw.len());
+    assert_eq!(&storage.as_slice()[9..9 + raw.len()], raw);
+}
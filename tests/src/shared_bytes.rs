@@ -0,0 +1,138 @@
+use bytes::Bytes;
+use flat_message::*;
+use std::collections::HashSet;
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Shared {
+    id: u32,
+    name: SharedStr,
+    payload: Bytes,
+    comment: Option<SharedStr>,
+    tags: Vec<SharedStr>,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Owned {
+    id: u32,
+    name: String,
+    payload: Vec<u8>,
+    comment: Option<String>,
+    tags: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+#[flat_message_options(store_name: false)]
+struct Borrowed<'a> {
+    id: u32,
+    name: &'a str,
+    payload: &'a [u8],
+    comment: Option<&'a str>,
+    tags: Vec<&'a str>,
+}
+
+fn shared() -> Shared {
+    Shared {
+        id: 7,
+        name: SharedStr::from("sensor"),
+        payload: Bytes::from(vec![1, 2, 3, 4, 5]),
+        comment: Some(SharedStr::from_static("ok")),
+        tags: vec![SharedStr::from("a"), SharedStr::from(String::from("bb"))],
+    }
+}
+
+fn is_inside(data: &[u8], buffer: &[u8]) -> bool {
+    let range = buffer.as_ptr_range();
+    range.contains(&data.as_ptr()) && data.as_ptr() as usize + data.len() <= range.end as usize
+}
+
+#[test]
+fn check_deserialize_from_shared() {
+    let mut storage = Storage::default();
+    shared().serialize_to(&mut storage, Config::default()).unwrap();
+    let buffer = SharedStorage::from(storage);
+    let s = Shared::deserialize_from_shared(&buffer).unwrap();
+    assert_eq!(s, shared());
+    // the fields are views into the buffer
+    assert!(is_inside(s.name.as_bytes(), buffer.as_slice()));
+    assert!(is_inside(&s.payload, buffer.as_slice()));
+    assert!(is_inside(s.comment.as_ref().unwrap().as_bytes(), buffer.as_slice()));
+    assert!(s.tags.iter().all(|t| is_inside(t.as_bytes(), buffer.as_slice())));
+
+    // the fields keep the buffer alive
+    let buffer = buffer.try_into_storage().unwrap_err();
+    drop(buffer);
+    let handle = std::thread::spawn(move || s);
+    assert_eq!(handle.join().unwrap(), shared());
+}
+
+#[test]
+fn check_deserialize_copies_without_shared_storage() {
+    let mut storage = Storage::default();
+    shared().serialize_to(&mut storage, Config::default()).unwrap();
+    let s = Shared::deserialize_from(&storage).unwrap();
+    assert_eq!(s, shared());
+    assert!(!is_inside(&s.payload, storage.as_slice()));
+    assert!(!is_inside(s.name.as_bytes(), storage.as_slice()));
+}
+
+#[test]
+fn check_interchangeable_types() {
+    let owned = Owned {
+        id: 7,
+        name: "sensor".to_string(),
+        payload: vec![1, 2, 3, 4, 5],
+        comment: Some("ok".to_string()),
+        tags: vec!["a".to_string(), "bb".to_string()],
+    };
+    let mut storage = Storage::default();
+    owned.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(Shared::deserialize_from(&storage).unwrap(), shared());
+
+    let mut shared_storage = Storage::default();
+    shared().serialize_to(&mut shared_storage, Config::default()).unwrap();
+    assert_eq!(shared_storage.as_slice(), storage.as_slice());
+    assert_eq!(Owned::deserialize_from(&shared_storage).unwrap(), owned);
+    let b = Borrowed::deserialize_from(&shared_storage).unwrap();
+    assert_eq!(b.name, "sensor");
+    assert_eq!(b.payload, &[1, 2, 3, 4, 5]);
+    assert_eq!(b.tags, vec!["a", "bb"]);
+}
+
+#[test]
+fn check_storage_and_bytes_conversions() {
+    let mut storage = Storage::default();
+    shared().serialize_to(&mut storage, Config::default()).unwrap();
+    let expected = storage.as_slice().to_vec();
+    let ptr = storage.as_slice().as_ptr();
+
+    // Storage -> Bytes does not copy the content
+    let bytes = Bytes::from(storage);
+    assert_eq!(bytes.as_ptr(), ptr);
+    assert_eq!(&bytes[..], &expected[..]);
+
+    // Bytes -> Storage / SharedStorage copies it into an aligned buffer
+    let storage = Storage::from(bytes.slice(..));
+    assert_eq!(storage.as_slice(), &expected[..]);
+    let buffer = SharedStorage::from(bytes);
+    assert_eq!(buffer.to_bytes().as_ptr(), buffer.as_slice().as_ptr());
+    assert_eq!(Shared::deserialize_from_shared(&buffer).unwrap(), shared());
+    assert_eq!(buffer.try_into_storage().unwrap(), storage);
+}
+
+#[test]
+fn check_shared_str() {
+    let s = SharedStr::from("hello");
+    assert_eq!(s, "hello");
+    assert_eq!(s.len(), 5);
+    assert_eq!(s.to_string(), "hello");
+    assert_eq!(format!("{:?}", s), "\"hello\"");
+    assert!(SharedStr::from_utf8(Bytes::from_static(&[0xFF, 0xFE])).is_err());
+    assert_eq!(SharedStr::from_utf8(Bytes::from_static(b"abc")).unwrap(), "abc");
+    let set: HashSet<SharedStr> = ["a", "b"].into_iter().map(SharedStr::from).collect();
+    assert!(set.contains("a"));
+    assert!(!set.contains("c"));
+    assert_eq!(String::from(s.clone()), "hello");
+    assert_eq!(Bytes::from(s), Bytes::from_static(b"hello"));
+}
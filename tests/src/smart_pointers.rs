@@ -0,0 +1,247 @@
+use crate::*;
+use flat_message::*;
+use std::rc::Rc;
+use std::sync::Arc;
+
+#[derive(FlatMessageStruct, Debug, PartialEq, Eq, Clone)]
+struct Node {
+    value: u32,
+    #[flat_message_item(kind = struct, align = 4)]
+    left: Option<Box<Node>>,
+    #[flat_message_item(kind = struct, align = 4)]
+    right: Option<Box<Node>>,
+}
+
+#[derive(FlatMessageVariant, Debug, PartialEq, Eq)]
+enum Expr {
+    Number(i64),
+    Name(Box<str>),
+    #[flat_message_item(kind = variant, align = 8)]
+    Negate(Box<Expr>),
+}
+
+#[derive(FlatMessage, Debug, PartialEq, Eq)]
+#[flat_message_options(store_name: false)]
+struct Tree {
+    #[flat_message_item(kind = struct, align = 4)]
+    root: Node,
+    #[flat_message_item(kind = variant, align = 8)]
+    expr: Expr,
+}
+
+fn chain(depth: u32) -> Node {
+    let mut node = Node {
+        value: 0,
+        left: None,
+        right: None,
+    };
+    for value in 1..depth {
+        node = Node {
+            value,
+            left: Some(Box::new(node)),
+            right: None,
+        };
+    }
+    node
+}
+
+#[test]
+fn check_box_rc_arc() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        a: Box<u32>,
+        b: Rc<String>,
+        c: Arc<str>,
+        d: std::boxed::Box<str>,
+        e: Rc<[u16]>,
+        f: Arc<[u8]>,
+        g: Option<Box<i64>>,
+        h: Option<Arc<str>>,
+        i: Box<[u8; 4]>,
+        j: Arc<std::net::Ipv4Addr>,
+    }
+    validate_correct_serde(Test {
+        a: Box::new(1),
+        b: Rc::new("rc".to_string()),
+        c: Arc::from("arc"),
+        d: Box::from("box"),
+        e: Rc::from(vec![1, 2, 3]),
+        f: Arc::from(vec![]),
+        g: Some(Box::new(-5)),
+        h: None,
+        i: Box::new([1, 2, 3, 4]),
+        j: Arc::new(std::net::Ipv4Addr::LOCALHOST),
+    });
+}
+
+#[test]
+fn check_pointer_compatible_with_inner_type() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Plain {
+        value: u64,
+        name: String,
+        values: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Wrapped {
+        value: Box<u64>,
+        name: Arc<str>,
+        values: Box<[u32]>,
+    }
+    let mut storage = Storage::default();
+    Plain {
+        value: 10,
+        name: "name".to_string(),
+        values: vec![1, 2, 3],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    let w = Wrapped::deserialize_from(&storage).unwrap();
+    assert_eq!(*w.value, 10);
+    assert_eq!(&*w.name, "name");
+    assert_eq!(&*w.values, &[1, 2, 3]);
+    let mut wrapped_storage = Storage::default();
+    w.serialize_to(&mut wrapped_storage, Config::default()).unwrap();
+    assert_eq!(wrapped_storage.as_slice(), storage.as_slice());
+}
+
+#[test]
+fn check_pointers_to_vectors() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        a: Box<Vec<u32>>,
+        b: Rc<Vec<String>>,
+        c: Arc<Vec<u8>>,
+        d: std::boxed::Box<Vec<i128>>,
+        e: Option<Box<Vec<u16>>>,
+        f: Option<Arc<Vec<bool>>>,
+        g: Box<Vec<std::net::Ipv4Addr>>,
+    }
+    #[derive(FlatMessageStruct, Debug, PartialEq, Eq)]
+    struct Inner {
+        names: Box<Vec<String>>,
+    }
+    #[derive(FlatMessageVariant, Debug, PartialEq, Eq)]
+    enum Values {
+        Numbers(Rc<Vec<u64>>),
+        Empty,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Nested {
+        #[flat_message_item(kind = struct, align = 4)]
+        inner: Inner,
+        #[flat_message_item(kind = variant, align = 8)]
+        values: Values,
+    }
+    validate_correct_serde(Nested {
+        inner: Inner {
+            names: Box::new(vec!["a".to_string(), "b".to_string()]),
+        },
+        values: Values::Numbers(Rc::new(vec![1, u64::MAX])),
+    });
+    validate_correct_serde(Test {
+        a: Box::new(vec![1, 2, 3]),
+        b: Rc::new(vec!["x".to_string(), String::new()]),
+        c: Arc::new(vec![]),
+        d: Box::new(vec![-1, i128::MAX]),
+        e: Some(Box::new(vec![7])),
+        f: None,
+        g: Box::new(vec![std::net::Ipv4Addr::LOCALHOST]),
+    });
+
+    // same format as Vec<T>
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Plain {
+        values: Vec<u32>,
+        names: Vec<String>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Wrapped {
+        values: Box<Vec<u32>>,
+        names: Arc<Vec<String>>,
+    }
+    let mut storage = Storage::default();
+    Plain {
+        values: vec![1, 2, 3],
+        names: vec!["a".to_string()],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    let w = Wrapped::deserialize_from(&storage).unwrap();
+    assert_eq!(*w.values, vec![1, 2, 3]);
+    assert_eq!(*w.names, vec!["a".to_string()]);
+    let mut wrapped_storage = Storage::default();
+    w.serialize_to(&mut wrapped_storage, Config::default()).unwrap();
+    assert_eq!(wrapped_storage.as_slice(), storage.as_slice());
+}
+
+#[test]
+fn check_recursive_structures() {
+    let root = Node {
+        value: 1,
+        left: Some(Box::new(Node {
+            value: 2,
+            left: None,
+            right: Some(Box::new(Node {
+                value: 3,
+                left: None,
+                right: None,
+            })),
+        })),
+        right: Some(Box::new(Node {
+            value: 4,
+            left: None,
+            right: None,
+        })),
+    };
+    validate_correct_serde(Tree {
+        root,
+        expr: Expr::Negate(Box::new(Expr::Negate(Box::new(Expr::Name(Box::from("x")))))),
+    });
+    validate_correct_serde(Tree {
+        root: chain(50),
+        expr: Expr::Number(7),
+    });
+}
+
+#[test]
+fn check_max_depth() {
+    let tree = Tree {
+        root: chain(100),
+        expr: Expr::Number(1),
+    };
+    let mut storage = Storage::default();
+    tree.serialize_to(&mut storage, Config::default()).unwrap();
+    // the default limit (64) is too small for this tree
+    assert_eq!(
+        Tree::deserialize_from_with_config(&storage, Config::default()),
+        Err(Error::MaxDepthExceeded(Config::DEFAULT_MAX_DEPTH))
+    );
+    assert_eq!(
+        Tree::deserialize_from(&storage),
+        Err(Error::MaxDepthExceeded(Config::DEFAULT_MAX_DEPTH))
+    );
+    let config = ConfigBuilder::new().max_depth(100).build();
+    assert_eq!(Tree::deserialize_from_with_config(&storage, config), Ok(tree));
+
+    let expr = Tree {
+        root: chain(1),
+        expr: Expr::Negate(Box::new(Expr::Negate(Box::new(Expr::Number(1))))),
+    };
+    expr.serialize_to(&mut storage, Config::default()).unwrap();
+    // every variant (and structure) is a nesting level
+    let config = ConfigBuilder::new().max_depth(2).build();
+    assert_eq!(
+        Tree::deserialize_from_with_config(&storage, config),
+        Err(Error::MaxDepthExceeded(2))
+    );
+    let config = ConfigBuilder::new().max_depth(3).build();
+    assert_eq!(Tree::deserialize_from_with_config(&storage, config), Ok(expr));
+}
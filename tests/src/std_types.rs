@@ -0,0 +1,249 @@
+use crate::*;
+use flat_message::*;
+use std::num::{NonZeroI16, NonZeroI64, NonZeroU128, NonZeroU32, NonZeroU64, NonZeroU8};
+use std::time::Duration;
+
+#[test]
+fn check_char() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        c1: char,
+        c2: Option<char>,
+        c4: Vec<char>,
+        c5: Option<Vec<char>>,
+    }
+    validate_correct_serde(Test {
+        c1: 'a',
+        c2: Some('😀'),
+        c4: vec!['F', 'l', 'a', 't'],
+        c5: None,
+    });
+}
+
+#[test]
+fn check_slices() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    struct Test<'a> {
+        chars: &'a [char],
+        ids: &'a [NonZeroU32],
+        sizes: &'a [usize],
+        offsets: &'a [isize],
+    }
+    let ids = [NonZeroU32::new(1).unwrap(), NonZeroU32::new(2).unwrap()];
+    let t = Test {
+        chars: &['x', 'ț', '\u{10FFFF}'],
+        ids: &ids,
+        sizes: &[10, 20, usize::MAX],
+        offsets: &[-1, isize::MAX],
+    };
+    let mut storage = Storage::default();
+    t.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(Test::deserialize_from(&storage).unwrap(), t);
+}
+
+#[test]
+fn check_invalid_char() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Chars {
+        value: char,
+        values: Vec<char>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Numbers {
+        value: u32,
+        values: Vec<u32>,
+    }
+    let mut storage = Storage::default();
+    // surrogates are not valid chars
+    Numbers {
+        value: 0xD800,
+        values: vec![65],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert!(Chars::deserialize_from(&storage).is_err());
+    Numbers {
+        value: 65,
+        values: vec![66, 0x110000],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert!(Chars::deserialize_from(&storage).is_err());
+}
+
+#[test]
+fn check_nonzero() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        v1: NonZeroU8,
+        v2: NonZeroI16,
+        v3: NonZeroU32,
+        v4: Option<NonZeroU64>,
+        v5: NonZeroU128,
+        v7: Vec<NonZeroI64>,
+        v8: std::num::NonZero<u32>,
+    }
+    validate_correct_serde(Test {
+        v1: NonZeroU8::new(1).unwrap(),
+        v2: NonZeroI16::new(-2).unwrap(),
+        v3: NonZeroU32::new(u32::MAX).unwrap(),
+        v4: NonZeroU64::new(123456789),
+        v5: NonZeroU128::new(u128::MAX).unwrap(),
+        v7: vec![NonZeroI64::new(i64::MIN).unwrap(), NonZeroI64::new(i64::MAX).unwrap()],
+        v8: NonZeroU32::new(5).unwrap(),
+    });
+}
+
+#[test]
+fn check_nonzero_validation() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Ids {
+        id: NonZeroU32,
+        list: Vec<NonZeroU64>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct RawIds {
+        id: u32,
+        list: Vec<u64>,
+    }
+    // non-zero values are interchangeable with the integers they wrap
+    let mut storage = Storage::default();
+    RawIds {
+        id: 10,
+        list: vec![1, 2, 3],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    let ids = Ids::deserialize_from(&storage).unwrap();
+    assert_eq!(ids.id.get(), 10);
+    assert_eq!(ids.list.iter().map(|v| v.get()).collect::<Vec<_>>(), vec![1, 2, 3]);
+
+    RawIds {
+        id: 0,
+        list: vec![1, 2, 3],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert!(Ids::deserialize_from(&storage).is_err());
+    RawIds {
+        id: 1,
+        list: vec![1, 0, 3],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert!(Ids::deserialize_from(&storage).is_err());
+}
+
+#[test]
+fn check_usize_isize() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        v1: usize,
+        v2: isize,
+        v3: Option<usize>,
+        v4: Vec<usize>,
+        v5: Vec<isize>,
+    }
+    validate_correct_serde(Test {
+        v1: usize::MAX,
+        v2: isize::MIN,
+        v3: Some(12345),
+        v4: vec![1, 2, 3, usize::MAX],
+        v5: vec![-1, 0, 1],
+    });
+}
+
+#[test]
+fn check_usize_isize_wire_format() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Sizes {
+        len: usize,
+        delta: isize,
+        lens: Vec<usize>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Fixed {
+        len: u64,
+        delta: i64,
+        lens: Vec<u64>,
+    }
+    // usize / isize are stored as u64 / i64 on every platform
+    let mut storage = Storage::default();
+    Sizes {
+        len: 100,
+        delta: -100,
+        lens: vec![1, 2],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert_eq!(
+        Fixed::deserialize_from(&storage).unwrap(),
+        Fixed {
+            len: 100,
+            delta: -100,
+            lens: vec![1, 2]
+        }
+    );
+    Fixed {
+        len: 5,
+        delta: 6,
+        lens: vec![7],
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert_eq!(
+        Sizes::deserialize_from(&storage).unwrap(),
+        Sizes {
+            len: 5,
+            delta: 6,
+            lens: vec![7]
+        }
+    );
+}
+
+#[test]
+fn check_duration() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        timeout: Duration,
+        retry: Option<std::time::Duration>,
+        intervals: Vec<Duration>,
+        value: u8,
+    }
+    validate_correct_serde(Test {
+        timeout: Duration::from_millis(1500),
+        retry: Some(Duration::new(u64::MAX, 999_999_999)),
+        intervals: vec![Duration::ZERO, Duration::from_nanos(1), Duration::from_secs(3600)],
+        value: 1,
+    });
+}
+
+#[test]
+fn check_invalid_duration() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        timeout: Duration,
+    }
+    let mut storage = Storage::default();
+    Test {
+        timeout: Duration::new(1, 5),
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    // the nanoseconds are stored right after the seconds (header + 8 bytes)
+    let mut buffer = storage.as_slice().to_vec();
+    buffer[16..20].copy_from_slice(&1_000_000_000u32.to_le_bytes());
+    let storage = Storage::from_buffer(&buffer);
+    assert!(Test::deserialize_from(&storage).is_err());
+}
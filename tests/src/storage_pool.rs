@@ -0,0 +1,159 @@
+use flat_message::*;
+
+#[derive(Debug, PartialEq, Eq, FlatMessagePacked)]
+struct Data {
+    data: Vec<u32>,
+    idx: u8,
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessagePacked)]
+struct Point {
+    x: i8,
+    #[flat_message_item(kind = packed, align = 4)]
+    d: Data,
+}
+
+#[derive(FlatMessageVariant, Debug, PartialEq, Eq)]
+enum Value {
+    Byte(u8),
+    Vector(Vec<u128>),
+}
+
+#[derive(Debug, PartialEq, Eq, FlatMessage)]
+struct Message {
+    a: u8,
+    big: u128,
+    small: Vec<u16>,
+    nullable: Vec<Option<u64>>,
+    nested: Vec<Vec<u128>>,
+    tuple: (u8, u64, u16),
+    #[flat_message_item(kind = packed, align = 4)]
+    point: Point,
+    #[flat_message_item(kind = variant, align = 16)]
+    value: Value,
+    text: String,
+}
+
+fn message() -> Message {
+    Message {
+        a: 1,
+        big: u128::MAX - 5,
+        small: vec![1, 2, 3],
+        nullable: vec![Some(1), None, None, Some(4)],
+        nested: vec![vec![1], vec![], vec![2, 3]],
+        tuple: (7, 8, 9),
+        point: Point {
+            x: -1,
+            d: Data { data: vec![10, 20], idx: 3 },
+        },
+        value: Value::Byte(5),
+        text: "hello".to_string(),
+    }
+}
+
+/// A buffer that was used before (every byte is set to 0xAA).
+fn dirty_storage() -> Storage {
+    let mut storage = Storage::default();
+    storage.resize_zero(4096);
+    storage.as_mut_slice().fill(0xAA);
+    storage
+}
+
+#[test]
+fn check_reused_storage_has_same_bytes() {
+    let mut fresh = Storage::default();
+    message().serialize_to(&mut fresh, Config::default()).unwrap();
+
+    let mut storage = dirty_storage();
+    message().serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(storage.as_slice(), fresh.as_slice());
+    assert_eq!(Message::deserialize_from(&storage).unwrap(), message());
+
+    // a smaller message written over a larger one
+    let small = Message {
+        value: Value::Vector(vec![1, 2]),
+        ..message()
+    };
+    let mut fresh = Storage::default();
+    small.serialize_to(&mut fresh, Config::default()).unwrap();
+    let mut storage = dirty_storage();
+    small.serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(storage.as_slice(), fresh.as_slice());
+}
+
+#[test]
+fn check_resize_zero() {
+    let mut storage = dirty_storage();
+    storage.resize(10);
+    storage.resize_zero(100);
+    assert!(storage.as_slice()[..10].iter().all(|b| *b == 0xAA));
+    assert!(storage.as_slice()[10..].iter().all(|b| *b == 0));
+}
+
+#[test]
+fn check_pool_reuses_storage() {
+    let pool = StoragePool::new(2, 1024 * 1024);
+    assert!(pool.is_empty());
+    let mut expected = Storage::default();
+    message().serialize_to(&mut expected, Config::default()).unwrap();
+    {
+        let mut storage = pool.get();
+        assert_eq!(storage.len(), 0);
+        message().serialize_to(&mut storage, Config::default()).unwrap();
+        assert_eq!(storage.as_slice(), expected.as_slice());
+    }
+    assert_eq!(pool.len(), 1);
+    let mut storage = pool.get();
+    assert!(pool.is_empty());
+    // the buffer from the pool keeps its capacity (and its previous content)
+    assert!(storage.capacity() >= expected.len());
+    assert_eq!(storage.len(), 0);
+    message().serialize_to(&mut storage, Config::default()).unwrap();
+    assert_eq!(storage.as_slice(), expected.as_slice());
+    // buffers taken out of the pool are not returned
+    let inner: Storage = storage.into_inner();
+    assert_eq!(inner, expected);
+    assert!(pool.is_empty());
+}
+
+#[test]
+fn check_pool_bounds() {
+    let pool = StoragePool::new(2, 1024);
+    let buffers: Vec<_> = (0..3).map(|_| pool.get()).collect();
+    drop(buffers);
+    // at most 2 buffers are kept
+    assert_eq!(pool.len(), 2);
+    pool.clear();
+    assert!(pool.is_empty());
+
+    // large buffers are released
+    let mut storage = pool.get();
+    storage.resize_zero(4096);
+    drop(storage);
+    assert!(pool.is_empty());
+    let mut storage = pool.get();
+    storage.resize_zero(100);
+    drop(storage);
+    assert_eq!(pool.len(), 1);
+    assert_eq!(pool.max_pooled(), 2);
+    assert_eq!(pool.max_capacity(), 1024);
+}
+
+#[test]
+fn check_pool_from_multiple_threads() {
+    static POOL: StoragePool = StoragePool::new(4, 1024 * 1024);
+    std::thread::scope(|s| {
+        for _ in 0..8 {
+            s.spawn(|| {
+                for i in 0..100u8 {
+                    let mut storage = POOL.get();
+                    let m = Message { a: i, ..message() };
+                    m.serialize_to(&mut storage, Config::default()).unwrap();
+                    assert_eq!(Message::deserialize_from(&storage).unwrap(), m);
+                }
+            });
+        }
+    });
+    assert!(POOL.len() <= 4);
+    assert!(!POOL.is_empty());
+}
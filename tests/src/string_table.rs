@@ -0,0 +1,131 @@
+use crate::*;
+use flat_message::*;
+
+#[test]
+fn check_string_table() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false, string_table: true)]
+    struct Trade {
+        currency: String,
+        settlement_currency: String,
+        venue: Option<String>,
+        missing: Option<String>,
+        tags: Vec<String>,
+        empty: Vec<String>,
+        optional_tags: Option<Vec<String>>,
+        quantity: u32,
+    }
+    validate_correct_serde(Trade {
+        currency: "EUR".to_string(),
+        settlement_currency: "EUR".to_string(),
+        venue: Some("XETRA".to_string()),
+        missing: None,
+        tags: vec!["EUR".to_string(), "fx".to_string(), "fx".to_string(), String::new()],
+        empty: Vec::new(),
+        optional_tags: Some(vec!["XETRA".to_string()]),
+        quantity: 100,
+    });
+}
+
+#[test]
+fn check_string_table_zero_copy() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false, string_table: true)]
+    struct Request<'a> {
+        host: &'a str,
+        origin: Option<&'a str>,
+        headers: Vec<&'a str>,
+    }
+    let host = String::from("example.com");
+    let r = Request {
+        host: &host,
+        origin: Some("example.com"),
+        headers: vec!["gzip", "example.com", "gzip"],
+    };
+    let mut s = Storage::default();
+    r.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Request::deserialize_from(&s).unwrap();
+    assert_eq!(d, r);
+    // all the references point to the same string from the buffer
+    let range = s.as_slice().as_ptr_range();
+    assert!(range.contains(&d.host.as_ptr()));
+    assert_eq!(d.host.as_ptr(), d.origin.unwrap().as_ptr());
+    assert_eq!(d.host.as_ptr(), d.headers[1].as_ptr());
+    assert_eq!(d.headers[0].as_ptr(), d.headers[2].as_ptr());
+    let d = unsafe { Request::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(d, r);
+}
+
+#[test]
+fn check_string_table_size() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Inline {
+        hosts: Vec<String>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false, string_table: true)]
+    struct Shared {
+        hosts: Vec<String>,
+    }
+    let hosts: Vec<String> = (0..1000).map(|i| format!("server-{}.example.com", i % 4)).collect();
+    let mut inline = Storage::default();
+    Inline { hosts: hosts.clone() }.serialize_to(&mut inline, Config::default()).unwrap();
+    let mut shared = Storage::default();
+    Shared { hosts: hosts.clone() }.serialize_to(&mut shared, Config::default()).unwrap();
+    assert!(inline.len() > 20000);
+    assert!(shared.len() < 1200);
+    assert_eq!(Shared::deserialize_from(&shared).unwrap().hosts, hosts);
+}
+
+#[test]
+fn check_string_table_not_compatible_with_inline_strings() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Inline {
+        name: String,
+        value: u32,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false, string_table: true)]
+    struct Shared {
+        name: String,
+        value: u32,
+    }
+    let mut s = Storage::default();
+    Inline { name: "a".to_string(), value: 1 }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Shared::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Shared { name: "a".to_string(), value: 1 }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Inline::deserialize_from(&s).is_err());
+    // fields that are not strings are not affected by the string table
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct ValueOnly {
+        value: u32,
+    }
+    assert_eq!(ValueOnly::deserialize_from(&s).unwrap(), ValueOnly { value: 1 });
+}
+
+#[test]
+fn check_string_table_invalid_index() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false, string_table: true)]
+    struct Test {
+        name: String,
+    }
+    let mut s = Storage::default();
+    Test { name: "abc".to_string() }.serialize_to(&mut s, Config::default()).unwrap();
+    let mut buf = s.as_slice().to_vec();
+    // header (8 bytes) + string table (count, length, "abc") + index of the name
+    assert_eq!(&buf[8..14], &[1, 3, b'a', b'b', b'c', 0]);
+    buf[13] = 1;
+    let s = Storage::from_buffer(&buf);
+    // the structure of the message is still valid, but the index is out of bounds
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    assert_eq!(
+        Test::deserialize_from(&s),
+        Err(Error::FailToDeserialize(flat_message::name!("name").value & 0xFFFFFF00 | DataFormat::StringRef as u32))
+    );
+}
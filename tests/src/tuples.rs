@@ -0,0 +1,93 @@
+use crate::*;
+use flat_message::*;
+
+#[test]
+fn check_tuples() {
+    #[derive(Debug, PartialEq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        pair: (u32, String),
+        point: (f32, f32, f32),
+        mixed: (u8, u64, u16),
+        single: (i128,),
+        nested: ((u8, u32), [u16; 2]),
+        eight: (u8, u16, u32, u64, i8, i16, i32, bool),
+        optional: Option<(u32, u32)>,
+        missing: Option<(u32, u32)>,
+    }
+    let t = Test {
+        pair: (1, "one".to_string()),
+        point: (1.5, 2.5, -3.5),
+        mixed: (1, u64::MAX, 3),
+        single: (-5,),
+        nested: ((1, 2), [3, 4]),
+        eight: (1, 2, 3, 4, -5, -6, -7, true),
+        optional: Some((10, 20)),
+        missing: None,
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_tuples_with_references() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test<'a> {
+        entry: (&'a str, u32),
+    }
+    let t = Test { entry: ("key", 10) };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    assert!(s.as_slice().as_ptr_range().contains(&d.entry.0.as_ptr()));
+}
+
+#[test]
+fn check_tuples_of_custom_types() {
+    #[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+    #[repr(u8)]
+    enum Kind {
+        A = 1,
+        B = 2,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        // the alignment can not be deduced for types that are not known (enums, structs, ...)
+        #[flat_message_item(kind = struct, align = 4)]
+        value: (Kind, u32),
+    }
+    validate_correct_serde(Test {
+        value: (Kind::B, 100),
+    });
+    let _ = Kind::A;
+}
+
+#[test]
+fn check_tuple_type_mismatch() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct V1 {
+        value: (u32, u32),
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct V2 {
+        value: (u32, String),
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct V3 {
+        value: (u32,),
+    }
+    let mut s = Storage::default();
+    V1 { value: (1, 2) }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(V2::deserialize_from(&s).is_err());
+    // extra elements are ignored (the same way new fields are ignored for structs)
+    assert_eq!(V3::deserialize_from(&s).unwrap(), V3 { value: (1,) });
+}
@@ -0,0 +1,80 @@
+use crate::*;
+use flat_message::*;
+use uuid::Uuid;
+
+#[test]
+fn check_uuid() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        id: Uuid,
+        parent: Option<uuid::Uuid>,
+        children: Vec<Uuid>,
+        value: u8,
+    }
+    validate_correct_serde(Test {
+        id: Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8),
+        parent: Some(Uuid::max()),
+        children: vec![Uuid::nil(), Uuid::from_u128(1), Uuid::from_u128(u128::MAX - 1)],
+        value: 1,
+    });
+}
+
+#[test]
+fn check_uuid_slice() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    struct Test<'a> {
+        value: u8,
+        ids: &'a [Uuid],
+        empty: &'a [Uuid],
+    }
+    let ids: Vec<Uuid> = (0..300u128).map(Uuid::from_u128).collect();
+    let t = Test {
+        value: 7,
+        ids: &ids,
+        empty: &[],
+    };
+    let mut storage = Storage::default();
+    t.serialize_to(&mut storage, Config::default()).unwrap();
+    let ds = Test::deserialize_from(&storage).unwrap();
+    assert_eq!(ds, t);
+}
+
+#[test]
+fn check_uuid_byte_order() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        id: Uuid,
+    }
+    let id = Uuid::from_u128(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff);
+    let mut storage = Storage::default();
+    Test { id }.serialize_to(&mut storage, Config::default()).unwrap();
+    // the UUID is stored right after the header, in its canonical (big-endian) form
+    assert_eq!(&storage.as_slice()[8..24], id.as_bytes());
+}
+
+#[test]
+fn check_uuid_not_fix_array() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithUuid {
+        id: Uuid,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct WithBytes {
+        id: [u8; 16],
+    }
+    let mut storage = Storage::default();
+    WithBytes { id: [1; 16] }
+        .serialize_to(&mut storage, Config::default())
+        .unwrap();
+    assert!(WithUuid::deserialize_from(&storage).is_err());
+    WithUuid {
+        id: Uuid::from_bytes([1; 16]),
+    }
+    .serialize_to(&mut storage, Config::default())
+    .unwrap();
+    assert!(WithBytes::deserialize_from(&storage).is_err());
+}
@@ -0,0 +1,287 @@
+use flat_message::*;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+#[derive(Copy, Clone, FlatMessageEnum, PartialEq, Eq, Debug)]
+#[repr(u16)]
+enum Color {
+    Red = 1,
+    Green = 2,
+    Blue = 3,
+}
+
+#[derive(FlatMessageStruct, Debug, PartialEq, Eq)]
+struct Inner {
+    a: u8,
+    b: u64,
+    name: String,
+}
+
+#[derive(FlatMessageVariant, Debug, PartialEq, Eq)]
+enum Value {
+    Byte(u8),
+    Text(String),
+    List(Vec<u32>),
+    Empty,
+}
+
+#[derive(FlatMessage, Debug, PartialEq)]
+#[flat_message_options(checksum = true)]
+struct Everything {
+    a: u8,
+    b: i64,
+    c: f32,
+    name: String,
+    tags: Vec<String>,
+    values: Vec<u32>,
+    small: Vec<u16>,
+    big: Vec<u128>,
+    flags: Vec<bool>,
+    ip: IpAddr,
+    ip4: Ipv4Addr,
+    ip6: Ipv6Addr,
+    buf: [u8; 5],
+    opt: Option<u32>,
+    letter: char,
+    letters: Vec<char>,
+    timeout: Duration,
+    timeouts: Vec<Duration>,
+    uuid: uuid::Uuid,
+    uuids: Vec<uuid::Uuid>,
+    #[flat_message_item(repr = u16, kind = enum)]
+    color: Color,
+    #[flat_message_item(repr = u16, kind = enum)]
+    colors: Vec<Color>,
+    #[flat_message_item(kind = struct, align = 4)]
+    inner: Inner,
+    #[flat_message_item(kind = variant, align = 4)]
+    value: Value,
+    #[flat_message_item(kind = variant, align = 4)]
+    empty: Value,
+    timestamp: Timestamp,
+    id: UniqueID,
+}
+
+fn everything() -> Everything {
+    Everything {
+        a: 1,
+        b: -2,
+        c: 3.5,
+        name: "FlatMessage".to_string(),
+        tags: vec!["a".to_string(), "bc".to_string(), String::new()],
+        values: vec![1, 2, 3, 4],
+        small: vec![5, 6],
+        big: vec![u128::MAX],
+        flags: vec![true, false, true],
+        ip: IpAddr::V6(Ipv6Addr::new(1, 2, 3, 4, 5, 6, 7, 8)),
+        ip4: Ipv4Addr::new(127, 0, 0, 1),
+        ip6: Ipv6Addr::LOCALHOST,
+        buf: [1, 2, 3, 4, 5],
+        opt: None,
+        letter: 'x',
+        letters: vec!['a', 'b'],
+        timeout: Duration::from_millis(10),
+        timeouts: vec![Duration::from_secs(1), Duration::from_nanos(5)],
+        uuid: uuid::Uuid::from_u128(1),
+        uuids: vec![uuid::Uuid::nil(), uuid::Uuid::max()],
+        color: Color::Green,
+        colors: vec![Color::Red, Color::Blue],
+        inner: Inner {
+            a: 10,
+            b: 20,
+            name: "inner".to_string(),
+        },
+        value: Value::List(vec![7, 8, 9]),
+        empty: Value::Empty,
+        timestamp: Timestamp::with_value(123),
+        id: UniqueID::with_value(456),
+    }
+}
+
+fn serialize<'a, T: FlatMessage<'a>>(obj: &T) -> Vec<u8> {
+    let mut storage = Storage::default();
+    obj.serialize_to(&mut storage, Config::default()).unwrap();
+    storage.as_slice().to_vec()
+}
+
+#[derive(FlatMessage)]
+#[flat_message_options(store_name = false)]
+struct TwoFields {
+    x: u32,
+    y: u32,
+}
+
+// layout for TwoFields: header (8) + x (4) + y (4) + hash table (2 * 4) + offset table (2 * 1)
+const TWO_FIELDS_HASH_TABLE: usize = 16;
+const TWO_FIELDS_OFFSET_TABLE: usize = 24;
+
+#[test]
+fn check_valid_buffers() {
+    let buf = serialize(&everything());
+    assert_eq!(validate(&buf), Ok(()));
+    assert_eq!(validate(&serialize(&TwoFields { x: 1, y: 2 })), Ok(()));
+}
+
+#[test]
+fn check_valid_large_buffer() {
+    #[derive(FlatMessage)]
+    struct Large {
+        data: Vec<u64>,
+        text: String,
+    }
+    let buf = serialize(&Large {
+        data: (0..20000).collect(),
+        text: "x".repeat(70000),
+    });
+    assert_eq!(validate(&buf), Ok(()));
+}
+
+#[test]
+fn check_truncated_buffers() {
+    let buf = serialize(&everything());
+    assert_eq!(validate(&buf[..4]), Err(Error::InvalidHeaderLength(4)));
+    for len in 0..buf.len() {
+        assert!(validate(&buf[..len]).is_err());
+    }
+}
+
+#[test]
+fn check_header_and_flags() {
+    let mut buf = serialize(&TwoFields { x: 1, y: 2 });
+    buf[0] = b'X';
+    assert_eq!(validate(&buf), Err(Error::InvalidMagic));
+
+    // offset size 3 means 64-bit offsets (the offset table no longer fits in the buffer)
+    let mut buf = serialize(&TwoFields { x: 1, y: 2 });
+    buf[7] |= 0b0000_0011;
+    assert!(matches!(validate(&buf), Err(Error::InvalidSizeToStoreFieldsTable(_))));
+
+    let mut buf = serialize(&TwoFields { x: 1, y: 2 });
+    buf[7] |= 0b1000_0000;
+    assert_eq!(validate(&buf), Err(Error::InvalidFlags(buf[7])));
+}
+
+#[test]
+fn check_checksum() {
+    let mut buf = serialize(&everything());
+    buf[9] ^= 0xFF;
+    assert!(matches!(validate(&buf), Err(Error::InvalidChecksum(_))));
+}
+
+#[test]
+fn check_hash_table_order() {
+    let buf = serialize(&TwoFields { x: 1, y: 2 });
+    let first = u32::from_le_bytes(buf[TWO_FIELDS_HASH_TABLE..TWO_FIELDS_HASH_TABLE + 4].try_into().unwrap());
+    let second = u32::from_le_bytes(buf[TWO_FIELDS_HASH_TABLE + 4..TWO_FIELDS_HASH_TABLE + 8].try_into().unwrap());
+
+    let mut swapped = buf.clone();
+    swapped[TWO_FIELDS_HASH_TABLE..TWO_FIELDS_HASH_TABLE + 4].copy_from_slice(&second.to_le_bytes());
+    swapped[TWO_FIELDS_HASH_TABLE + 4..TWO_FIELDS_HASH_TABLE + 8].copy_from_slice(&first.to_le_bytes());
+    assert_eq!(validate(&swapped), Err(Error::UnsortedHashTable(first)));
+
+    let mut duplicated = buf.clone();
+    duplicated[TWO_FIELDS_HASH_TABLE + 4..TWO_FIELDS_HASH_TABLE + 8].copy_from_slice(&first.to_le_bytes());
+    assert_eq!(validate(&duplicated), Err(Error::DuplicateFieldHash(first)));
+}
+
+#[test]
+fn check_offsets() {
+    let buf = serialize(&TwoFields { x: 1, y: 2 });
+
+    let mut invalid = buf.clone();
+    invalid[TWO_FIELDS_OFFSET_TABLE] = 3;
+    assert_eq!(validate(&invalid), Err(Error::InvalidFieldOffset((3, 16))));
+
+    let mut invalid = buf.clone();
+    invalid[TWO_FIELDS_OFFSET_TABLE] = 16;
+    assert_eq!(validate(&invalid), Err(Error::InvalidFieldOffset((16, 16))));
+
+    // a u32 value that starts at offset 13 does not fit in the data region
+    let mut invalid = buf.clone();
+    invalid[TWO_FIELDS_OFFSET_TABLE + 1] = 13;
+    assert!(matches!(validate(&invalid), Err(Error::InvalidFieldSize(_))));
+}
+
+#[test]
+fn check_size_prefixes() {
+    #[derive(FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct Text {
+        text: String,
+    }
+    let buf = serialize(&Text {
+        text: "abc".to_string(),
+    });
+    assert_eq!(validate(&buf), Ok(()));
+    // the string length is stored right after the header
+    let mut invalid = buf.clone();
+    invalid[8] = 200;
+    assert!(matches!(validate(&invalid), Err(Error::InvalidFieldSize(_))));
+
+    #[derive(FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct Numbers {
+        values: Vec<u32>,
+    }
+    let buf = serialize(&Numbers {
+        values: vec![1, 2, 3],
+    });
+    assert_eq!(validate(&buf), Ok(()));
+    let mut invalid = buf.clone();
+    invalid[8..12].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(validate(&invalid), Err(Error::InvalidFieldSize(_))));
+}
+
+#[test]
+fn check_unaligned_slice() {
+    #[derive(FlatMessage)]
+    #[flat_message_options(store_name = false)]
+    struct Numbers {
+        values: Vec<u32>,
+    }
+    let mut buf = serialize(&Numbers { values: vec![] });
+    // the offset table is the last byte of the buffer
+    let last = buf.len() - 1;
+    buf[last] = 9;
+    assert!(matches!(validate(&buf), Err(Error::UnalignedField(_))));
+}
+
+#[test]
+fn check_unaligned_slice_reference() {
+    let values: &[u32] = &[1, 2, 3];
+    let mut storage = Storage::default();
+    storage.resize_zero(SerDeSlice::size(values) + 1);
+    let end = unsafe { SerDeSlice::write(values, storage.as_mut_slice().as_mut_ptr(), 1) };
+    assert_eq!(end, storage.len());
+    // the values start at offset 5 (after the size), so they can not be referenced
+    let ds: Option<&[u32]> = SerDeSlice::from_buffer(storage.as_slice(), 1);
+    assert!(ds.is_none());
+    let ds: Option<&[char]> = SerDeSlice::from_buffer(storage.as_slice(), 1);
+    assert!(ds.is_none());
+    let ds: Option<Vec<u32>> = SerDeVec::from_buffer(storage.as_slice(), 1);
+    assert!(ds.is_none());
+}
+
+#[test]
+fn check_unaligned_hash_table() {
+    #[derive(FlatMessage, Debug)]
+    #[flat_message_options(store_name = false)]
+    struct Optional {
+        value: Option<u32>,
+    }
+    // one field (offset 0 => missing) with the hash table at offset 11
+    let buf = [70, 76, 77, 1, 1, 0, 0, 0, 0, 0, 17, 224, 0, 42, 0, 0];
+    assert_eq!(validate(&buf), Err(Error::UnalignedHashTable(11)));
+    // the derive reads the hash table without assuming it is aligned
+    let storage = Storage::from_buffer(&buf);
+    assert!(Optional::deserialize_from(&storage).is_ok());
+    assert!(TwoFields::deserialize_from(&storage).is_err());
+}
+
+#[test]
+fn check_unknown_data_format() {
+    let mut buf = serialize(&TwoFields { x: 1, y: 2 });
+    // the low byte of the hash holds the data format - 0x7F is not a valid one
+    buf[TWO_FIELDS_HASH_TABLE + 4] = 0x7F;
+    assert!(matches!(validate(&buf), Err(Error::UnknownDataFormat(_))));
+}
@@ -0,0 +1,188 @@
+use crate::*;
+use flat_message::*;
+
+#[test]
+fn check_varint_values() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        #[flat_message_item(encoding = "varint")]
+        a: u8,
+        #[flat_message_item(encoding = "varint")]
+        b: u16,
+        #[flat_message_item(encoding = "varint")]
+        c: u32,
+        #[flat_message_item(encoding = "varint")]
+        d: u64,
+        #[flat_message_item(encoding = "varint")]
+        e: u128,
+        #[flat_message_item(encoding = "varint")]
+        f: usize,
+        #[flat_message_item(encoding = "varint")]
+        g: i8,
+        #[flat_message_item(encoding = "varint")]
+        h: i16,
+        #[flat_message_item(encoding = "varint")]
+        i: i32,
+        #[flat_message_item(encoding = "varint")]
+        j: i64,
+        #[flat_message_item(encoding = "varint")]
+        k: i128,
+        #[flat_message_item(encoding = "varint")]
+        l: isize,
+        #[flat_message_item(encoding = "varint")]
+        optional: Option<u32>,
+        #[flat_message_item(encoding = "varint")]
+        missing: Option<i64>,
+    }
+    validate_correct_serde(Test {
+        a: u8::MAX,
+        b: 300,
+        c: 0,
+        d: u64::MAX,
+        e: u128::MAX,
+        f: 127,
+        g: i8::MIN,
+        h: -1,
+        i: i32::MAX,
+        j: i64::MIN,
+        k: i128::MIN,
+        l: -64,
+        optional: Some(128),
+        missing: None,
+    });
+}
+
+#[test]
+fn check_varint_vectors() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        #[flat_message_item(encoding = "varint")]
+        counters: Vec<u64>,
+        #[flat_message_item(encoding = "varint")]
+        deltas: Vec<i32>,
+        #[flat_message_item(encoding = "varint")]
+        big: Vec<i128>,
+        #[flat_message_item(encoding = "varint")]
+        empty: Vec<u16>,
+        #[flat_message_item(encoding = "varint")]
+        optional: Option<Vec<u32>>,
+    }
+    let t = Test {
+        counters: vec![0, 1, 127, 128, 16383, 16384, u64::MAX],
+        deltas: vec![-1, 1, -64, 64, i32::MIN, i32::MAX],
+        big: vec![i128::MAX, 0, -2],
+        empty: Vec::new(),
+        optional: Some(vec![5; 300]),
+    };
+    let mut s = Storage::default();
+    t.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(validate(s.as_slice()), Ok(()));
+    let d = Test::deserialize_from(&s).unwrap();
+    assert_eq!(d, t);
+    let d = unsafe { Test::deserialize_from_unchecked(&s).unwrap() };
+    assert_eq!(d, t);
+}
+
+#[test]
+fn check_varint_size() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Fixed {
+        counters: Vec<u64>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Compact {
+        #[flat_message_item(encoding = "varint")]
+        counters: Vec<u64>,
+    }
+    let counters: Vec<u64> = (0..1000).map(|i| i % 100).collect();
+    let mut fixed = Storage::default();
+    Fixed { counters: counters.clone() }
+        .serialize_to(&mut fixed, Config::default())
+        .unwrap();
+    let mut compact = Storage::default();
+    Compact { counters }.serialize_to(&mut compact, Config::default()).unwrap();
+    assert!(fixed.len() > 8000);
+    assert!(compact.len() < 1100);
+}
+
+#[test]
+fn check_varint_not_compatible_with_fixed_encoding() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Fixed {
+        value: u32,
+        list: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Compact {
+        #[flat_message_item(encoding = "varint")]
+        value: u32,
+        #[flat_message_item(encoding = "varint")]
+        list: Vec<u32>,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Signed {
+        #[flat_message_item(encoding = "varint")]
+        value: i32,
+        #[flat_message_item(encoding = "varint")]
+        list: Vec<i32>,
+    }
+    let mut s = Storage::default();
+    Fixed { value: 1, list: vec![1] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Compact::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Compact { value: 1, list: vec![1] }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Fixed::deserialize_from(&s).is_err());
+    // signed values are zigzag encoded (a different format)
+    assert!(Signed::deserialize_from(&s).is_err());
+}
+
+#[test]
+fn check_varint_different_sizes() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Wide {
+        #[flat_message_item(encoding = "varint")]
+        value: i64,
+    }
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Narrow {
+        #[flat_message_item(encoding = "varint")]
+        value: i16,
+    }
+    let mut s = Storage::default();
+    Wide { value: -1000 }.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(Narrow::deserialize_from(&s).unwrap(), Narrow { value: -1000 });
+    let mut s = Storage::default();
+    Wide { value: 100_000 }.serialize_to(&mut s, Config::default()).unwrap();
+    assert!(Narrow::deserialize_from(&s).is_err());
+    let mut s = Storage::default();
+    Narrow { value: i16::MIN }.serialize_to(&mut s, Config::default()).unwrap();
+    assert_eq!(Wide::deserialize_from(&s).unwrap(), Wide { value: i16::MIN as i64 });
+}
+
+#[test]
+fn check_varint_invalid_buffer() {
+    #[derive(Debug, PartialEq, Eq, FlatMessage)]
+    #[flat_message_options(store_name: false)]
+    struct Test {
+        #[flat_message_item(encoding = "varint")]
+        value: u32,
+    }
+    let mut s = Storage::default();
+    Test { value: 0 }.serialize_to(&mut s, Config::default()).unwrap();
+    let mut buf = s.as_slice().to_vec();
+    // header (8 bytes) + value (1 byte) + padding - replace the value with a longer (non canonical) encoding of 0
+    assert_eq!(&buf[8..10], &[0, 0]);
+    buf[8] = 0x80;
+    let s = Storage::from_buffer(&buf);
+    assert!(validate(s.as_slice()).is_err());
+    assert!(Test::deserialize_from(&s).is_err());
+}